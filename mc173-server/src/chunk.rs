@@ -58,6 +58,14 @@ impl ChunkTrackers {
         }
     }
 
+    /// Remove the tracker of the given chunk, this should be called when the chunk is
+    /// unloaded from the world, any scheduled save is also cancelled.
+    pub fn remove(&mut self, cx: i32, cz: i32) {
+        if self.inner.remove(&(cx, cz)).is_some() {
+            self.scheduled_saves.retain(|&(scx, scz, _)| (scx, scz) != (cx, cz));
+        }
+    }
+
     /// Internal method to schedule a save in the future at given timestamp, this will
     /// be sorted into the scheduled save queue.
     fn schedule_save(&mut self, cx: i32, cz: i32, instant: Instant) {
@@ -309,16 +317,8 @@ impl ChunkTracker {
 /// and "to" are inclusive but might be modified to include more blocks if ths reduces
 /// computation.
 pub fn new_chunk_data_packet(chunk: &Chunk, mut from: IVec3, mut size: IVec3) -> proto::ChunkDataPacket {
-
-    use flate2::write::ZlibEncoder;
-    use flate2::Compression;
-    
-    debug_assert!(size.x != 0 && size.y != 0 && size.z != 0);
     
-    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::fast());
-    chunk.write_data(&mut encoder, &mut from, &mut size).unwrap();
-
-    debug_assert!(size.x != 0 && size.y != 0 && size.z != 0);
+    let data = proto::encode_chunk_data(chunk, &mut from, &mut size);
     
     proto::ChunkDataPacket {
         x: from.x,
@@ -327,7 +327,7 @@ pub fn new_chunk_data_packet(chunk: &Chunk, mut from: IVec3, mut size: IVec3) ->
         x_size: size.x as u8, 
         y_size: size.y as u8, 
         z_size: size.z as u8,
-        compressed_data: Arc::new(encoder.finish().unwrap()),
+        compressed_data: Arc::new(data),
     }
     
 }
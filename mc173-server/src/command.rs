@@ -2,12 +2,12 @@
 
 use std::mem;
 
-use glam::IVec3;
+use glam::{DVec3, IVec3};
 
-use mc173::entity::{BaseKind, Entity, EntityCategory, EntityKind};
-use mc173::world::{Event, Weather};
+use mc173::entity::{BaseKind, Entity, EntityCategory, EntityKind, LivingKind};
+use mc173::world::{Event, EntityEvent, Weather};
 use mc173::item::{self, ItemStack};
-use mc173::block;
+use mc173::{block, chunk};
 
 use crate::world::{ServerWorld, TickMode};
 use crate::proto::{OutPacket, self};
@@ -75,6 +75,9 @@ struct Command {
     handler: fn(CommandContext) -> CommandResult,
 }
 
+/// Maximum number of full stacks that can be given at once by the give command.
+const GIVE_MAX_STACKS: u32 = 64;
+
 /// Internal array of commands.
 const COMMANDS: &'static [Command] = &[
     Command {
@@ -85,10 +88,16 @@ const COMMANDS: &'static [Command] = &[
     },
     Command {
         name: "give",
-        usage: "<item>[:<damage>] [<size>]",
+        usage: "<player> <item|block>[:<damage>] [<count>] [<damage>]",
         description: "Give item to a player",
         handler: cmd_give
     },
+    Command {
+        name: "tp",
+        usage: "<target> <x> <y> <z>|<destination>",
+        description: "Teleport an entity, coordinates can be relative with ~",
+        handler: cmd_tp
+    },
     Command {
         name: "spawn",
         usage: "<entity_kind> [<params>...]",
@@ -97,8 +106,8 @@ const COMMANDS: &'static [Command] = &[
     },
     Command {
         name: "time",
-        usage: "",
-        description: "Display world and server time",
+        usage: "[set day|noon|night|midnight|<time>]",
+        description: "Display or set world and server time",
         handler: cmd_time
     },
     Command {
@@ -181,17 +190,27 @@ fn cmd_help(ctx: CommandContext) -> CommandResult {
 
 fn cmd_give(ctx: CommandContext) -> CommandResult {
 
-    if ctx.parts.len() != 1 && ctx.parts.len() != 2 {
+    let [target, item_raw, ref params @ ..] = *ctx.parts else {
+        return Err(None);
+    };
+
+    if params.len() > 2 {
         return Err(None);
     }
 
-    let item_raw = ctx.parts[0];
+    // NOTE: The command context only gives access to the sender, so it's the only 
+    // player that can be targeted for now.
+    if target != ctx.player.username {
+        return Err(Some(format!("§cError: unknown player:§r {target}")));
+    }
 
     let (
         id_raw, 
         metadata_raw
     ) = item_raw.split_once(':').unwrap_or((item_raw, ""));
 
+    // Items are searched first because some blocks have the same name as their item,
+    // such as signs or doors, in such case the block is not obtainable as an item.
     let id;
     if let Ok(direct_id) = id_raw.parse::<u16>() {
         id = direct_id;
@@ -204,24 +223,96 @@ fn cmd_give(ctx: CommandContext) -> CommandResult {
     }
 
     let item = item::from_id(id);
-    if item.name.is_empty() {
+    if item.name.is_empty() || id == block::AIR as u16 {
         return Err(Some(format!("§cError: unknown item id:§r {id_raw}")));
     }
 
-    let mut stack = ItemStack::new_sized(id, 0, item.max_stack_size);
+    let mut stack = ItemStack::new_single(id, 0);
+    let mut count = 1;
 
-    if !metadata_raw.is_empty() {
-        stack.damage = metadata_raw.parse::<u16>()
-            .map_err(|_| format!("§cError: invalid item damage:§r {metadata_raw}"))?;
+    if let Some(count_raw) = params.first() {
+        count = count_raw.parse::<u32>()
+            .ok()
+            .filter(|&count| count != 0)
+            .ok_or_else(|| format!("§cError: invalid count:§r {count_raw}"))?;
     }
 
-    if let Some(size_raw) = ctx.parts.get(1) {
-        stack.size = size_raw.parse::<u16>()
-            .map_err(|_| format!("§cError: invalid stack size:§r {size_raw}"))?;
+    let damage_raw = params.get(1).copied().unwrap_or(metadata_raw);
+    if !damage_raw.is_empty() {
+        stack.damage = damage_raw.parse::<u16>()
+            .map_err(|_| format!("§cError: invalid item damage:§r {damage_raw}"))?;
+    }
+
+    // The count is capped to a full inventory of stacks of the maximum size, this
+    // avoids flooding the world with item entities.
+    let max_stack_size = item.max_stack_size as u32;
+    count = count.min(max_stack_size * GIVE_MAX_STACKS);
+
+    ctx.player.send_chat(format!("§aGiving §r{}§a (§r{}:{}§a) x§r{}§a to §r{}", item.name, stack.id, stack.damage, count, ctx.player.username));
+
+    // Give the items by stacks of the maximum size, items that cannot fit in the 
+    // inventory are dropped on the ground.
+    while count != 0 {
+        
+        let size = count.min(max_stack_size);
+        count -= size;
+
+        stack.size = size as u16;
+        ctx.player.pickup_stack(&mut stack);
+        
+        if !stack.is_empty() {
+            ctx.player.drop_stack(ctx.world, stack, true);
+        }
+
     }
 
-    ctx.player.send_chat(format!("§aGiving §r{}§a (§r{}:{}§a) x§r{}§a to §r{}", item.name, stack.id, stack.damage, stack.size, ctx.player.username));
-    ctx.player.pickup_stack(&mut stack);
+    Ok(())
+
+}
+
+fn cmd_tp(ctx: CommandContext) -> CommandResult {
+
+    let (target_raw, dest_parts) = match *ctx.parts {
+        [target_raw, ref dest_parts @ ..] if dest_parts.len() == 1 || dest_parts.len() == 3 => 
+            (target_raw, dest_parts),
+        _ => return Err(None)
+    };
+
+    let target_id = find_entity(ctx.world, target_raw)?;
+    let target_pos = ctx.world.world.get_entity(target_id).unwrap().0.pos;
+
+    let pos = match *dest_parts {
+        [x_raw, y_raw, z_raw] => DVec3 {
+            x: parse_coord(x_raw, target_pos.x)?,
+            y: parse_coord(y_raw, target_pos.y)?,
+            z: parse_coord(z_raw, target_pos.z)?,
+        },
+        [dest_raw] => {
+            let dest_id = find_entity(ctx.world, dest_raw)?;
+            ctx.world.world.get_entity(dest_id).unwrap().0.pos
+        }
+        _ => unreachable!()
+    };
+
+    // The destination chunk must be loaded, if not the case we request it.
+    let (cx, cz) = chunk::calc_entity_chunk_pos(pos);
+    if !ctx.world.world.contains_chunk(cx, cz) {
+        ctx.world.request_chunk_load(cx, cz);
+        return Err(Some("§cError: destination chunk is loading, try again".to_string()));
+    }
+
+    if target_id == ctx.player.entity_id {
+        ctx.player.teleport(ctx.world, pos);
+    } else if ctx.world.world.is_player_entity(target_id) {
+        // NOTE: The command context only gives access to the sender, so we can't update
+        // the client of other players.
+        return Err(Some("§cError: cannot teleport other players".to_string()));
+    } else {
+        ctx.world.world.get_entity_mut(target_id).unwrap().teleport(pos);
+        ctx.world.world.push_event(Event::Entity { id: target_id, inner: EntityEvent::Position { pos } });
+    }
+
+    ctx.player.send_chat(format!("§aTeleported §r{target_raw}§a to:§r {:.2}/{:.2}/{:.2}", pos.x, pos.y, pos.z));
     Ok(())
 
 }
@@ -250,12 +341,7 @@ fn cmd_spawn(ctx: CommandContext) -> CommandResult {
         _ => return Err(Some(format!("§cError: invalid or unsupported entity kind:§r {entity_kind_raw}")))
     };
 
-    let mut entity = entity_kind.new_default(ctx.player.pos);
-    entity.0.persistent = true;
-
-    entity.init_natural_spawn(&mut ctx.world.world);
-
-    let entity_id = ctx.world.world.spawn_entity(entity);
+    let entity_id = ctx.world.world.spawn_kind(entity_kind, ctx.player.pos, true);
     ctx.player.send_chat(format!("§aEntity spawned:§r {entity_id}"));
 
     Ok(())
@@ -263,9 +349,37 @@ fn cmd_spawn(ctx: CommandContext) -> CommandResult {
 }
 
 fn cmd_time(ctx: CommandContext) -> CommandResult {
-    ctx.player.send_chat(format!("§aWorld time:§r {}", ctx.world.world.get_time()));
-    ctx.player.send_chat(format!("§aServer time:§r {}", ctx.world.time));
-    Ok(())
+    match ctx.parts {
+        [] => {
+            ctx.player.send_chat(format!("§aWorld time:§r {}", ctx.world.world.get_time()));
+            ctx.player.send_chat(format!("§aServer time:§r {}", ctx.world.time));
+            Ok(())
+        }
+        ["set", day_time_raw] => {
+
+            let day_time = match *day_time_raw {
+                "day" => 0,
+                "noon" => 6000,
+                "night" => 12000,
+                "midnight" => 18000,
+                _ => day_time_raw.parse::<u64>()
+                    .map_err(|_| format!("§cError: invalid time:§r {day_time_raw}"))?
+            };
+
+            // We only move the time forward to the next given time of the day, this 
+            // avoids breaking scheduled ticks that are relative to the world time.
+            let time = ctx.world.world.get_time();
+            let delta = (day_time % 24000 + 24000 - time % 24000) % 24000;
+            let time = time + delta;
+
+            ctx.world.world.set_time(time);
+            ctx.player.send(OutPacket::UpdateTime(proto::UpdateTimePacket { time }));
+            ctx.player.send_chat(format!("§aWorld time set to:§r {time}"));
+            Ok(())
+
+        }
+        _ => Err(None)
+    }
 }
 
 fn cmd_weather(ctx: CommandContext) -> CommandResult { 
@@ -308,7 +422,7 @@ fn cmd_pos(ctx: CommandContext) -> CommandResult {
     ctx.player.send_chat(format!("§aBlock light:§r {}", light.block));
     ctx.player.send_chat(format!("§aSky light:§r {}", light.sky));
     ctx.player.send_chat(format!("§aSky real light:§r {}", light.sky_real));
-    ctx.player.send_chat(format!("§aBrightness:§r {}", light.brightness()));
+    ctx.player.send_chat(format!("§aBrightness:§r {}", light.brightness(ctx.world.world.get_dimension())));
 
     if let Some(biome) = ctx.world.world.get_biome(block_pos) {
         ctx.player.send_chat(format!("§aBiome:§r {biome:?}"));
@@ -501,8 +615,8 @@ fn cmd_entity(ctx: CommandContext) -> CommandResult {
         base.on_ground, base.in_water, base.in_lava));
     ctx.player.send_chat(format!("§aFall Distance:§r {} §8| §aFire Time:§r {} §8| §aAir Time:§r {}", 
         base.fall_distance, base.fire_time, base.air_time));
-    ctx.player.send_chat(format!("§aRider Id:§r {:?} §8| §aBobber Id:§r {:?}", 
-        base.rider_id, base.bobber_id));
+    ctx.player.send_chat(format!("§aRider Id:§r {:?} §8| §aVehicle Id:§r {:?} §8| §aBobber Id:§r {:?}", 
+        base.rider_id, base.vehicle_id, base.bobber_id));
 
     match base_kind {
         BaseKind::Item(item) => {
@@ -543,3 +657,138 @@ fn cmd_ib(ctx: CommandContext) -> CommandResult {
     Ok(())
 
 }
+
+
+/// Find an entity from its id or from a player username.
+fn find_entity(sw: &ServerWorld, raw: &str) -> Result<u32, Option<String>> {
+    if let Ok(id) = raw.parse::<u32>() {
+        if sw.world.get_entity(id).is_some() {
+            return Ok(id);
+        }
+    } else {
+        for (id, entity) in sw.world.iter_player_entities() {
+            if let Entity(_, BaseKind::Living(_, LivingKind::Human(human))) = entity {
+                if human.username == raw {
+                    return Ok(id);
+                }
+            }
+        }
+    }
+    Err(Some(format!("§cError: unknown entity or player:§r {raw}")))
+}
+
+/// Parse a coordinate that can be relative to the given origin if prefixed by '~'.
+fn parse_coord(raw: &str, origin: f64) -> Result<f64, Option<String>> {
+    let (base, raw) = match raw.strip_prefix('~') {
+        Some("") => return Ok(origin),
+        Some(raw) => (origin, raw),
+        None => (0.0, raw),
+    };
+    raw.parse::<f64>()
+        .map(|delta| base + delta)
+        .map_err(|_| Some(format!("§cError: invalid coordinate:§r {raw}")))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use glam::DVec3;
+
+    use mc173::chunk::Chunk;
+
+    use crate::test::Test;
+
+    use super::*;
+
+    impl Test {
+
+        /// Execute the given command line (without leading slash).
+        fn command(&mut self, line: &str) {
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            handle_command(CommandContext {
+                parts: &parts,
+                world: &mut self.world,
+                player: &mut self.player,
+            });
+        }
+
+    }
+
+    #[test]
+    fn give() {
+
+        let mut test = Test::new();
+
+        test.command("give test cobblestone 64");
+        assert_eq!(test.player.get_inv().main[0], ItemStack::new_block_sized(block::COBBLESTONE, 0, 64));
+        assert!(test.player.get_inv().main[1].is_empty());
+
+        test.command("give test wool 3 14");
+        assert_eq!(test.player.get_inv().main[1], ItemStack::new_block_sized(block::WOOL, 14, 3));
+
+        test.command("give test diamond_sword 2");
+        assert_eq!(test.player.get_inv().main[2], ItemStack::new_single(item::DIAMOND_SWORD, 0));
+        assert_eq!(test.player.get_inv().main[3], ItemStack::new_single(item::DIAMOND_SWORD, 0));
+
+        test.command("give other stone 1");
+        test.command("give test unknown 1");
+        assert!(test.player.get_inv().main[4].is_empty());
+
+    }
+
+    #[test]
+    fn give_full() {
+
+        let mut test = Test::new();
+        let entity_count = test.world.world.get_entity_count();
+
+        test.command("give test stone 2336");
+        assert!(test.player.get_inv().main.iter().all(|stack| stack.size == 64));
+        
+        // The last stack should be dropped as an item entity.
+        assert_eq!(test.world.world.get_entity_count(), entity_count + 1);
+
+        // Huge counts are capped, only the remaining stacks are dropped.
+        test.command("give test stone 1000000");
+        assert_eq!(test.world.world.get_entity_count(), entity_count + 1 + GIVE_MAX_STACKS as usize);
+
+    }
+
+    #[test]
+    fn tp() {
+
+        let mut test = Test::new();
+        test.world.world.set_chunk(0, 0, Chunk::new());
+
+        let origin = test.player.pos;
+        test.command("tp test ~10 ~ ~");
+        assert_eq!(test.player.pos, origin + DVec3::new(10.0, 0.0, 0.0));
+        assert_eq!(test.world.world.get_entity(test.player.entity_id).unwrap().0.pos, test.player.pos);
+
+        test.command("tp test 2 ~-20 ~3.5");
+        assert_eq!(test.player.pos, DVec3::new(2.0, 80.0, 3.5));
+
+        // Unloaded chunk, the player should not move.
+        test.command("tp test ~100 ~ ~");
+        assert_eq!(test.player.pos, DVec3::new(2.0, 80.0, 3.5));
+
+    }
+
+    #[test]
+    fn time_set() {
+
+        let mut test = Test::new();
+        test.world.world.set_time(30000);
+        
+        test.command("time set day");
+        assert_eq!(test.world.world.get_time(), 48000);
+        
+        test.command("time set night");
+        assert_eq!(test.world.world.get_time(), 60000);
+        
+        test.command("time set day");
+        assert_eq!(test.world.world.get_time(), 72000);
+
+    }
+
+}
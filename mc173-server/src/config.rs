@@ -3,7 +3,9 @@
 
 use std::env;
 
-use once_cell::race::OnceBool;
+use std::num::NonZeroUsize;
+
+use once_cell::race::{OnceBool, OnceNonZeroUsize};
 use glam::DVec3;
 
 
@@ -33,6 +35,24 @@ pub fn client_piston() -> bool {
     })
 }
 
+/// Return the view distance, in chunks, around each player where chunks are loaded by
+/// the server. Chunks outside of this distance of any player are unloaded.
+/// 
+/// To change this value, set `MC173_VIEW_DISTANCE=<distance>` (default to 10).
+pub fn view_distance() -> u8 {
+    static ENV: OnceNonZeroUsize = OnceNonZeroUsize::new();
+    ENV.get_or_init(|| {
+        env::var("MC173_VIEW_DISTANCE").ok()
+            .and_then(|s| s.parse::<u8>().ok())
+            .and_then(|d| NonZeroUsize::new(d as usize))
+            .unwrap_or(NonZeroUsize::new(10).unwrap())
+    }).get() as u8
+}
+
+/// Maximum number of chunks load requested to the storage on each world tick, this
+/// avoids large spikes when a player moves quickly or joins.
+pub const CHUNK_LOADS_PER_TICK: usize = 16;
+
 /// Server world seed is currently hardcoded.
 pub const SEED: i64 = 9999;
 
@@ -45,6 +45,8 @@ pub struct EntityTracker {
     sent_vel: (i16, i16, i16),
     /// Last encoded look sent to clients.
     sent_look: (i8, i8),
+    /// Last known position when players tracking was last updated.
+    tracking_pos: (i32, i32, i32),
 }
 
 impl EntityTracker {
@@ -86,6 +88,7 @@ impl EntityTracker {
             sent_pos: (0, 0, 0),
             sent_vel: (0, 0, 0),
             sent_look: (0, 0),
+            tracking_pos: (0, 0, 0),
         };
 
         // If fast entity tracking is enabled and interval is not disabled, set interval
@@ -100,6 +103,7 @@ impl EntityTracker {
         tracker.sent_pos = tracker.pos;
         tracker.sent_look = tracker.look;
         tracker.sent_vel = tracker.vel;
+        tracker.tracking_pos = tracker.pos;
         tracker
 
     } 
@@ -154,6 +158,50 @@ impl EntityTracker {
     /// Update this tracker to determine which move packet to send and to which players.
     pub fn update_players(&mut self, players: &[ServerPlayer]) {
 
+        if let Some(packet) = self.update_move() {
+            for player in players {
+                if player.tracked_entities.contains(&self.id) {
+                    player.send(packet.clone());
+                }
+            }
+        }
+
+        // If velocity tracking is enabled...
+        if self.vel_enable {
+
+            // We differ from the Notchian server because we don't check for the distance.
+            let dvx = self.vel.0 as i32 - self.sent_vel.0 as i32;
+            let dvy = self.vel.1 as i32 - self.sent_vel.1 as i32;
+            let dvz = self.vel.2 as i32 - self.sent_vel.2 as i32;
+            // If any axis velocity change by 0.0125 (100 when encoded *8000).
+            if dvx.abs() > 100 || dvy.abs() > 100 || dvz.abs() > 100 {
+                
+                for player in players {
+                    if player.tracked_entities.contains(&self.id) {
+                        player.send(OutPacket::EntityVelocity(proto::EntityVelocityPacket {
+                            entity_id: self.id,
+                            vx: self.vel.0,
+                            vy: self.vel.1,
+                            vz: self.vel.2,
+                        }));
+                    }
+                }
+        
+                self.sent_vel = self.vel;
+
+            }
+            
+        }
+
+    }
+
+    /// Compute the move packet to send for the delta accumulated since the last sent
+    /// position and look. A relative move packet is used when the delta in 1/32 block
+    /// units fits in a byte, else the absolute position is sent, this is also the case
+    /// every 400 ticks to avoid client-side drifting. The sent position and look are
+    /// updated if relevant, and no packet is returned if the delta is too small.
+    fn update_move(&mut self) -> Option<OutPacket> {
+
         let mut send_pos = true;
         let send_look = self.look.0.abs_diff(self.sent_look.0) >= 8 || self.look.1.abs_diff(self.sent_look.1) >= 8;
 
@@ -214,46 +262,24 @@ impl EntityTracker {
             self.sent_look = self.look;
         }
 
-        if let Some(packet) = move_packet {
-            for player in players {
-                if player.tracked_entities.contains(&self.id) {
-                    player.send(packet.clone());
-                }
-            }
-        }
-
-        // If velocity tracking is enabled...
-        if self.vel_enable {
+        move_packet
 
-            // We differ from the Notchian server because we don't check for the distance.
-            let dvx = self.vel.0 as i32 - self.sent_vel.0 as i32;
-            let dvy = self.vel.1 as i32 - self.sent_vel.1 as i32;
-            let dvz = self.vel.2 as i32 - self.sent_vel.2 as i32;
-            // If any axis velocity change by 0.0125 (100 when encoded *8000).
-            if dvx.abs() > 100 || dvy.abs() > 100 || dvz.abs() > 100 {
-                
-                for player in players {
-                    if player.tracked_entities.contains(&self.id) {
-                        player.send(OutPacket::EntityVelocity(proto::EntityVelocityPacket {
-                            entity_id: self.id,
-                            vx: self.vel.0,
-                            vy: self.vel.1,
-                            vz: self.vel.2,
-                        }));
-                    }
-                }
-        
-                self.sent_vel = self.vel;
-
-            }
-            
-        }
+    }
 
+    /// Return true if the entity has moved by more than 4 blocks since the last update
+    /// of tracking players, in such case the tracking players should be updated.
+    pub fn need_tracking_update(&self) -> bool {
+        // Position is encoded in 1/32 of block, so 4 blocks is 128.
+        let dx = (self.pos.0 - self.tracking_pos.0) as i64;
+        let dy = (self.pos.1 - self.tracking_pos.1) as i64;
+        let dz = (self.pos.2 - self.tracking_pos.2) as i64;
+        dx * dx + dy * dy + dz * dz > 128 * 128
     }
 
     /// Update players to track or untrack this entity. 
     /// See [`update_tracking_player`](Self::update_tracking_player).
-    pub fn update_tracking_players(&self, players: &mut [ServerPlayer], world: &World) {
+    pub fn update_tracking_players(&mut self, players: &mut [ServerPlayer], world: &World) {
+        self.tracking_pos = self.pos;
         for player in players {
             self.update_tracking_player(player, world);
         }
@@ -279,6 +305,16 @@ impl EntityTracker {
 
     }
 
+    /// Spawn the entity again for all players tracking it, at its current position, 
+    /// this is used when a player respawns because other players have seen its entity
+    /// dying.
+    pub fn respawn_players(&mut self, players: &mut [ServerPlayer], world: &World) {
+        self.untrack_players(players);
+        self.sent_pos = self.pos;
+        self.sent_look = self.look;
+        self.update_tracking_players(players, world);
+    }
+
     /// Force untrack this entity to this player if the player is already tracking it.
     pub fn untrack_player(&self, player: &mut ServerPlayer) {
         if player.tracked_entities.remove(&self.id) {
@@ -307,9 +343,9 @@ impl EntityTracker {
             BaseKind::Item(item) => self.spawn_entity_item(player, base, item),
             BaseKind::Painting(painting) => self.spawn_entity_painting(player, painting),
             BaseKind::Boat(_) => self.spawn_entity_object(player, 1, false),
-            BaseKind::Minecart(e::Minecart::Normal) => self.spawn_entity_object(player, 10, false),
-            BaseKind::Minecart(e::Minecart::Chest { .. }) => self.spawn_entity_object(player, 11, false),
-            BaseKind::Minecart(e::Minecart::Furnace { .. }) => self.spawn_entity_object(player, 12, false),
+            BaseKind::Minecart(e::Minecart { kind: e::MinecartKind::Normal, .. }) => self.spawn_entity_object(player, 10, false),
+            BaseKind::Minecart(e::Minecart { kind: e::MinecartKind::Chest { .. }, .. }) => self.spawn_entity_object(player, 11, false),
+            BaseKind::Minecart(e::Minecart { kind: e::MinecartKind::Furnace { .. }, .. }) => self.spawn_entity_object(player, 12, false),
             BaseKind::LightningBolt(_) => self.spawn_entity_lightning_bolt(player),
             BaseKind::FallingBlock(falling_block) => {
                 // NOTE: We use sand for any block id that is unsupported.
@@ -349,6 +385,13 @@ impl EntityTracker {
             }
         }
 
+        if let Some(vehicle_id) = base.vehicle_id {
+            player.send(OutPacket::EntityRide(proto::EntityRidePacket {
+                entity_id: self.id,
+                vehicle_entity_id: vehicle_id,
+            }));
+        }
+
     }
 
     fn spawn_entity_human(&self, player: &ServerPlayer, human: &e::Human, metadata: Vec<proto::Metadata>) {
@@ -496,3 +539,79 @@ impl EntityTracker {
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::test::Test;
+
+    use super::*;
+
+    #[test]
+    fn track_mob() {
+
+        let mut test = Test::new();
+
+        let pos = test.player.pos + DVec3::new(5.0, 0.0, 0.0);
+        let id = test.world.world.spawn_entity(e::Zombie::new_with(|base, _, _| {
+            base.pos = pos;
+        }));
+
+        let mut tracker = EntityTracker::new(id, test.world.world.get_entity(id).unwrap());
+        assert!(!tracker.need_tracking_update());
+        tracker.update_tracking_players(std::slice::from_mut(&mut test.player), &test.world.world);
+        assert!(test.player.tracked_entities.contains(&id));
+
+        // Mob spawn packet (24) should be sent with the entity id.
+        let mut spawn = vec![24];
+        spawn.extend_from_slice(&(id as i32).to_be_bytes());
+        test.recv_until(|buf| buf.starts_with(&spawn));
+
+        // Moving a little doesn't require tracking update.
+        tracker.set_pos(pos + DVec3::new(2.0, 0.0, 0.0));
+        assert!(!tracker.need_tracking_update());
+
+        // Moving out of the tracking range.
+        tracker.set_pos(pos + DVec3::new(200.0, 0.0, 0.0));
+        assert!(tracker.need_tracking_update());
+        tracker.update_tracking_players(std::slice::from_mut(&mut test.player), &test.world.world);
+        assert!(!tracker.need_tracking_update());
+        assert!(!test.player.tracked_entities.contains(&id));
+
+        // Entity kill packet (29) should be sent with the entity id.
+        let mut kill = vec![29];
+        kill.extend_from_slice(&(id as i32).to_be_bytes());
+        test.recv_until(|buf| buf.ends_with(&kill));
+
+    }
+
+    #[test]
+    fn move_packet() {
+
+        let pos = DVec3::new(0.5, 64.0, 0.5);
+        let entity = e::Zombie::new_with(|base, _, _| base.pos = pos);
+        let mut tracker = EntityTracker::new(1, &entity);
+
+        // Delta too small to be sent (less than 8/32 block).
+        tracker.set_pos(pos + DVec3::new(0.2, 0.0, 0.0));
+        assert!(tracker.update_move().is_none());
+
+        // Small deltas are accumulated until large enough to be sent relatively.
+        tracker.set_pos(pos + DVec3::new(0.5, 0.0, 0.0));
+        let Some(OutPacket::EntityMove(packet)) = tracker.update_move() else { panic!("expected relative move") };
+        assert_eq!((packet.dx, packet.dy, packet.dz), (16, 0, 0));
+
+        // Delta that doesn't fit in a byte (128/32 = 4 blocks) is sent absolutely.
+        tracker.set_pos(pos + DVec3::new(10.5, 0.0, 0.0));
+        let Some(OutPacket::EntityPositionAndLook(packet)) = tracker.update_move() else { panic!("expected absolute move") };
+        assert_eq!((packet.x, packet.y, packet.z), (352, 2048, 16));
+
+        // The absolute position is periodically resent.
+        tracker.set_pos(pos + DVec3::new(11.0, 0.0, 0.0));
+        tracker.absolute_countdown_time = 401;
+        assert!(matches!(tracker.update_move(), Some(OutPacket::EntityPositionAndLook(_))));
+
+    }
+
+}
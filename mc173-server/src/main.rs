@@ -23,6 +23,9 @@ pub mod command;
 // This module link the previous ones to make a fully functional, multi-world server.
 pub mod server;
 
+#[cfg(test)]
+mod test;
+
 /// Storing true while the server should run.
 static RUNNING: AtomicBool = AtomicBool::new(true);
 
@@ -41,6 +41,8 @@ pub struct Network<I, O> {
     commands_sender: Sender<ThreadCommand<O>>,
     /// This channels allows received events from the thread.
     events_receiver: Receiver<ThreadEvent<I>>,
+    /// The local address the listener is bound to.
+    local_addr: SocketAddr,
 }
 
 impl<I, O> Network<I, O>
@@ -53,6 +55,7 @@ where
 
         let poll = Poll::new()?;
         let mut listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
         poll.registry().register(&mut listener, LISTENER_TOKEN, Interest::READABLE)?;
 
         // TODO: Adapt channel sizes depending on number of players, maybe unbounded 
@@ -97,10 +100,18 @@ where
         Ok(Self {
             commands_sender,
             events_receiver,
+            local_addr,
         })
 
     }
 
+    /// Return the local address the server is bound to, this is useful when binding to
+    /// a random port.
+    #[inline]
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
     /// Poll events from this packet server. If an I/O error is returned, the error is
     /// critical and the 
     pub fn poll(&self) -> io::Result<Option<NetworkEvent<I>>> {
@@ -7,15 +7,15 @@ use glam::{DVec3, Vec2, IVec3};
 use mc173::util::split_at_utf8_boundary;
 use tracing::warn;
 
-use mc173::world::{BlockEntityStorage, BlockEntityEvent, Event, BlockEntityProgress, EntityEvent};
+use mc173::world::{Dimension, BlockEntityStorage, BlockEntityEvent, Event, BlockEntityProgress, EntityEvent};
 use mc173::world::interact::Interaction;
 
-use mc173::entity::{self as e, Entity, Hurt, BaseKind, LivingKind};
+use mc173::entity::{self as e, Entity, BaseKind, LivingKind};
 use mc173::block_entity::BlockEntity;
 use mc173::item::{self, ItemStack};
 use mc173::{block, chunk};
 
-use mc173::inventory::InventoryHandle;
+use mc173::inventory::{InventoryHandle, ChangesIter};
 use mc173::craft::CraftTracker;
 use mc173::geom::Face;
 
@@ -24,6 +24,7 @@ use crate::command::{self, CommandContext};
 use crate::chunk::new_chunk_data_packet;
 use crate::offline::OfflinePlayer;
 use crate::world::ServerWorld;
+use crate::config;
 
 
 /// A server player is an actual 
@@ -48,16 +49,10 @@ pub struct ServerPlayer {
     /// known and rendered by the client, when the entity will disappear, a kill packet
     /// should be sent.
     pub tracked_entities: HashSet<u32>,
-    /// The main player inventory including the hotbar in the first 9 slots.
-    main_inv: Box<[ItemStack; 36]>,
-    /// The armor player inventory.
-    armor_inv: Box<[ItemStack; 4]>,
-    /// The item stacks for the 3x3 crafting grid. Also support the 2x2 as top left slots.
-    craft_inv: Box<[ItemStack; 9]>,
+    /// The player inventory, with armor, crafting grid and selected hand slot.
+    inv: Inventory,
     /// The item stack in the cursor of the client's using a window.
     cursor_stack: ItemStack,
-    /// The slot current selected for the hand. Must be in range 0..9.
-    hand_slot: u8,
     /// The total number of windows that have been opened by this player, this is also 
     /// used to generate a unique window id. This id should never be zero because it is
     /// reserved for the player inventory.
@@ -73,6 +68,15 @@ pub struct ServerPlayer {
     breaking_block: Option<BreakingBlock>,
 }
 
+/// The difference between chunks sent to a player and the chunks it should view.
+#[derive(Debug, Default)]
+pub struct ChunksDiff {
+    /// Chunks that are in view but not yet sent to the player.
+    pub send: Vec<(i32, i32)>,
+    /// Chunks that were sent to the player but are no longer in view.
+    pub drop: Vec<(i32, i32)>,
+}
+
 /// Describe an opened window and how to handle clicks into it.
 #[derive(Debug, Default)]
 struct Window {
@@ -105,6 +109,104 @@ enum WindowKind {
     /// The client-side has a dispenser window onto the given block entity.
     Dispenser {
         pos: IVec3,
+    },
+    /// The client-side has a chest window onto the given chest minecart entity.
+    ChestMinecart {
+        id: u32,
+    },
+}
+
+/// The inventory of a player, composed of the main inventory with the hotbar, the armor
+/// inventory and the crafting grid, it also tracks the hotbar slot selected for the hand.
+#[derive(Debug, Clone)]
+pub struct Inventory {
+    /// The main player inventory including the hotbar in the first 9 slots.
+    pub main: Box<[ItemStack; 36]>,
+    /// The armor player inventory.
+    pub armor: Box<[ItemStack; 4]>,
+    /// The item stacks for the 3x3 crafting grid. Also support the 2x2 as top left slots.
+    pub craft: Box<[ItemStack; 9]>,
+    /// The slot current selected for the hand. Must be in range 0..9.
+    hand_slot: u8,
+}
+
+impl Inventory {
+
+    /// Create a new empty inventory.
+    pub fn new() -> Self {
+        Self {
+            main: Box::new([ItemStack::EMPTY; 36]),
+            armor: Box::new([ItemStack::EMPTY; 4]),
+            craft: Box::new([ItemStack::EMPTY; 9]),
+            hand_slot: 0,
+        }
+    }
+
+    /// Get the hotbar slot currently selected for the hand, in range 0..9.
+    #[inline]
+    pub fn get_hand_slot(&self) -> u8 {
+        self.hand_slot
+    }
+
+    /// Select the hotbar slot for the hand, this panics if the slot is not in range 0..9.
+    #[inline]
+    pub fn set_hand_slot(&mut self, slot: u8) {
+        assert!(slot < 9, "invalid hand slot");
+        self.hand_slot = slot;
+    }
+
+    /// Get the item stack currently in the hand.
+    #[inline]
+    pub fn get_hand(&self) -> ItemStack {
+        self.main[self.hand_slot as usize]
+    }
+
+    /// Set the item stack currently in the hand.
+    #[inline]
+    pub fn set_hand(&mut self, stack: ItemStack) {
+        self.main[self.hand_slot as usize] = stack;
+    }
+
+    /// Add an item stack to the main inventory, the stack is first merged into existing
+    /// stacks of the same item and then inserted into empty slots. The given stack's 
+    /// size is updated to the amount of items that has not been added, if the inventory
+    /// is full. The indices of all changed slots are returned.
+    pub fn add(&mut self, stack: &mut ItemStack) -> ChangesIter {
+        let mut inv = InventoryHandle::new(&mut self.main[..]);
+        inv.push_front(stack);
+        inv.iter_changes()
+    }
+
+    /// Remove at most the given count of items from a slot in the main inventory, the
+    /// removed stack is returned and is empty if the slot was empty.
+    pub fn remove(&mut self, index: usize, count: u16) -> ItemStack {
+        
+        let slot = &mut self.main[index];
+        if slot.is_empty() {
+            return ItemStack::EMPTY;
+        }
+
+        let removed = slot.with_size(slot.size.min(count));
+        slot.size -= removed.size;
+        if slot.size == 0 {
+            *slot = ItemStack::EMPTY;
+        }
+
+        removed
+
+    }
+
+    /// Swap two slots of the main inventory.
+    #[inline]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.main.swap(a, b);
+    }
+
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -133,11 +235,8 @@ impl ServerPlayer {
             instant_break: false,
             tracked_chunks: HashSet::new(),
             tracked_entities: HashSet::new(),
-            main_inv: Box::new([ItemStack::EMPTY; 36]),
-            armor_inv: Box::new([ItemStack::EMPTY; 4]),
-            craft_inv: Box::new([ItemStack::EMPTY; 9]),
+            inv: Inventory::new(),
             cursor_stack: ItemStack::EMPTY,
-            hand_slot: 0,
             window_count: 0,
             window: Window::default(),
             craft_tracker: CraftTracker::default(),
@@ -145,6 +244,11 @@ impl ServerPlayer {
         }
     }
 
+    /// Get the player inventory.
+    pub fn get_inv(&self) -> &Inventory {
+        &self.inv
+    }
+
     /// Send a packet to this player.
     pub fn send(&self, packet: OutPacket) {
         // println!("[NET] Sending packet {packet:?}");
@@ -206,6 +310,8 @@ impl ServerPlayer {
                 self.handle_action(world, packet),
             InPacket::UpdateSign(packet) =>
                 self.handle_update_sign(world, packet),
+            InPacket::Respawn(_) =>
+                self.handle_respawn(world),
             _ => warn!("unhandled packet from #{}: {packet:?}", self.client.id())
         }
 
@@ -228,6 +334,51 @@ impl ServerPlayer {
         }
     }
 
+    /// Handle a respawn packet, sent by the client when its player is dead. The player 
+    /// entity is restored with full health and moved to the world spawn position.
+    fn handle_respawn(&mut self, sw: &mut ServerWorld) {
+
+        let entity = sw.world.get_entity_mut(self.entity_id).expect("incoherent player entity");
+        let Entity(base, BaseKind::Living(living, LivingKind::Human(_))) = entity else {
+            panic!("incoherent player entity");
+        };
+
+        if living.health != 0 {
+            warn!("from {}, cannot respawn while alive", self.username);
+            return;
+        }
+
+        living.health = 20;
+        living.death_time = 0;
+        living.hurt_time = 0;
+        living.hurt_last_damage = 0;
+        base.hurt.clear();
+        base.vel = DVec3::ZERO;
+        base.fire_time = 0;
+        base.fall_distance = 0.0;
+        base.air_time = 300;
+
+        self.send(OutPacket::Respawn(proto::RespawnPacket {
+            dimension: match sw.world.get_dimension() {
+                Dimension::Overworld => 0,
+                Dimension::Nether => -1,
+            },
+        }));
+
+        self.send(OutPacket::UpdateHealth(proto::UpdateHealthPacket {
+            health: 20,
+        }));
+
+        sw.world.push_event(Event::Entity { id: self.entity_id, inner: EntityEvent::Metadata });
+
+        let spawn_pos = sw.world.get_spawn_pos().as_dvec3() + DVec3::new(0.5, 0.0, 0.5);
+        self.teleport(sw, spawn_pos);
+
+        // Other players have seen the entity dying, so it is spawned again for them.
+        sw.world.push_event(Event::Entity { id: self.entity_id, inner: EntityEvent::Spawn });
+
+    }
+
     /// Handle a position packet.
     fn handle_position(&mut self, sw: &mut ServerWorld, packet: proto::PositionPacket) {
         self.handle_position_look_inner(sw, Some(packet.pos), None, packet.on_ground);
@@ -243,7 +394,28 @@ impl ServerPlayer {
         self.handle_position_look_inner(sw, Some(packet.pos), Some(packet.look), packet.on_ground);
     }
 
-    fn handle_position_look_inner(&mut self, sw: &mut ServerWorld, pos: Option<DVec3>, look: Option<Vec2>, on_ground: bool) {
+    fn handle_position_look_inner(&mut self, sw: &mut ServerWorld, mut pos: Option<DVec3>, look: Option<Vec2>, on_ground: bool) {
+
+        // While riding, the entity is moved with its vehicle and the client sends an
+        // invalid position, so we just follow the entity's position.
+        let entity = sw.world.get_entity(self.entity_id).expect("incoherent player entity");
+        if entity.0.vehicle_id.is_some() {
+            pos = None;
+            if self.pos != entity.0.pos {
+                self.pos = entity.0.pos;
+                self.update_chunks(sw);
+            }
+        }
+
+        // Reject invalid or too large moves (more than 10 blocks), the client is sent 
+        // back to its last known position.
+        if let Some(pos) = pos {
+            if !pos.is_finite() || pos.distance_squared(self.pos) > 100.0 {
+                warn!("from {}, moved too quickly from {} to {pos}", self.username, self.pos);
+                self.teleport(sw, self.pos);
+                return;
+            }
+        }
 
         let entity = sw.world.get_entity_mut(self.entity_id).expect("incoherent player entity");
         entity.0.on_ground = on_ground;
@@ -269,6 +441,26 @@ impl ServerPlayer {
 
     }
 
+    /// Teleport this player and its entity to the given position, the position is sent
+    /// to the client and chunks are updated.
+    pub fn teleport(&mut self, sw: &mut ServerWorld, pos: DVec3) {
+
+        let entity = sw.world.get_entity_mut(self.entity_id).expect("incoherent player entity");
+        entity.teleport(pos);
+        self.pos = pos;
+
+        self.send(OutPacket::PositionLook(proto::PositionLookPacket {
+            pos,
+            stance: pos.y + 1.62,
+            look: Vec2::new(self.look.x.to_degrees(), self.look.y.to_degrees()),
+            on_ground: false,
+        }));
+
+        sw.world.push_event(Event::Entity { id: self.entity_id, inner: EntityEvent::Position { pos } });
+        self.update_chunks(sw);
+
+    }
+
     /// Handle a break block packet.
     fn handle_break_block(&mut self, sw: &mut ServerWorld, packet: proto::BreakBlockPacket) {
         
@@ -285,11 +477,18 @@ impl ServerPlayer {
         let Some(entity) = sw.world.get_entity_mut(self.entity_id) else { return };
         let pos = IVec3::new(packet.x, packet.y as i32, packet.z);
 
+        // Ignore digging of blocks out of reach, 6 blocks from the eye to the block center.
+        let eye_pos = self.pos + DVec3::new(0.0, 1.62, 0.0);
+        if matches!(packet.status, 0 | 2) && eye_pos.distance_squared(pos.as_dvec3() + 0.5) > 36.0 {
+            warn!("from {}, break block out of reach at {pos}", self.username);
+            return;
+        }
+
         // TODO: Use server time for breaking blocks.
 
         let in_water = entity.0.in_water;
         let on_ground = entity.0.on_ground;
-        let mut stack = self.main_inv[self.hand_slot as usize];
+        let mut stack = self.inv.get_hand();
 
         if packet.status == 0 {
 
@@ -315,7 +514,9 @@ impl ServerPlayer {
                 if break_duration.is_infinite() {
                     // Do nothing, the block is unbreakable.
                 } else if break_duration == 0.0 {
-                    sw.world.break_block(pos);
+                    if sw.world.break_block_with(pos, stack.id).is_some() && is_tool(stack.id) {
+                        self.damage_hand(1);
+                    }
                 } else {
                     self.breaking_block = Some(BreakingBlock {
                         start_time: sw.world.get_time(), // + (break_duration * 0.7) as u64,
@@ -333,7 +534,9 @@ impl ServerPlayer {
                     let break_duration = sw.world.get_break_duration(stack.id, state.id, in_water, on_ground);
                     let min_time = state.start_time + (break_duration * 0.7) as u64;
                     if sw.world.get_time() >= min_time {
-                        sw.world.break_block(pos);
+                        if sw.world.break_block_with(pos, stack.id).is_some() && is_tool(stack.id) {
+                            self.damage_hand(1);
+                        }
                     } else {
                         warn!("from {}, incoherent break time, expected {min_time} but got {}", self.username, sw.world.get_time());
                     }
@@ -347,11 +550,11 @@ impl ServerPlayer {
             if !stack.is_empty() {
                 
                 stack.size -= 1;
-                self.main_inv[self.hand_slot as usize] = stack.to_non_empty().unwrap_or_default();
+                self.inv.set_hand(stack.to_non_empty().unwrap_or_default());
                 
                 self.send(OutPacket::WindowSetItem(proto::WindowSetItemPacket {
                     window_id: 0,
-                    slot: 36 + self.hand_slot as i16,
+                    slot: 36 + self.inv.get_hand_slot() as i16,
                     stack: stack.to_non_empty(),
                 }));
 
@@ -383,8 +586,8 @@ impl ServerPlayer {
             z: packet.z,
         };
 
-        let mut inv = InventoryHandle::new(&mut self.main_inv[..]);
-        let inv_index = self.hand_slot as usize;
+        let inv_index = self.inv.get_hand_slot() as usize;
+        let mut inv = InventoryHandle::new(&mut self.inv.main[..]);
 
         // Check if the player is reasonably near the block.
         if face.is_none() || self.pos.distance_squared(pos.as_dvec3() + 0.5) < 64.0 {
@@ -408,6 +611,8 @@ impl ServerPlayer {
                         return self.open_window(sw, WindowKind::Dispenser { pos });
                     }
                     Interaction::Handled => {}
+                    // Only returned when interacting with entities.
+                    Interaction::ChestMinecart { .. } => {}
                 }
             } else {
                 sw.world.use_raw_stack(&mut inv, inv_index, self.entity_id);
@@ -426,7 +631,7 @@ impl ServerPlayer {
 
             // If the previous item was a fishing rod, then we ensure that the bobber id
             // is unset from the player's entity, so that the bobber will be removed.
-            let prev_stack = self.main_inv[self.hand_slot as usize];
+            let prev_stack = self.inv.get_hand();
             if prev_stack.size != 0 && prev_stack.id == item::FISHING_ROD {
                 if prev_stack.id == item::FISHING_ROD {
 
@@ -436,7 +641,7 @@ impl ServerPlayer {
                 }
             }
 
-            self.hand_slot = slot as u8;
+            self.inv.set_hand_slot(slot as u8);
 
         } else {
             warn!("from {}, invalid hand slot: {slot}", self.username);
@@ -490,6 +695,7 @@ impl ServerPlayer {
                 WindowKind::Chest { ref pos } => slot.checked_sub(pos.len() * 27),
                 WindowKind::Furnace { .. } => slot.checked_sub(3),
                 WindowKind::Dispenser { .. } => slot.checked_sub(9),
+                WindowKind::ChestMinecart { .. } => slot.checked_sub(27),
             };
 
             // From the slot number, we get the index in the main inventory stacks.
@@ -502,7 +708,7 @@ impl ServerPlayer {
             };
             
             // Create a handle to the main inventory.
-            let mut main_inv = InventoryHandle::new(&mut self.main_inv[..]);
+            let mut main_inv = InventoryHandle::new(&mut self.inv.main[..]);
 
             // Each window kind has a different handling of shift click...
             match self.window.kind {
@@ -521,7 +727,7 @@ impl ServerPlayer {
                             slot_stack = result_stack;
                             if main_inv.can_push(result_stack) {
 
-                                self.craft_tracker.consume(&mut self.craft_inv);
+                                self.craft_tracker.consume(&mut self.inv.craft);
 
                                 main_inv.push_back_in(&mut result_stack, 0..9);
                                 main_inv.push_back_in(&mut result_stack, 9..36);
@@ -544,8 +750,8 @@ impl ServerPlayer {
 
                         // Craft matrix
                         let stack = match slot {
-                            1 | 2 => &mut self.craft_inv[slot - 1],
-                            3 | 4 => &mut self.craft_inv[slot],
+                            1 | 2 => &mut self.inv.craft[slot - 1],
+                            3 | 4 => &mut self.inv.craft[slot],
                             _ => unreachable!()
                         };
 
@@ -560,7 +766,7 @@ impl ServerPlayer {
 
                     } else {
                         // Armor
-                        let stack = &mut self.armor_inv[slot - 5];
+                        let stack = &mut self.inv.armor[slot - 5];
                         slot_stack = *stack;
                         main_inv.push_front_in(stack, 9..36);
                         main_inv.push_front_in(stack, 0..9);
@@ -583,7 +789,7 @@ impl ServerPlayer {
                             slot_stack = result_stack;
                             if main_inv.can_push(result_stack) {
 
-                                self.craft_tracker.consume(&mut self.craft_inv);
+                                self.craft_tracker.consume(&mut self.inv.craft);
 
                                 main_inv.push_back_in(&mut result_stack, 0..9);
                                 main_inv.push_back_in(&mut result_stack, 9..36);
@@ -605,7 +811,7 @@ impl ServerPlayer {
                     } else {
 
                         // Craft matrix
-                        let stack = &mut self.craft_inv[slot - 1];
+                        let stack = &mut self.inv.craft[slot - 1];
 
                         slot_stack = *stack;
                         main_inv.push_front_in(stack, 9..36);
@@ -741,6 +947,64 @@ impl ServerPlayer {
                     slot_notify = SlotNotify::None;
 
                 }
+                WindowKind::ChestMinecart { id } => {
+
+                    // Temporarily swap events out to avoid borrowing issues.
+                    let mut events = sw.world.swap_events(None);
+
+                    let Some(Entity(_, BaseKind::Minecart(e::Minecart { kind: e::MinecartKind::Chest { inv }, .. }))) = sw.world.get_entity_mut(id) else {
+                        sw.world.swap_events(events);
+                        return;
+                    };
+
+                    if let Some(main_index) = main_index {
+
+                        // From hotbar/inventory to the minecart.
+                        slot_stack = main_inv.get(main_index);
+                        let mut stack = slot_stack;
+
+                        let mut minecart_inv = InventoryHandle::new(&mut inv[..]);
+                        minecart_inv.push_front(&mut stack);
+
+                        // Push all changes in the minecart inventory as world event.
+                        if let Some(events) = &mut events {
+                            for index in minecart_inv.iter_changes() {
+                                events.push(Event::Entity { 
+                                    id, 
+                                    inner: EntityEvent::Storage { 
+                                        index: index as u8, 
+                                        stack: minecart_inv.get(index),
+                                    },
+                                });
+                            }
+                        }
+
+                        main_inv.set(main_index, stack);
+                        // No notify because we handled the events for the minecart.
+                        slot_notify = SlotNotify::None;
+
+                    } else {
+
+                        // From the minecart to hotbar/inventory.
+                        let stack = &mut inv[slot];
+                        slot_stack = *stack;
+                        if !stack.is_empty() {
+                            main_inv.push_back_in(stack, 0..9);
+                            main_inv.push_back_in(stack, 9..36);
+                        }
+
+                        slot_notify = SlotNotify::EntityStorageEvent { 
+                            id, 
+                            index: slot as u8, 
+                            stack: Some(*stack),
+                        };
+
+                    }
+
+                    // Swap events back in.
+                    sw.world.swap_events(events);
+
+                }
             }
 
         } else {
@@ -844,7 +1108,7 @@ impl ServerPlayer {
                 modified: true,
             } => {
 
-                self.craft_tracker.update(&self.craft_inv);
+                self.craft_tracker.update(&self.inv.craft);
                 
                 self.net.send(self.client, OutPacket::WindowSetItem(proto::WindowSetItemPacket {
                     window_id: packet.window_id,
@@ -858,7 +1122,7 @@ impl ServerPlayer {
                             self.net.send(self.client, OutPacket::WindowSetItem(proto::WindowSetItemPacket {
                                 window_id: packet.window_id,
                                 slot,
-                                stack: self.craft_inv[index].to_non_empty(),
+                                stack: self.inv.craft[index].to_non_empty(),
                             }));
                         }
                     }
@@ -878,6 +1142,19 @@ impl ServerPlayer {
                     },
                 });
             }
+            SlotNotify::EntityStorageEvent { 
+                id,
+                index, 
+                stack: Some(stack),
+            } => {
+                sw.world.push_event(Event::Entity { 
+                    id, 
+                    inner: EntityEvent::Storage { 
+                        index, 
+                        stack,
+                    },
+                });
+            }
             _ => {}
         }
             
@@ -894,6 +1171,11 @@ impl ServerPlayer {
             warn!("from {}, incoherent item at {} in window {}", self.username, packet.slot, packet.window_id);
         }
 
+        // Armor slots of the player window are also equipped on the player entity.
+        if let (WindowKind::Player, 5..=8) = (&self.window.kind, packet.slot) {
+            self.sync_entity_armor(sw);
+        }
+
         if cursor_stack != self.cursor_stack || !accepted {
 
             // Send the new cursor item.
@@ -929,7 +1211,7 @@ impl ServerPlayer {
             warn!("from {}, incoherent interact entity: {}, expected: {}", self.username, packet.player_entity_id, self.entity_id);
         }
 
-        let Some(Entity(target_base, _)) = sw.world.get_entity_mut(packet.target_entity_id) else {
+        let Some(Entity(target_base, _)) = sw.world.get_entity(packet.target_entity_id) else {
             warn!("from {}, incoherent interact entity target: {}", self.username, packet.target_entity_id);
             return;
         };
@@ -939,20 +1221,33 @@ impl ServerPlayer {
             return;
         }
 
-        let hand_stack = self.main_inv[self.hand_slot as usize];
+        let hand_stack = self.inv.get_hand();
 
         if packet.left_click {
 
             // TODO: Critical damage if vel.y < 0
 
             let damage = item::attack::get_base_damage(hand_stack.id);
-            target_base.hurt.push(Hurt {
-                damage,
-                origin_id: Some(self.entity_id),
-            });
+            sw.world.damage_entity(packet.target_entity_id, damage, Some(self.entity_id));
+
+            if is_sword(hand_stack.id) {
+                self.damage_hand(1);
+            }
 
         } else {
-            
+
+            let inv_index = self.inv.get_hand_slot() as usize;
+            let mut inv = InventoryHandle::new(&mut self.inv.main[..]);
+            let interaction = sw.world.interact_entity(&mut inv, inv_index, self.entity_id, packet.target_entity_id);
+
+            for index in inv.iter_changes() {
+                self.send_main_inv_item(index);
+            }
+
+            if let Interaction::ChestMinecart { id } = interaction {
+                self.open_window(sw, WindowKind::ChestMinecart { id });
+            }
+
         }
 
     }
@@ -1099,6 +1394,23 @@ impl ServerPlayer {
                 }
 
             }
+            WindowKind::ChestMinecart { id } => {
+
+                self.send(OutPacket::WindowOpen(proto::WindowOpenPacket {
+                    window_id,
+                    inventory_type: 0,
+                    title: "Minecart".to_string(),
+                    slots_count: 27,
+                }));
+
+                if let Some(Entity(_, BaseKind::Minecart(e::Minecart { kind: e::MinecartKind::Chest { inv }, .. }))) = sw.world.get_entity(id) {
+                    self.send(OutPacket::WindowItems(proto::WindowItemsPacket {
+                        window_id,
+                        stacks: inv.iter().map(|stack| stack.to_non_empty()).collect()
+                    }));
+                }
+
+            }
         };
 
         self.window.id = window_id;
@@ -1120,7 +1432,7 @@ impl ServerPlayer {
         // For any closed inventory, we drop the cursor stack and crafting matrix.
         let mut drop_stacks = Vec::new();
         drop_stacks.extend(self.cursor_stack.take_non_empty());
-        for stack in self.craft_inv.iter_mut() {
+        for stack in self.inv.craft.iter_mut() {
             drop_stacks.extend(stack.take_non_empty());
         }
 
@@ -1163,7 +1475,7 @@ impl ServerPlayer {
 
         Some(SlotHandle {
             kind: SlotKind::Standard { 
-                stack: &mut self.main_inv[index],
+                stack: &mut self.inv.main[index],
                 access: SlotAccess::PickupDrop, 
                 max_size: 64,
             },
@@ -1187,7 +1499,7 @@ impl ServerPlayer {
                 match slot {
                     0 => SlotHandle {
                         kind: SlotKind::CraftingResult { 
-                            craft_inv: &mut self.craft_inv, 
+                            craft_inv: &mut self.inv.craft, 
                             craft_tracker: &mut self.craft_tracker,
                         },
                         notify: SlotNotify::Craft { 
@@ -1197,7 +1509,7 @@ impl ServerPlayer {
                     },
                     1..=4 => SlotHandle { 
                         kind: SlotKind::Standard { 
-                            stack: &mut self.craft_inv[match slot {
+                            stack: &mut self.inv.craft[match slot {
                                 1 => 0,
                                 2 => 1,
                                 3 => 3,
@@ -1214,7 +1526,7 @@ impl ServerPlayer {
                     },
                     5..=8 => SlotHandle { 
                         kind: SlotKind::Standard { 
-                            stack: &mut self.armor_inv[slot as usize - 5], 
+                            stack: &mut self.inv.armor[slot as usize - 5], 
                             access: match slot {
                                 5 => SlotAccess::ArmorHelmet,
                                 6 => SlotAccess::ArmorChestplate,
@@ -1232,7 +1544,7 @@ impl ServerPlayer {
                 match slot {
                     0 => SlotHandle {
                         kind: SlotKind::CraftingResult { 
-                            craft_inv: &mut self.craft_inv, 
+                            craft_inv: &mut self.inv.craft, 
                             craft_tracker: &mut self.craft_tracker,
                         },
                         notify: SlotNotify::Craft {
@@ -1242,7 +1554,7 @@ impl ServerPlayer {
                     },
                     1..=9 => SlotHandle { 
                         kind: SlotKind::Standard { 
-                            stack: &mut self.craft_inv[slot as usize - 1], 
+                            stack: &mut self.inv.craft[slot as usize - 1], 
                             access: SlotAccess::PickupDrop,
                             max_size: 64,
                         },
@@ -1343,6 +1655,32 @@ impl ServerPlayer {
                 }
 
             }
+            WindowKind::ChestMinecart { id } => {
+
+                if slot < 27 {
+
+                    let Some(Entity(_, BaseKind::Minecart(e::Minecart { kind: e::MinecartKind::Chest { inv }, .. }))) = sw.world.get_entity_mut(id) else {
+                        return None
+                    };
+
+                    SlotHandle {
+                        kind: SlotKind::Standard { 
+                            stack: &mut inv[slot as usize], 
+                            access: SlotAccess::PickupDrop,
+                            max_size: 64,
+                        },
+                        notify: SlotNotify::EntityStorageEvent { 
+                            id, 
+                            index: slot as u8, 
+                            stack: None,
+                        },
+                    }
+
+                } else {
+                    self.make_player_window_slot_handle(slot, 27)?
+                }
+
+            }
         })
 
     }
@@ -1358,9 +1696,34 @@ impl ServerPlayer {
         self.send(OutPacket::WindowSetItem(proto::WindowSetItemPacket {
             window_id: 0,
             slot: slot as i16,
-            stack: self.main_inv[index].to_non_empty(),
+            stack: self.inv.main[index].to_non_empty(),
+        }));
+
+    }
+
+    /// Send the armor inventory item at given index to the client.
+    fn send_armor_inv_item(&self, index: usize) {
+        self.send(OutPacket::WindowSetItem(proto::WindowSetItemPacket {
+            window_id: 0,
+            slot: 5 + index as i16,
+            stack: self.inv.armor[index].to_non_empty(),
         }));
+    }
+
+    /// Copy the armor inventory to the player entity, this must be called each time the
+    /// armor inventory is modified so that the entity is protected by the same armor.
+    fn sync_entity_armor(&self, sw: &mut ServerWorld) {
+        if let Some(Entity(_, BaseKind::Living(_, LivingKind::Human(human)))) = sw.world.get_entity_mut(self.entity_id) {
+            human.armor = *self.inv.armor;
+        }
+    }
 
+    /// Damage the item stack in hand by the given amount, the stack is destroyed if its
+    /// damage exceeds the item's max damage. The updated hand slot is sent to the client.
+    fn damage_hand(&mut self, amount: u16) {
+        let stack = self.inv.get_hand().inc_damage(amount);
+        self.inv.set_hand(stack.to_non_empty().unwrap_or_default());
+        self.send_main_inv_item(self.inv.get_hand_slot() as usize);
     }
 
     /// Drop an item from the player's entity, items are drop in front of the player, but
@@ -1409,52 +1772,76 @@ impl ServerPlayer {
 
     }
 
-    /// Update the chunks sent to this player.
+    /// Return the set of chunks that should be viewed by this player, given a view
+    /// distance in chunks around the chunk of the player.
+    pub fn view_chunks(&self, view_distance: u8) -> HashSet<(i32, i32)> {
+        let (ocx, ocz) = chunk::calc_entity_chunk_pos(self.pos);
+        let dist = view_distance as i32;
+        (ocx - dist..=ocx + dist)
+            .flat_map(|cx| (ocz - dist..=ocz + dist).map(move |cz| (cx, cz)))
+            .collect()
+    }
+
+    /// Diff the chunks already sent to this player against the given view set, this
+    /// returns the chunks that should be sent and the chunks that should be dropped by
+    /// the client. The tracked chunks are not modified by this function.
+    pub fn diff_tracked_chunks(&self, view: &HashSet<(i32, i32)>) -> ChunksDiff {
+        ChunksDiff {
+            send: view.difference(&self.tracked_chunks).copied().collect(),
+            drop: self.tracked_chunks.difference(view).copied().collect(),
+        }
+    }
+
+    /// Update the chunks sent to this player, new chunks in view are sent if loaded in
+    /// the world and chunks that are no longer in view are unloaded client-side.
     pub fn update_chunks(&mut self, sw: &ServerWorld) {
 
-        let (ocx, ocz) = chunk::calc_entity_chunk_pos(self.pos);
-        let view_range = 3;
+        let view = self.view_chunks(config::view_distance());
+        let diff = self.diff_tracked_chunks(&view);
 
-        for cx in (ocx - view_range)..(ocx + view_range) {
-            for cz in (ocz - view_range)..(ocz + view_range) {
+        for (cx, cz) in diff.drop {
+            self.tracked_chunks.remove(&(cx, cz));
+            self.send(OutPacket::ChunkState(proto::ChunkStatePacket {
+                cx, cz, init: false
+            }));
+        }
 
-                if let Some(chunk) = sw.world.get_chunk(cx, cz) {
-                    if self.tracked_chunks.insert((cx, cz)) {
+        for (cx, cz) in diff.send {
 
-                        self.send(OutPacket::ChunkState(proto::ChunkStatePacket {
-                            cx, cz, init: true
-                        }));
+            // Chunks that are not yet loaded will be sent on a future update.
+            let Some(chunk) = sw.world.get_chunk(cx, cz) else { continue };
+            self.tracked_chunks.insert((cx, cz));
 
-                        let from = IVec3 {
-                            x: cx * 16,
-                            y: 0,
-                            z: cz * 16,
-                        };
+            self.send(OutPacket::ChunkState(proto::ChunkStatePacket {
+                cx, cz, init: true
+            }));
 
-                        let size = IVec3 { 
-                            x: 16, 
-                            y: 128, 
-                            z: 16,
-                        };
+            let from = IVec3 {
+                x: cx * 16,
+                y: 0,
+                z: cz * 16,
+            };
 
-                        self.send(OutPacket::ChunkData(new_chunk_data_packet(chunk, from, size)));
+            let size = IVec3 { 
+                x: 16, 
+                y: 128, 
+                z: 16,
+            };
 
-                    }
-                }
+            self.send(OutPacket::ChunkData(new_chunk_data_packet(chunk, from, size)));
 
-                // Search signs block entities in chunk.
-                for (pos, block_entity) in sw.world.iter_block_entities_in_chunk(cx, cz) {
-                    if let BlockEntity::Sign(sign) = block_entity {
-                        self.send(OutPacket::UpdateSign(proto::UpdateSignPacket {
-                            x: pos.x,
-                            y: pos.y as i16,
-                            z: pos.z,
-                            lines: sign.lines.clone(),
-                        }));
-                    }
+            // Search signs block entities in chunk.
+            for (pos, block_entity) in sw.world.iter_block_entities_in_chunk(cx, cz) {
+                if let BlockEntity::Sign(sign) = block_entity {
+                    self.send(OutPacket::UpdateSign(proto::UpdateSignPacket {
+                        x: pos.x,
+                        y: pos.y as i16,
+                        z: pos.z,
+                        lines: sign.lines.clone(),
+                    }));
                 }
-
             }
+
         }
 
     }
@@ -1463,11 +1850,8 @@ impl ServerPlayer {
     /// regarding the amount actually picked up.
     pub fn pickup_stack(&mut self, stack: &mut ItemStack) {
         
-        let mut inv = InventoryHandle::new(&mut self.main_inv[..]);
-        inv.push_front(stack);
-
         // Update the associated slots in the player inventory.
-        for index in inv.iter_changes() {
+        for index in self.inv.add(stack) {
             self.send_main_inv_item(index);
         }
 
@@ -1485,6 +1869,7 @@ impl ServerPlayer {
                 pos == target_pos,
             WindowKind::Chest { ref pos } => 
                 pos.iter().any(|&pos| pos == target_pos),
+            WindowKind::ChestMinecart { .. } => false,
         };
 
         if contains {
@@ -1493,6 +1878,16 @@ impl ServerPlayer {
 
     }
 
+    /// For the given entity id, close any window that may be linked to it. This is 
+    /// usually called when the chest minecart entity is removed.
+    pub fn close_entity_window(&mut self, sw: &mut ServerWorld, target_id: u32) {
+        if let WindowKind::ChestMinecart { id } = self.window.kind {
+            if id == target_id {
+                self.close_window(sw, None, true);
+            }
+        }
+    }
+
     /// If this player has a window opened for the given position, this will update the
     /// displayed storage according to the given storage event.
     pub fn update_block_window_storage(&mut self, target_pos: IVec3, storage: BlockEntityStorage, stack: ItemStack) {
@@ -1546,6 +1941,31 @@ impl ServerPlayer {
         }
     }
 
+    /// If this player has a window opened for the given entity, this will update the
+    /// displayed storage according to the given storage event.
+    pub fn update_entity_window_storage(&mut self, target_id: u32, index: u8, stack: ItemStack) {
+        if let WindowKind::ChestMinecart { id } = self.window.kind {
+            if id == target_id {
+                self.send(OutPacket::WindowSetItem(proto::WindowSetItemPacket {
+                    window_id: self.window.id,
+                    slot: index as i16,
+                    stack: stack.to_non_empty(),
+                }));
+            }
+        }
+    }
+
+    /// If the given entity is the entity of this player, this will update the armor
+    /// inventory according to the given armor event, such as when armor is damaged.
+    pub fn update_armor_storage(&mut self, target_id: u32, index: u8, stack: ItemStack) {
+        if self.entity_id == target_id {
+            if let Some(armor_stack) = self.inv.armor.get_mut(index as usize) {
+                *armor_stack = stack;
+                self.send_armor_inv_item(index as usize);
+            }
+        }
+    }
+
     /// If this player has a window opened for the given position, this will update the
     /// displayed storage according to the given storage event.
     pub fn update_block_window_progress(&mut self, target_pos: IVec3, progress: BlockEntityProgress, value: u16) {
@@ -1642,7 +2062,16 @@ enum SlotNotify {
         storage: BlockEntityStorage,
         /// If the stack is actually modified, this is the new item stack at the index.
         stack: Option<ItemStack>,
-    }
+    },
+    /// An entity storage event need to be pushed to the world.
+    EntityStorageEvent {
+        /// The id of the entity owning the inventory.
+        id: u32,
+        /// The index of the inventory stack that is modified.
+        index: u8,
+        /// If the stack is actually modified, this is the new item stack at the index.
+        stack: Option<ItemStack>,
+    },
 }
 
 impl<'a> SlotHandle<'a> {
@@ -1695,7 +2124,8 @@ impl<'a> SlotHandle<'a> {
         match &mut self.notify {
             SlotNotify::None => {}
             SlotNotify::Craft { modified, .. } => *modified = true,
-            SlotNotify::BlockEntityStorageEvent { stack, .. } => *stack = Some(new_stack),
+            SlotNotify::BlockEntityStorageEvent { stack, .. } |
+            SlotNotify::EntityStorageEvent { stack, .. } => *stack = Some(new_stack),
         }
 
     }
@@ -1736,3 +2166,432 @@ impl SlotAccess {
     }
 
 }
+
+/// Return true if the given item is a tool that takes damage when breaking blocks.
+fn is_tool(id: u16) -> bool {
+    matches!(id,
+        item::WOOD_PICKAXE | item::STONE_PICKAXE | item::IRON_PICKAXE | item::GOLD_PICKAXE | item::DIAMOND_PICKAXE |
+        item::WOOD_AXE | item::STONE_AXE | item::IRON_AXE | item::GOLD_AXE | item::DIAMOND_AXE |
+        item::WOOD_SHOVEL | item::STONE_SHOVEL | item::IRON_SHOVEL | item::GOLD_SHOVEL | item::DIAMOND_SHOVEL)
+}
+
+/// Return true if the given item is a sword that takes damage when hitting entities.
+fn is_sword(id: u16) -> bool {
+    matches!(id, item::WOOD_SWORD | item::STONE_SWORD | item::IRON_SWORD | item::GOLD_SWORD | item::DIAMOND_SWORD)
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use mc173::chunk::Chunk;
+
+    use crate::test::Test;
+
+    use super::*;
+
+    #[test]
+    fn inventory_add() {
+
+        let mut inv = Inventory::new();
+        inv.main[0] = ItemStack::new_block_sized(block::DIRT, 0, 60);
+        inv.main[2] = ItemStack::new_block_sized(block::DIRT, 0, 50);
+        inv.main[3] = ItemStack::new_block_sized(block::STONE, 0, 10);
+
+        let mut stack = ItemStack::new_block_sized(block::DIRT, 0, 30);
+        let changes = inv.add(&mut stack).collect::<Vec<_>>();
+
+        assert!(stack.is_empty());
+        assert_eq!(changes, [0, 1, 2]);
+        assert_eq!(inv.main[0].size, 64);
+        assert_eq!(inv.main[1], ItemStack::new_block_sized(block::DIRT, 0, 12));
+        assert_eq!(inv.main[2].size, 64);
+        assert_eq!(inv.main[3], ItemStack::new_block_sized(block::STONE, 0, 10));
+
+        // Fill the inventory, only one slot remaining.
+        for stack in &mut inv.main[4..] {
+            *stack = ItemStack::new_block_sized(block::STONE, 0, 64);
+        }
+
+        let mut stack = ItemStack::new_block_sized(block::DIRT, 0, 100);
+        inv.add(&mut stack);
+        assert_eq!(inv.main[1].size, 64);
+        assert_eq!(stack.size, 48);
+
+        let mut stack = ItemStack::new_block_sized(block::STONE, 0, 100);
+        inv.add(&mut stack);
+        assert_eq!(inv.main[3].size, 64);
+        assert_eq!(stack.size, 46);
+
+    }
+
+    #[test]
+    fn inventory_remove_swap() {
+
+        let mut inv = Inventory::new();
+        inv.main[5] = ItemStack::new_block_sized(block::DIRT, 0, 10);
+
+        assert_eq!(inv.remove(5, 4), ItemStack::new_block_sized(block::DIRT, 0, 4));
+        assert_eq!(inv.main[5].size, 6);
+        assert_eq!(inv.remove(5, 10), ItemStack::new_block_sized(block::DIRT, 0, 6));
+        assert!(inv.main[5].is_empty());
+        assert!(inv.remove(5, 1).is_empty());
+
+        inv.main[8] = ItemStack::new_single(item::DIAMOND_PICKAXE, 0);
+        inv.swap(8, 0);
+        assert!(inv.main[8].is_empty());
+        assert_eq!(inv.get_hand(), ItemStack::new_single(item::DIAMOND_PICKAXE, 0));
+
+        inv.set_hand_slot(8);
+        assert!(inv.get_hand().is_empty());
+
+    }
+
+    #[test]
+    fn tool_durability() {
+
+        let mut test = Test::new();
+        test.world.world.set_chunk(0, 0, Chunk::new());
+        test.player.pos = DVec3::new(0.5, 66.0, 0.5);
+        test.player.instant_break = true;
+        test.player.inv.set_hand(ItemStack::new_single(item::WOOD_PICKAXE, 0));
+
+        let pos = IVec3::new(0, 64, 0);
+        let packet = proto::BreakBlockPacket { x: pos.x, y: pos.y as i8, z: pos.z, face: 1, status: 0 };
+
+        // PARITY: The stack is destroyed when its damage exceeds the max damage, so a
+        // wooden pickaxe can be used 59 times and is destroyed on the next use.
+        for _ in 0..59 {
+            test.world.world.set_block(pos, block::STONE, 0);
+            test.player.handle_break_block(&mut test.world, packet.clone());
+        }
+
+        assert_eq!(test.player.inv.get_hand(), ItemStack::new_single(item::WOOD_PICKAXE, 59));
+
+        test.world.world.set_block(pos, block::STONE, 0);
+        test.player.handle_break_block(&mut test.world, packet.clone());
+        assert!(test.player.inv.get_hand().is_empty());
+
+        // Swords are not damaged when breaking blocks.
+        test.player.inv.set_hand(ItemStack::new_single(item::WOOD_SWORD, 0));
+        test.world.world.set_block(pos, block::STONE, 0);
+        test.player.handle_break_block(&mut test.world, packet);
+        assert_eq!(test.player.inv.get_hand(), ItemStack::new_single(item::WOOD_SWORD, 0));
+
+    }
+
+    #[test]
+    fn chunks_diff() {
+
+        let mut test = Test::new();
+        test.player.pos = DVec3::new(8.0, 64.0, 8.0);
+
+        let view = test.player.view_chunks(1);
+        assert_eq!(view.len(), 9);
+
+        let diff = test.player.diff_tracked_chunks(&view);
+        assert_eq!(diff.send.len(), 9);
+        assert!(diff.drop.is_empty());
+        test.player.tracked_chunks.extend(diff.send);
+
+        // Moving into the next chunk on X should send a new column and drop the old one.
+        test.player.pos.x = 17.0;
+        let view = test.player.view_chunks(1);
+        let diff = test.player.diff_tracked_chunks(&view);
+        assert_eq!(diff.send.into_iter().collect::<HashSet<_>>(), HashSet::from([(2, -1), (2, 0), (2, 1)]));
+        assert_eq!(diff.drop.into_iter().collect::<HashSet<_>>(), HashSet::from([(-1, -1), (-1, 0), (-1, 1)]));
+
+        // Only loaded chunks are actually sent and tracked.
+        test.world.world.set_chunk(1, 0, Chunk::new());
+        test.world.world.set_chunk(2, 0, Chunk::new());
+        test.player.tracked_chunks.clear();
+        test.player.update_chunks(&test.world);
+        assert_eq!(test.player.tracked_chunks, HashSet::from([(1, 0), (2, 0)]));
+
+    }
+
+    #[test]
+    fn window_click() {
+
+        let mut test = Test::new();
+        test.player.inv.main[0] = ItemStack::new_block_sized(block::DIRT, 0, 10);
+
+        let click = |slot: i16, right_click: bool, stack: Option<ItemStack>| proto::WindowClickPacket {
+            window_id: 0,
+            slot,
+            right_click,
+            shift_click: false,
+            transaction_id: 0,
+            stack,
+        };
+
+        // Pick up the whole hotbar stack (slot 36 is hotbar 0).
+        test.player.handle_window_click(&mut test.world, click(36, false, test.player.inv.main[0].to_non_empty()));
+        assert!(test.player.inv.main[0].is_empty());
+        assert_eq!(test.player.cursor_stack, ItemStack::new_block_sized(block::DIRT, 0, 10));
+
+        // Place it into the first slot of the main inventory (slot 9).
+        test.player.handle_window_click(&mut test.world, click(9, false, None));
+        assert_eq!(test.player.inv.main[9], ItemStack::new_block_sized(block::DIRT, 0, 10));
+        assert!(test.player.cursor_stack.is_empty());
+
+        // Split the stack with a right click, then place a single item.
+        test.player.handle_window_click(&mut test.world, click(9, true, test.player.inv.main[9].to_non_empty()));
+        assert_eq!(test.player.inv.main[9].size, 5);
+        assert_eq!(test.player.cursor_stack.size, 5);
+        test.player.handle_window_click(&mut test.world, click(10, true, None));
+        assert_eq!(test.player.inv.main[10], ItemStack::new_block_sized(block::DIRT, 0, 1));
+        assert_eq!(test.player.cursor_stack.size, 4);
+
+        // Place the remaining cursor back onto the compatible stack.
+        test.player.handle_window_click(&mut test.world, click(9, false, test.player.inv.main[9].to_non_empty()));
+        assert_eq!(test.player.inv.main[9].size, 9);
+        assert!(test.player.cursor_stack.is_empty());
+
+    }
+
+    #[test]
+    fn armor_sync() {
+
+        let mut test = Test::new();
+        test.world.world.set_chunk(0, 0, Chunk::new());
+        test.player.cursor_stack = ItemStack::new_single(item::IRON_HELMET, 0);
+
+        // Equipping the helmet also equips it on the player entity.
+        test.player.handle_window_click(&mut test.world, proto::WindowClickPacket {
+            window_id: 0,
+            slot: 5,
+            right_click: false,
+            shift_click: false,
+            transaction_id: 0,
+            stack: None,
+        });
+
+        assert_eq!(test.player.inv.armor[0], ItemStack::new_single(item::IRON_HELMET, 0));
+        let Some(Entity(base, BaseKind::Living(_, LivingKind::Human(human)))) = test.world.world.get_entity_mut(test.player.entity_id) else { panic!() };
+        assert_eq!(human.armor[0], ItemStack::new_single(item::IRON_HELMET, 0));
+
+        // The armor damaged by hurting the entity is reported back to the inventory.
+        base.hurt.push(e::Hurt { damage: 4, origin_id: None });
+        test.world.tick(std::slice::from_mut(&mut test.player));
+        assert_eq!(test.player.inv.armor[0], ItemStack::new_single(item::IRON_HELMET, 4));
+
+    }
+
+    #[test]
+    fn chest_minecart_window() {
+
+        let mut test = Test::new();
+        test.world.world.set_chunk(0, 0, Chunk::new());
+        test.player.inv.main[0] = ItemStack::new_block_sized(block::DIRT, 0, 10);
+
+        let minecart_id = test.world.world.spawn_entity(e::Minecart::new_with(|base, minecart| {
+            base.pos = DVec3::new(2.5, 100.0, 0.5);
+            minecart.kind = e::MinecartKind::Chest { inv: Box::default() };
+        }));
+
+        test.player.handle_interact(&mut test.world, proto::InteractPacket {
+            player_entity_id: test.player.entity_id,
+            target_entity_id: minecart_id,
+            left_click: false,
+        });
+
+        assert!(matches!(test.player.window.kind, WindowKind::ChestMinecart { id } if id == minecart_id));
+        let window_id = test.player.window.id;
+
+        let click = |slot: i16, shift_click: bool, stack: Option<ItemStack>| proto::WindowClickPacket {
+            window_id,
+            slot,
+            right_click: false,
+            shift_click,
+            transaction_id: 0,
+            stack,
+        };
+
+        let minecart_stack = |test: &Test, index: usize| {
+            let Some(Entity(_, BaseKind::Minecart(e::Minecart { kind: e::MinecartKind::Chest { inv }, .. }))) = test.world.world.get_entity(minecart_id) else { panic!() };
+            inv[index]
+        };
+
+        // Shift click the hotbar stack (slot 54 is hotbar 0) into the minecart.
+        test.world.world.swap_events(Some(Vec::new()));
+        test.player.handle_window_click(&mut test.world, click(54, true, test.player.inv.main[0].to_non_empty()));
+        assert!(test.player.inv.main[0].is_empty());
+        assert_eq!(minecart_stack(&test, 0), ItemStack::new_block_sized(block::DIRT, 0, 10));
+        let events = test.world.world.swap_events(Some(Vec::new())).unwrap();
+        assert!(events.contains(&Event::Entity { id: minecart_id, inner: EntityEvent::Storage { index: 0, stack: ItemStack::new_block_sized(block::DIRT, 0, 10) } }));
+
+        // Pick it up and place it in another minecart slot.
+        let stack = minecart_stack(&test, 0);
+        test.player.handle_window_click(&mut test.world, click(0, false, stack.to_non_empty()));
+        test.player.handle_window_click(&mut test.world, click(5, false, None));
+        assert!(minecart_stack(&test, 0).is_empty());
+        assert_eq!(minecart_stack(&test, 5), ItemStack::new_block_sized(block::DIRT, 0, 10));
+        let events = test.world.world.swap_events(Some(Vec::new())).unwrap();
+        assert!(events.contains(&Event::Entity { id: minecart_id, inner: EntityEvent::Storage { index: 5, stack: ItemStack::new_block_sized(block::DIRT, 0, 10) } }));
+
+        // Shift click it back into the player inventory, starting from the hotbar end.
+        let stack = minecart_stack(&test, 5);
+        test.player.handle_window_click(&mut test.world, click(5, true, stack.to_non_empty()));
+        assert!(minecart_stack(&test, 5).is_empty());
+        assert_eq!(test.player.inv.main[8], ItemStack::new_block_sized(block::DIRT, 0, 10));
+
+        // The window is closed when the minecart is removed.
+        test.player.close_entity_window(&mut test.world, minecart_id);
+        assert!(matches!(test.player.window.kind, WindowKind::Player));
+
+    }
+
+    #[test]
+    fn break_block() {
+
+        let mut test = Test::new();
+        test.world.world.set_chunk(0, 0, Chunk::new());
+        test.player.pos = DVec3::new(0.5, 66.0, 0.5);
+
+        let pos = IVec3::new(0, 64, 0);
+        test.world.world.set_block(pos, block::DIRT, 0);
+        let packet = |status: u8| proto::BreakBlockPacket { x: pos.x, y: pos.y as i8, z: pos.z, face: 1, status };
+
+        // Finishing too early is ignored.
+        test.player.handle_break_block(&mut test.world, packet(0));
+        test.player.handle_break_block(&mut test.world, packet(2));
+        assert!(test.world.world.is_block(pos, block::DIRT));
+
+        // Finishing after the break duration breaks the block and drops dirt.
+        test.player.handle_break_block(&mut test.world, packet(0));
+        let time = test.world.world.get_time();
+        test.world.world.set_time(time + 100);
+        test.player.handle_break_block(&mut test.world, packet(2));
+        assert!(test.world.world.is_block(pos, block::AIR));
+
+        let dropped = test.world.world.iter_entities().any(|(_, entity)| {
+            matches!(entity, Entity(_, BaseKind::Item(item)) if item.stack.id == block::DIRT as u16)
+        });
+        assert!(dropped);
+
+        // Blocks out of reach cannot be broken, even instantly.
+        test.player.instant_break = true;
+        let far_pos = IVec3::new(0, 64, 10);
+        test.world.world.set_block(far_pos, block::DIRT, 0);
+        test.player.handle_break_block(&mut test.world, proto::BreakBlockPacket { 
+            x: far_pos.x, y: far_pos.y as i8, z: far_pos.z, face: 1, status: 0 
+        });
+        assert!(test.world.world.is_block(far_pos, block::DIRT));
+
+        // The reach is computed from the eye, so blocks above can be further from feet.
+        let above_pos = IVec3::new(0, 72, 0);
+        test.world.world.set_block(above_pos, block::DIRT, 0);
+        test.player.handle_break_block(&mut test.world, proto::BreakBlockPacket { 
+            x: above_pos.x, y: above_pos.y as i8, z: above_pos.z, face: 0, status: 0 
+        });
+        assert!(test.world.world.is_block(above_pos, block::AIR));
+
+    }
+
+    #[test]
+    fn place_block() {
+
+        let mut test = Test::new();
+        test.world.world.set_chunk(0, 0, Chunk::new());
+        test.player.pos = DVec3::new(0.5, 67.0, 0.5);
+        test.player.inv.set_hand(ItemStack::new_block_sized(block::STONE, 0, 10));
+
+        let pos = IVec3::new(0, 64, 0);
+        test.world.world.set_block(pos, block::STONE, 0);
+
+        // Place against the top face of the block.
+        test.player.handle_place_block(&mut test.world, proto::PlaceBlockPacket {
+            x: pos.x, y: pos.y as i8, z: pos.z, direction: 1, stack: None,
+        });
+        assert!(test.world.world.is_block(pos + IVec3::Y, block::STONE));
+        assert_eq!(test.player.inv.get_hand(), ItemStack::new_block_sized(block::STONE, 0, 9));
+
+        // Using a snowball in the air throws it.
+        test.player.inv.set_hand(ItemStack::new_sized(item::SNOWBALL, 0, 2));
+        let entity_count = test.world.world.get_entity_count();
+        test.player.handle_place_block(&mut test.world, proto::PlaceBlockPacket {
+            x: -1, y: -1, z: -1, direction: 0xFF, stack: None,
+        });
+        assert_eq!(test.world.world.get_entity_count(), entity_count + 1);
+        assert_eq!(test.player.inv.get_hand(), ItemStack::new_sized(item::SNOWBALL, 0, 1));
+
+    }
+
+    #[test]
+    fn position() {
+
+        let mut test = Test::new();
+        test.world.world.set_chunk(0, 0, Chunk::new());
+        let origin = test.player.pos;
+
+        let new_pos = origin + DVec3::new(2.0, 0.0, 1.0);
+        test.player.handle_position(&mut test.world, proto::PositionPacket { 
+            pos: new_pos, stance: new_pos.y + 1.62, on_ground: false,
+        });
+        
+        let entity = test.world.world.get_entity(test.player.entity_id).unwrap();
+        assert_eq!(test.player.pos, new_pos);
+        assert_eq!(entity.0.pos, new_pos);
+        assert_eq!(entity.0.bb.center_x(), new_pos.x);
+
+        // Too large jumps are rejected.
+        let far_pos = new_pos + DVec3::new(50.0, 0.0, 0.0);
+        test.player.handle_position(&mut test.world, proto::PositionPacket { 
+            pos: far_pos, stance: far_pos.y + 1.62, on_ground: false,
+        });
+        assert_eq!(test.player.pos, new_pos);
+        assert_eq!(test.world.world.get_entity(test.player.entity_id).unwrap().0.pos, new_pos);
+
+        // Invalid positions are also rejected.
+        test.player.handle_position(&mut test.world, proto::PositionPacket { 
+            pos: DVec3::NAN, stance: 0.0, on_ground: false,
+        });
+        assert_eq!(test.player.pos, new_pos);
+
+    }
+
+    #[test]
+    fn respawn() {
+
+        let mut test = Test::new();
+        test.world.world.set_chunk(0, 0, Chunk::new());
+        test.world.world.set_spawn_pos(IVec3::new(3, 64, 5));
+
+        // Respawn is ignored while alive.
+        let origin = test.player.pos;
+        test.player.handle(&mut test.world, InPacket::Respawn(proto::RespawnPacket { dimension: 0 }));
+        assert_eq!(test.player.pos, origin);
+
+        let Some(Entity(base, BaseKind::Living(living, _))) = test.world.world.get_entity_mut(test.player.entity_id) else { panic!() };
+        living.health = 0;
+        living.death_time = 30;
+        base.fire_time = 100;
+        base.fall_distance = 12.0;
+        base.air_time = 0;
+
+        // The player entity should not be removed while dead.
+        test.world.world.tick();
+        assert!(test.world.world.get_entity(test.player.entity_id).is_some());
+
+        test.world.world.swap_events(Some(Vec::new()));
+        test.player.handle(&mut test.world, InPacket::Respawn(proto::RespawnPacket { dimension: 0 }));
+
+        // The entity is spawned again for other players.
+        let events = test.world.world.swap_events(Some(Vec::new())).unwrap();
+        assert!(events.contains(&Event::Entity { id: test.player.entity_id, inner: EntityEvent::Spawn }));
+
+        let spawn_pos = DVec3::new(3.5, 64.0, 5.5);
+        assert_eq!(test.player.pos, spawn_pos);
+
+        let Some(Entity(base, BaseKind::Living(living, _))) = test.world.world.get_entity(test.player.entity_id) else { panic!() };
+        assert_eq!(base.pos, spawn_pos);
+        assert_eq!(living.health, 20);
+        assert_eq!(living.death_time, 0);
+        assert_eq!(base.fire_time, 0);
+        assert_eq!(base.fall_distance, 0.0);
+        assert_eq!(base.air_time, 300);
+
+    }
+
+}
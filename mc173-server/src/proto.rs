@@ -11,6 +11,7 @@ use byteorder::{ReadBytesExt, WriteBytesExt};
 use mc173::io::{ReadJavaExt, WriteJavaExt};
 use mc173::util::split_at_utf8_boundary;
 use mc173::item::ItemStack;
+use mc173::chunk::Chunk;
 
 use crate::net;
 
@@ -1297,6 +1298,27 @@ impl net::OutPacket for OutPacket {
 }
 
 
+/// Encode the given area of a chunk to the deflated data expected by the chunk data 
+/// packet. The data is composed of the block ids, followed by metadata, block light and
+/// sky light nibble arrays. Because nibbles are packed by two on the Y axis, the given
+/// `from` and `size` may be adjusted to an even Y range, the adjusted values must be 
+/// used in the packet.
+pub fn encode_chunk_data(chunk: &Chunk, from: &mut IVec3, size: &mut IVec3) -> Vec<u8> {
+
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+
+    debug_assert!(size.x != 0 && size.y != 0 && size.z != 0);
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::fast());
+    chunk.write_data(&mut encoder, from, size).unwrap();
+
+    debug_assert!(size.x != 0 && size.y != 0 && size.z != 0);
+
+    encoder.finish().unwrap()
+
+}
+
 /// Return an invalid data io error with specific message.
 fn new_invalid_packet_err(format: Arguments) -> io::Error {
     io::Error::new(io::ErrorKind::InvalidData, format!("invalid packet: {format}"))
@@ -1365,3 +1387,52 @@ fn write_metadata_list(write: &mut impl Write, list: &[Metadata]) -> io::Result<
     }
     write.write_java_byte(127)
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::io::Read;
+
+    use flate2::read::ZlibDecoder;
+
+    use super::*;
+
+    /// Decompress the given chunk data and return its uncompressed length.
+    fn uncompressed_len(data: &[u8]) -> usize {
+        let mut buf = Vec::new();
+        ZlibDecoder::new(data).read_to_end(&mut buf).unwrap();
+        buf.len()
+    }
+
+    #[test]
+    fn chunk_data_full() {
+
+        let chunk = Chunk::new();
+        let mut from = IVec3::ZERO;
+        let mut size = IVec3::new(16, 128, 16);
+        let data = encode_chunk_data(&chunk, &mut from, &mut size);
+
+        assert_eq!(from, IVec3::ZERO);
+        assert_eq!(size, IVec3::new(16, 128, 16));
+        // Blocks (1 byte) + metadata, block light, sky light (half byte each).
+        assert_eq!(uncompressed_len(&data), 16 * 128 * 16 * 5 / 2);
+
+    }
+
+    #[test]
+    fn chunk_data_partial() {
+
+        let chunk = Chunk::new();
+        let mut from = IVec3::new(3, 5, 7);
+        let mut size = IVec3::new(2, 3, 4);
+        let data = encode_chunk_data(&chunk, &mut from, &mut size);
+
+        // The Y range should be aligned to even coordinates for nibble packing.
+        assert_eq!(from, IVec3::new(3, 4, 7));
+        assert_eq!(size, IVec3::new(2, 4, 4));
+        assert_eq!(uncompressed_len(&data), 2 * 4 * 4 * 5 / 2);
+
+    }
+
+}
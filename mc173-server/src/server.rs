@@ -11,16 +11,21 @@ use tracing::{warn, info};
 
 use mc173::world::{Dimension, Weather};
 use mc173::entity::{self as e};
+use mc173::util::TickTimer;
 
-use crate::config;
 use crate::proto::{self, Network, NetworkEvent, NetworkClient, InPacket, OutPacket};
 use crate::offline::OfflinePlayer;
 use crate::player::ServerPlayer;
 use crate::world::ServerWorld;
 
 
-/// Target tick duration. Currently 20 TPS, so 50 ms/tick.
-const TICK_DURATION: Duration = Duration::from_millis(50);
+/// Target ticks per second, so 50 ms/tick.
+const TICK_RATE: u32 = 20;
+/// Interval between each keep alive packet sent to clients.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(1);
+/// Default duration without receiving any packet from a client before disconnecting it,
+/// this is the same as the Notchian implementation (1200 ticks).
+const KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(60);
 
 
 /// This structure manages a whole server and its clients, dispatching incoming packets
@@ -29,11 +34,15 @@ pub struct Server {
     /// Packet server handle.
     net: Network,
     /// Clients of this server, these structures track the network state of each client.
-    clients: HashMap<NetworkClient, ClientState>,
+    clients: HashMap<NetworkClient, Client>,
     /// Worlds list.
     worlds: Vec<WorldState>,
     /// Offline players database.
     offline_players: HashMap<String, OfflinePlayer>,
+    /// Duration without receiving any packet from a client before disconnecting it.
+    keep_alive_timeout: Duration,
+    /// The timer used to pace ticks.
+    tick_timer: TickTimer,
 }
 
 impl Server {
@@ -48,6 +57,8 @@ impl Server {
             clients: HashMap::new(),
             worlds: vec![],
             offline_players: HashMap::new(),
+            keep_alive_timeout: KEEP_ALIVE_TIMEOUT,
+            tick_timer: TickTimer::new(TICK_RATE),
         })
 
     }
@@ -76,12 +87,9 @@ impl Server {
 
         let start = Instant::now();
         self.tick()?;
-        let elapsed = start.elapsed();
 
-        if let Some(missing) = TICK_DURATION.checked_sub(elapsed) {
-            std::thread::sleep(missing);
-        } else {
-            warn!("tick too long {:?}, expected {:?}", elapsed, TICK_DURATION);
+        if let Some(exceeding) = self.tick_timer.pad(start) {
+            warn!("tick too long {:?}, expected {:?}", exceeding + self.tick_timer.get_duration(), self.tick_timer.get_duration());
         }
 
         Ok(())
@@ -119,24 +127,58 @@ impl Server {
             }
         }
 
+        self.tick_keep_alive();
+
         Ok(())
 
     }
 
+    /// Send keep alive packets to clients and disconnect clients that have not sent any
+    /// packet for too long.
+    fn tick_keep_alive(&mut self) {
+
+        let now = Instant::now();
+        let mut timed_out = Vec::new();
+
+        for (&client, state) in &mut self.clients {
+            if now - state.last_received >= self.keep_alive_timeout {
+                timed_out.push(client);
+            } else if now - state.last_keep_alive >= KEEP_ALIVE_INTERVAL {
+                state.last_keep_alive = now;
+                self.net.send(client, OutPacket::KeepAlive);
+            }
+        }
+
+        for client in timed_out {
+            info!("client #{} timed out", client.id());
+            self.send_disconnect(client, "Timed out".to_string());
+            self.net.disconnect(client);
+            // Remove the client now, the lost event will be ignored when received.
+            self.handle_lost(client, None);
+        }
+
+    }
+
     /// Handle new client accepted by the network.
     fn handle_accept(&mut self, client: NetworkClient) {
         info!("accept client #{}", client.id());
-        self.clients.insert(client, ClientState::Handshaking);
+        let now = Instant::now();
+        self.clients.insert(client, Client {
+            state: ClientState::Handshaking,
+            last_received: now,
+            last_keep_alive: now,
+        });
     }
 
     /// Handle a lost client.
     fn handle_lost(&mut self, client: NetworkClient, error: Option<io::Error>) {
 
+        // The client may have already been removed, for example on timeout.
+        let Some(state) = self.clients.remove(&client) else { return };
+
         info!("lost client #{}: {:?}", client.id(), error);
         
-        let state = self.clients.remove(&client).unwrap();
-        
-        if let ClientState::Playing { world_index, player_index } = state {
+        if let ClientState::Playing { world_index, player_index } = state.state {
             // If the client was playing, remove it from its world.
             let state = &mut self.worlds[world_index];
             // Swap remove the player and tell the world.
@@ -144,10 +186,12 @@ impl Server {
             state.world.handle_player_leave(&mut player, true);
             // If a player has been swapped in place of this new one, redefine its state.
             if let Some(swapped_player) = state.players.get(player_index) {
-                self.clients.insert(swapped_player.client, ClientState::Playing { 
-                    world_index, 
-                    player_index,
-                }).expect("swapped player should have a previous state");
+                self.clients.get_mut(&swapped_player.client)
+                    .expect("swapped player should have a previous state")
+                    .state = ClientState::Playing { 
+                        world_index, 
+                        player_index,
+                    };
             }
         }
 
@@ -157,7 +201,11 @@ impl Server {
         
         // println!("[{client:?}] Packet: {packet:?}");
 
-        match *self.clients.get(&client).unwrap() {
+        // Ignore remaining packets of clients that have already been removed.
+        let Some(state) = self.clients.get_mut(&client) else { return };
+        state.last_received = Instant::now();
+
+        match state.state {
             ClientState::Handshaking => {
                 self.handle_handshaking(client, packet);
             }
@@ -198,7 +246,7 @@ impl Server {
             return;
         }
 
-        let spawn_pos = config::SPAWN_POS;
+        let spawn_pos = self.worlds[0].world.world.get_spawn_pos().as_dvec3();
 
         // Get the offline player, if not existing we create a new one with the 
         let offline_player = self.offline_players.entry(packet.username.clone())
@@ -272,13 +320,14 @@ impl Server {
 
         // Replace the previous state with a playing state containing the world and 
         // player indices, used to get to the player instance.
-        let previous_state = self.clients.insert(client, ClientState::Playing {
+        let state = self.clients.get_mut(&client).unwrap();
+        let previous_state = std::mem::replace(&mut state.state, ClientState::Playing {
             world_index,
             player_index,
         });
 
         // Just a sanity check...
-        debug_assert_eq!(previous_state, Some(ClientState::Handshaking));
+        debug_assert_eq!(previous_state, ClientState::Handshaking);
 
         // TODO: Broadcast chat joining chat message.
 
@@ -293,6 +342,17 @@ impl Server {
 
 }
 
+/// Track a network client in the server.
+#[derive(Debug, Clone, Copy)]
+struct Client {
+    /// Current state of the client.
+    state: ClientState,
+    /// Last time a packet was received from this client.
+    last_received: Instant,
+    /// Last time a keep alive packet was sent to this client.
+    last_keep_alive: Instant,
+}
+
 /// Track state of a network client in the server.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ClientState {
@@ -314,3 +374,44 @@ struct WorldState {
     /// The players currently in this world.
     players: Vec<ServerPlayer>,
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::TcpStream;
+
+    use crate::test::wait_until;
+
+    use super::*;
+
+    /// Tick the server until the given condition is met, panicking after some time.
+    fn tick_until(server: &mut Server, mut cond: impl FnMut(&Server) -> bool) {
+        wait_until(|| {
+            if cond(server) {
+                return true;
+            }
+            server.tick().unwrap();
+            std::thread::sleep(Duration::from_millis(10));
+            false
+        });
+    }
+
+    #[test]
+    fn keep_alive_timeout() {
+
+        let mut server = Server::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        server.keep_alive_timeout = Duration::from_millis(200);
+
+        // This client never sends any packet.
+        let _stream = TcpStream::connect(server.net.local_addr()).unwrap();
+
+        tick_until(&mut server, |server| !server.clients.is_empty());
+        let accepted = Instant::now();
+        
+        tick_until(&mut server, |server| server.clients.is_empty());
+        assert!(accepted.elapsed() >= Duration::from_millis(200));
+
+    }
+
+}
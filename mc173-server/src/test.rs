@@ -0,0 +1,86 @@
+//! Common utilities for server unit tests.
+
+use std::time::{Duration, Instant};
+use std::net::TcpStream;
+use std::io::Read;
+
+use glam::{DVec3, Vec2};
+
+use mc173::world::Dimension;
+use mc173::entity as e;
+
+use crate::proto::{Network, NetworkEvent};
+use crate::offline::OfflinePlayer;
+use crate::player::ServerPlayer;
+use crate::world::ServerWorld;
+
+
+/// Maximum duration to wait for a condition before failing the test.
+const WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+
+/// Call the given function until it returns true, panicking after some time.
+pub fn wait_until(mut cond: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !cond() {
+        assert!(start.elapsed() < WAIT_TIMEOUT, "condition not met in time");
+    }
+}
+
+/// A test world with a single player connected to a local network.
+pub struct Test {
+    pub world: ServerWorld,
+    pub player: ServerPlayer,
+    pub stream: TcpStream,
+}
+
+impl Test {
+
+    pub fn new() -> Self {
+
+        let net = Network::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let stream = TcpStream::connect(net.local_addr()).unwrap();
+        
+        let mut client = None;
+        wait_until(|| {
+            if let Some(NetworkEvent::Accept { client: accepted }) = net.poll().unwrap() {
+                client = Some(accepted);
+            }
+            client.is_some()
+        });
+        let client = client.unwrap();
+
+        let mut world = ServerWorld::new("test".to_string(), Dimension::Overworld);
+        let offline = OfflinePlayer { 
+            world: world.name.clone(), 
+            pos: DVec3::new(0.0, 100.0, 0.0), 
+            look: Vec2::ZERO,
+        };
+
+        let entity_id = world.world.spawn_entity(e::Human::new_with(|base, _, player| {
+            base.pos = offline.pos;
+            player.username = "test".to_string();
+        }));
+        world.world.set_player_entity(entity_id, true);
+
+        let player = ServerPlayer::new(&net, client, entity_id, "test".to_string(), &offline);
+        Self { world, player, stream }
+
+    }
+
+    /// Receive raw bytes sent to the test player until the given condition is met,
+    /// panicking after some time.
+    pub fn recv_until(&mut self, mut cond: impl FnMut(&[u8]) -> bool) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let mut chunk = [0; 1024];
+        self.stream.set_read_timeout(Some(Duration::from_millis(50))).unwrap();
+        wait_until(|| {
+            if let Ok(len) = self.stream.read(&mut chunk) {
+                buf.extend_from_slice(&chunk[..len]);
+            }
+            cond(&buf)
+        });
+        buf
+    }
+
+}
@@ -1,17 +1,26 @@
 //! Server world structure.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufReader, BufWriter, Write};
 use std::time::Instant;
+use std::path::Path;
+use std::fs::{self, File};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 
 use glam::{DVec3, IVec3, Vec2};
 
 use mc173::block_entity::BlockEntity;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use mc173::entity::{Entity, BaseKind, ProjectileKind};
 use mc173::storage::{ChunkStorage, ChunkStorageReply};
 use mc173::gen::OverworldGenerator;
 use mc173::item::{ItemStack, self};
+use mc173::serde::level::{self, LevelData};
+use mc173::serde::nbt::{self, NbtError};
 use mc173::util::FadingAverage;
 use mc173::{chunk, block};
 
@@ -46,6 +55,8 @@ pub struct ServerWorld {
     storage: ChunkStorage,
     /// Chunks trackers used to send proper block changes packets.
     chunk_trackers: ChunkTrackers,
+    /// Chunk streamer used to load and unload chunks around players.
+    chunk_streamer: ChunkStreamer,
     /// Entity tracker, each is associated to the entity id.
     entity_trackers: HashMap<u32, EntityTracker>,
     /// Instant of the last tick.
@@ -58,6 +69,178 @@ pub struct ServerWorld {
     pub events_count: FadingAverage,
 }
 
+/// The chunk streamer keeps track of chunks that are loaded around players, within a
+/// given view distance. Chunks are requested for load progressively, with a limit of 
+/// loads per update, and unloaded when no longer in view of any player.
+#[derive(Debug)]
+pub struct ChunkStreamer {
+    /// The view distance in chunks around each center.
+    view_distance: u8,
+    /// Maximum number of chunk loads returned on each update.
+    max_loads: usize,
+    /// All chunks managed by this streamer, associated to true if the chunk is actually
+    /// loaded, or false if the load is still pending.
+    chunks: HashMap<(i32, i32), bool>,
+}
+
+/// The result of a chunk streamer update, listing chunks to load and to unload.
+#[derive(Debug, Default)]
+pub struct ChunkStreamUpdate {
+    /// Chunks that should be requested for load, nearest chunks first.
+    pub loads: Vec<(i32, i32)>,
+    /// Chunks that should be unloaded because not in view anymore.
+    pub unloads: Vec<(i32, i32)>,
+}
+
+impl ChunkStreamer {
+
+    /// Create a new chunk streamer with the given view distance in chunks and maximum
+    /// number of chunk loads per update.
+    pub fn new(view_distance: u8, max_loads: usize) -> Self {
+        Self {
+            view_distance,
+            max_loads,
+            chunks: HashMap::new(),
+        }
+    }
+
+    /// Return the view distance of this streamer.
+    #[inline]
+    pub fn view_distance(&self) -> u8 {
+        self.view_distance
+    }
+
+    /// Return true if the given chunk is managed by this streamer, loaded or pending.
+    #[inline]
+    pub fn contains(&self, cx: i32, cz: i32) -> bool {
+        self.chunks.contains_key(&(cx, cz))
+    }
+
+    /// Iterate over all chunks managed by this streamer, loaded or pending.
+    pub fn iter_chunks(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// Mark a chunk as loaded, this should be called when a load request has been 
+    /// answered, only loaded chunks can be unloaded by the streamer.
+    pub fn set_loaded(&mut self, cx: i32, cz: i32) {
+        if let Some(loaded) = self.chunks.get_mut(&(cx, cz)) {
+            *loaded = true;
+        }
+    }
+
+    /// Forget a chunk whose load has failed, if still in view it will be requested for
+    /// load again on the next update.
+    pub fn set_failed(&mut self, cx: i32, cz: i32) {
+        if let Some(false) = self.chunks.get(&(cx, cz)) {
+            self.chunks.remove(&(cx, cz));
+        }
+    }
+
+    /// Update the streamer with the given center chunks (typically the chunks where
+    /// players are and the spawn chunk), and return chunks to be loaded or unloaded. 
+    /// Chunks with a pending load are never unloaded until marked loaded.
+    pub fn update(&mut self, centers: &[(i32, i32)]) -> ChunkStreamUpdate {
+
+        let dist = self.view_distance as i32;
+        let mut update = ChunkStreamUpdate::default();
+
+        let mut in_view = HashSet::new();
+        for &(ccx, ccz) in centers {
+            for cx in ccx - dist..=ccx + dist {
+                for cz in ccz - dist..=ccz + dist {
+                    in_view.insert((cx, cz));
+                }
+            }
+        }
+
+        self.chunks.retain(|&pos, &mut loaded| {
+            if loaded && !in_view.contains(&pos) {
+                update.unloads.push(pos);
+                false
+            } else {
+                true
+            }
+        });
+
+        // Compute the squared distance to the nearest center for each missing chunk,
+        // the nearest chunks are loaded first.
+        let mut missing = in_view.into_iter()
+            .filter(|pos| !self.chunks.contains_key(pos))
+            .map(|(cx, cz)| {
+                let dist = centers.iter()
+                    .map(|&(ccx, ccz)| (cx - ccx).pow(2) + (cz - ccz).pow(2))
+                    .min()
+                    .unwrap();
+                (dist, cx, cz)
+            })
+            .collect::<Vec<_>>();
+        
+        missing.sort_unstable();
+
+        for (_, cx, cz) in missing.into_iter().take(self.max_loads) {
+            self.chunks.insert((cx, cz), false);
+            update.loads.push((cx, cz));
+        }
+
+        update
+
+    }
+
+}
+
+/// Path of the file storing the level data of the world.
+const LEVEL_DATA_PATH: &str = "test_world/level.dat";
+
+/// Load the level data from the given gzip-compressed NBT file, none is returned if the
+/// file doesn't exist yet or if it cannot be read.
+fn load_level_data(path: impl AsRef<Path>) -> Option<LevelData> {
+
+    let path = path.as_ref();
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+        Err(err) => {
+            warn!("failed to open level data at {}: {err}", path.display());
+            return None;
+        }
+    };
+
+    let root = match nbt::from_reader(GzDecoder::new(BufReader::new(file))) {
+        Ok(root) => root,
+        Err(err) => {
+            warn!("failed to read level data at {}: {err}", path.display());
+            return None;
+        }
+    };
+
+    match level::from_nbt(&root) {
+        Ok(data) => Some(data),
+        Err(err) => {
+            warn!("failed to parse level data at {}: {err}", path.display());
+            None
+        }
+    }
+
+}
+
+/// Save the level data to the given gzip-compressed NBT file, parent directories are
+/// created if needed.
+fn save_level_data(path: impl AsRef<Path>, data: &LevelData) -> Result<(), NbtError> {
+
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let file = File::create(path)?;
+    let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+    nbt::to_writer(&mut encoder, &level::to_nbt(data))?;
+    encoder.finish()?.flush()?;
+    Ok(())
+
+}
+
 /// Indicate the current mode for ticking the world.
 pub enum TickMode {
     /// The world is ticked on each server tick (20 TPS).
@@ -77,8 +260,20 @@ impl ServerWorld {
         // Make sure that the world initially have an empty events queue.
         world.swap_events(Some(Vec::new()));
 
-        let seed = config::SEED;
-        
+        // Restore the level data if the world has already been saved, its seed is then
+        // used by the chunk generator.
+        let seed = match load_level_data(LEVEL_DATA_PATH) {
+            Some(data) => {
+                info!("loaded level data of {name}");
+                data.apply_to_world(&mut world);
+                data.seed
+            }
+            None => {
+                world.set_spawn_pos(config::SPAWN_POS.floor().as_ivec3());
+                config::SEED
+            }
+        };
+
         Self {
             name,
             world,
@@ -87,6 +282,7 @@ impl ServerWorld {
             tick_mode: TickMode::Auto,
             storage: ChunkStorage::new("test_world/region/", OverworldGenerator::new(seed), 4),
             chunk_trackers: ChunkTrackers::new(),
+            chunk_streamer: ChunkStreamer::new(config::view_distance(), config::CHUNK_LOADS_PER_TICK),
             entity_trackers: HashMap::new(),
             tick_last: Instant::now(),
             tick_duration: FadingAverage::default(),
@@ -106,7 +302,7 @@ impl ServerWorld {
         // }
 
         for (cx, cz) in self.chunk_trackers.drain_save() {
-            if let Some(snapshot) = self.world.take_chunk_snapshot(cx, cz) {
+            if let Some(snapshot) = self.world.save_chunk_snapshot(cx, cz) {
                 debug!("saving {} chunk: {cx}/{cz}", self.name);
                 self.storage.request_save(snapshot);
             }
@@ -126,6 +322,11 @@ impl ServerWorld {
             }
         }
 
+        let data = LevelData::from_world(&self.world, self.seed);
+        if let Err(err) = save_level_data(LEVEL_DATA_PATH, &data) {
+            warn!("failed to save level data of {}: {err}", self.name);
+        }
+
     }
 
     /// Tick this world.
@@ -144,12 +345,19 @@ impl ServerWorld {
         // Poll all chunks to load in the world.
         while let Some(reply) = self.storage.poll() {
             match reply {
-                ChunkStorageReply::Load { cx, cz, res: Ok(snapshot) } => {
+                ChunkStorageReply::Load { cx, cz, res: Ok(snapshot), generated } => {
                     debug!("loaded chunk from storage: {cx}/{cz}");
+                    self.chunk_streamer.set_loaded(cx, cz);
                     self.world.insert_chunk_snapshot(snapshot);
+                    // Generated chunks are dirty so they are saved when unloaded.
+                    if generated {
+                        self.world.set_chunk_dirty(cx, cz);
+                    }
                 }
-                ChunkStorageReply::Load { cx, cz, res: Err(err) } => {
+                ChunkStorageReply::Load { cx, cz, res: Err(err), .. } => {
+                    // The chunk is forgotten by the streamer so its load is retried.
                     debug!("failed to load chunk from storage: {cx}/{cz}: {err}");
+                    self.chunk_streamer.set_failed(cx, cz);
                 }
                 ChunkStorageReply::Save { cx, cz, res: Ok(()) } => {
                     debug!("saved chunk in storage: {cx}/{cz}");
@@ -160,6 +368,9 @@ impl ServerWorld {
             }
         }
 
+        // Load and unload chunks depending on players' positions.
+        self.stream_chunks(players);
+
         // Only run if no tick freeze.
         match self.tick_mode {
             TickMode::Auto => {
@@ -190,6 +401,8 @@ impl ServerWorld {
                     }
                     BlockEvent::NoteBlock { instrument, note } =>
                         self.handle_block_action(players, pos, instrument as i8, note as i8),
+                    // The client plays break, place and step sounds by itself.
+                    BlockEvent::GroupSound { .. } => {}
                 }
                 Event::Entity { id, inner } => match inner {
                     EntityEvent::Spawn => 
@@ -210,6 +423,13 @@ impl ServerWorld {
                         self.handle_entity_dead(players, id),
                     EntityEvent::Metadata =>
                         self.handle_entity_metadata(players, id),
+                    EntityEvent::Ride { vehicle_id } =>
+                        self.handle_entity_ride(players, id, vehicle_id),
+                    EntityEvent::Storage { index, stack } =>
+                        self.handle_entity_storage(players, id, index, stack),
+                    EntityEvent::Armor { index, stack } =>
+                        self.handle_entity_armor(players, id, index, stack),
+                    EntityEvent::Portal => {}, // TODO: Dimension transfer.
                 }
                 Event::BlockEntity { pos, inner } => match inner {
                     BlockEntityEvent::Set =>
@@ -225,13 +445,13 @@ impl ServerWorld {
                 }
                 Event::Chunk { cx, cz, inner } => match inner {
                     ChunkEvent::Set => {}
-                    ChunkEvent::Remove => {}
+                    ChunkEvent::Remove => self.chunk_trackers.remove(cx, cz),
                     ChunkEvent::Dirty => self.chunk_trackers.set_dirty(cx, cz),
                 }
                 Event::Weather { new, .. } =>
                     self.handle_weather_change(players, new),
-                Event::Explode { center, radius } =>
-                    self.handle_explode(players, center, radius),
+                Event::Explode { center, radius, blocks, .. } =>
+                    self.handle_explode(players, center, radius, &blocks),
                 Event::DebugParticle { pos, block } =>
                     self.handle_debug_particle(players, pos, block),
             }
@@ -253,9 +473,19 @@ impl ServerWorld {
         // After we collected every block change, update all players accordingly.
         self.chunk_trackers.update_players(players, &self.world);
 
+        // Players that moved enough since the last tracking update should track or
+        // untrack the entities around them.
+        for player in &mut players[..] {
+            if self.entity_trackers.get(&player.entity_id).is_some_and(EntityTracker::need_tracking_update) {
+                for tracker in self.entity_trackers.values() {
+                    tracker.update_tracking_player(player, &self.world);
+                }
+            }
+        }
+
         // After world events are processed, tick entity trackers.
         for tracker in self.entity_trackers.values_mut() {
-            if time % 60 == 0 {
+            if time % 60 == 0 || tracker.need_tracking_update() {
                 tracker.update_tracking_players(players, &self.world);
             }
             tracker.tick_and_update_players(players);
@@ -263,7 +493,7 @@ impl ServerWorld {
 
         // Drain dirty chunks coordinates and save them.
         while let Some((cx, cz)) = self.chunk_trackers.next_save() {
-            if let Some(snapshot) = self.world.take_chunk_snapshot(cx, cz) {
+            if let Some(snapshot) = self.world.save_chunk_snapshot(cx, cz) {
                 self.storage.request_save(snapshot);
             }
         }
@@ -284,22 +514,54 @@ impl ServerWorld {
         // Ensure that every entity has a tracker.
         for (id, entity) in self.world.iter_entities() {
             self.entity_trackers.entry(id).or_insert_with(|| {
-                let tracker = EntityTracker::new(id, entity);
+                let mut tracker = EntityTracker::new(id, entity);
                 tracker.update_tracking_players(players, &self.world);
                 tracker
             });
         }
 
-        // NOTE: Temporary code.
-        let (center_cx, center_cz) = chunk::calc_entity_chunk_pos(config::SPAWN_POS);
-        for cx in center_cx - 10..=center_cx + 10 {
-            for cz in center_cz - 10..=center_cz + 10 {
-                self.storage.request_load(cx, cz);
+    }
+
+    /// Update the chunk streamer with the current players' positions, loads are 
+    /// requested to the storage for new chunks in view, and chunks that are no longer 
+    /// viewed by any player are unloaded. Dirty chunks are saved before being unloaded.
+    fn stream_chunks(&mut self, players: &[ServerPlayer]) {
+
+        let centers = self.stream_centers(players);
+        let update = self.chunk_streamer.update(&centers);
+
+        for (cx, cz) in update.loads {
+            self.storage.request_load(cx, cz);
+        }
+
+        for (cx, cz) in update.unloads {
+            let dirty = self.world.is_chunk_dirty(cx, cz);
+            if let Some(snapshot) = self.world.remove_chunk_snapshot(cx, cz) {
+                debug!("unloaded chunk: {cx}/{cz}");
+                if dirty {
+                    self.storage.request_save(snapshot);
+                }
             }
         }
 
     }
 
+    /// Return the center chunks to stream chunks around, this contains the chunk of each
+    /// player and the spawn chunk, that is always kept loaded even without any player.
+    fn stream_centers(&self, players: &[ServerPlayer]) -> Vec<(i32, i32)> {
+        let spawn_pos = self.world.get_spawn_pos();
+        players.iter()
+            .map(|player| chunk::calc_entity_chunk_pos(player.pos))
+            .chain(std::iter::once(chunk::calc_chunk_pos_unchecked(spawn_pos)))
+            .collect()
+    }
+
+    /// Request a chunk to be loaded from the storage, the chunk will be inserted in the 
+    /// world in a future tick.
+    pub fn request_chunk_load(&mut self, cx: i32, cz: i32) {
+        self.storage.request_load(cx, cz);
+    }
+
     /// Handle a player joining this world.
     pub fn handle_player_join(&mut self, player: &mut ServerPlayer) {
 
@@ -400,7 +662,15 @@ impl ServerWorld {
         }
     }
 
-    fn handle_explode(&mut self, players: &mut [ServerPlayer], center: DVec3, radius: f32) {
+    fn handle_explode(&mut self, players: &mut [ServerPlayer], center: DVec3, radius: f32, blocks: &[IVec3]) {
+
+        // Blocks are sent relative to the truncated center.
+        let origin = center.as_ivec3();
+        let blocks = blocks.iter()
+            .map(|&pos| pos - origin)
+            .map(|delta| (delta.x as i8, delta.y as i8, delta.z as i8))
+            .collect::<Vec<_>>();
+
         let (cx, cz) = chunk::calc_entity_chunk_pos(center);
         for player in players {
             if player.tracked_chunks.contains(&(cx, cz)) {
@@ -409,21 +679,26 @@ impl ServerWorld {
                     y: center.y,
                     z: center.z,
                     size: radius,
-                    blocks: vec![],
+                    blocks: blocks.clone(),
                 }));
             }
         }
+
     }
 
     /// Handle an entity spawn world event.
     fn handle_entity_spawn(&mut self, players: &mut [ServerPlayer], id: u32) {
+        // The entity is already tracked when respawned, players tracking it have seen
+        // it dying so it is spawned again.
+        if let Some(tracker) = self.entity_trackers.get_mut(&id) {
+            tracker.respawn_players(players, &self.world);
+            return;
+        }
         // The entity may have already been removed.
         if let Some(entity) = self.world.get_entity(id) {
-            self.entity_trackers.entry(id).or_insert_with(|| {
-                let tracker = EntityTracker::new(id, entity);
-                tracker.update_tracking_players(players, &self.world);
-                tracker
-            });
+            let mut tracker = EntityTracker::new(id, entity);
+            tracker.update_tracking_players(players, &self.world);
+            self.entity_trackers.insert(id, tracker);
         }
     }
 
@@ -433,6 +708,10 @@ impl ServerWorld {
         if let Some(tracker) = self.entity_trackers.remove(&id) {
             tracker.untrack_players(players);
         };
+        // Close the inventory of all players that had a window opened for this entity.
+        for player in players {
+            player.close_entity_window(self, id);
+        }
     }
 
     /// Handle an entity position world event.
@@ -541,6 +820,38 @@ impl ServerWorld {
         }
     }
 
+    /// Handle an entity ride event, the entity started or stopped riding a vehicle.
+    fn handle_entity_ride(&mut self, players: &mut [ServerPlayer], id: u32, vehicle_id: Option<u32>) {
+        for player in players {
+            if player.tracked_entities.contains(&id) || player.entity_id == id {
+                player.send(OutPacket::EntityRide(proto::EntityRidePacket {
+                    entity_id: id,
+                    vehicle_entity_id: vehicle_id.unwrap_or(u32::MAX),
+                }));
+            }
+        }
+    }
+
+    /// Handle a storage event for an entity.
+    fn handle_entity_storage(&mut self, players: &mut [ServerPlayer], id: u32, index: u8, stack: ItemStack) {
+
+        // Update any player that have a window opened on that entity.
+        for player in players {
+            player.update_entity_window_storage(id, index, stack);
+        }
+
+    }
+
+    /// Handle an entity armor event.
+    fn handle_entity_armor(&mut self, players: &mut [ServerPlayer], id: u32, index: u8, stack: ItemStack) {
+
+        // Update the armor inventory of the player owning that entity.
+        for player in players {
+            player.update_armor_storage(id, index, stack);
+        }
+
+    }
+
     /// Handle a block entity set event.
     fn handle_block_entity_set(&mut self, _players: &mut [ServerPlayer], _pos: IVec3) {
         
@@ -622,3 +933,133 @@ impl ServerWorld {
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::test::Test;
+
+    use super::*;
+
+    /// Update the streamer and immediately mark all requested chunks as loaded.
+    fn update_loaded(streamer: &mut ChunkStreamer, centers: &[(i32, i32)]) -> ChunkStreamUpdate {
+        let update = streamer.update(centers);
+        for &(cx, cz) in &update.loads {
+            streamer.set_loaded(cx, cz);
+        }
+        update
+    }
+
+    #[test]
+    fn chunk_streamer() {
+
+        let mut streamer = ChunkStreamer::new(2, 10);
+
+        // Loads are rate limited, nearest chunks first.
+        let update = update_loaded(&mut streamer, &[(0, 0)]);
+        assert_eq!(update.loads.len(), 10);
+        assert_eq!(update.loads[0], (0, 0));
+        assert!(update.unloads.is_empty());
+        
+        while !update_loaded(&mut streamer, &[(0, 0)]).loads.is_empty() { }
+        let expected = (-2..=2).flat_map(|cx| (-2..=2).map(move |cz| (cx, cz))).collect::<HashSet<_>>();
+        assert_eq!(streamer.iter_chunks().collect::<HashSet<_>>(), expected);
+
+        // Move the player across the chunk border on X.
+        let update = update_loaded(&mut streamer, &[(1, 0)]);
+        assert_eq!(update.loads.iter().copied().collect::<HashSet<_>>(), (-2..=2).map(|cz| (3, cz)).collect());
+        assert_eq!(update.unloads.iter().copied().collect::<HashSet<_>>(), (-2..=2).map(|cz| (-2, cz)).collect());
+
+        // Move diagonally, all chunks should be in the radius of the new center.
+        while !update_loaded(&mut streamer, &[(3, 3)]).loads.is_empty() { }
+        assert_eq!(streamer.iter_chunks().count(), 25);
+        assert!(streamer.iter_chunks().all(|(cx, cz)| (cx - 3).abs() <= 2 && (cz - 3).abs() <= 2));
+
+        // Chunks with pending load are not unloaded.
+        let update = streamer.update(&[(10, 10)]);
+        assert_eq!(update.unloads.len(), 25);
+        assert_eq!(update.loads.len(), 10);
+        let update = streamer.update(&[(0, 0)]);
+        assert!(update.unloads.is_empty());
+        assert!(streamer.contains(update.loads[0].0, update.loads[0].1));
+        assert!(streamer.contains(10, 10));
+
+        // A chunk with a failed load is requested again.
+        let mut streamer = ChunkStreamer::new(0, 10);
+        assert_eq!(streamer.update(&[(0, 0)]).loads, [(0, 0)]);
+        assert!(streamer.update(&[(0, 0)]).loads.is_empty());
+        streamer.set_failed(0, 0);
+        assert!(!streamer.contains(0, 0));
+        assert_eq!(streamer.update(&[(0, 0)]).loads, [(0, 0)]);
+
+    }
+
+    #[test]
+    fn stream_spawn_chunks() {
+
+        let mut world = ServerWorld::new("test".to_string(), Dimension::Overworld);
+        world.world.set_spawn_pos(IVec3::new(100, 64, -20));
+        
+        // Without any player, the spawn chunks are still loaded and never unloaded.
+        let centers = world.stream_centers(&[]);
+        assert_eq!(centers, [(6, -2)]);
+        update_loaded(&mut world.chunk_streamer, &centers);
+        assert!(update_loaded(&mut world.chunk_streamer, &centers).unloads.is_empty());
+        assert!(world.chunk_streamer.contains(6, -2));
+
+    }
+
+    #[test]
+    fn respawn_tracked_entity() {
+
+        let mut test = Test::new();
+
+        let pos = test.player.pos + DVec3::new(5.0, 0.0, 0.0);
+        let id = test.world.world.spawn_entity(mc173::entity::Human::new_with(|base, _, player| {
+            base.pos = pos;
+            player.username = "other".to_string();
+        }));
+
+        test.world.handle_entity_spawn(std::slice::from_mut(&mut test.player), id);
+        assert!(test.player.tracked_entities.contains(&id));
+
+        let mut spawn = vec![20];
+        spawn.extend_from_slice(&(id as i32).to_be_bytes());
+        test.recv_until(|buf| buf.starts_with(&spawn));
+
+        // The entity is spawned again, after being killed, for players tracking it.
+        test.world.handle_entity_spawn(std::slice::from_mut(&mut test.player), id);
+        assert!(test.player.tracked_entities.contains(&id));
+
+        let mut kill_spawn = vec![29];
+        kill_spawn.extend_from_slice(&(id as i32).to_be_bytes());
+        kill_spawn.extend_from_slice(&spawn);
+        test.recv_until(|buf| buf.windows(kill_spawn.len()).any(|w| w == kill_spawn));
+
+    }
+
+    #[test]
+    fn level_data_file() {
+
+        let dir = std::env::temp_dir().join(format!("mc173-test-level-data-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("level.dat");
+
+        assert_eq!(load_level_data(&path), None);
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_time(1000);
+        world.set_spawn_pos(IVec3::new(-30, 72, 45));
+        world.set_weather(Weather::Rain);
+        world.set_weather_next_time(5000);
+
+        let data = LevelData::from_world(&world, 1234);
+        save_level_data(&path, &data).unwrap();
+        assert_eq!(load_level_data(&path), Some(data));
+
+        let _ = fs::remove_dir_all(&dir);
+
+    }
+
+}
@@ -4,10 +4,12 @@
 //! client-side in order to have the proper foliage color.
 
 use crate::entity::{EntityCategory, EntityKind};
+use crate::block;
 
 
 /// Possible biomes, only used server-side for natural mob spawning.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
 pub enum Biome {
     #[default]
     Void,
@@ -28,6 +30,79 @@ pub enum Biome {
 
 impl Biome {
 
+    /// All biomes, ordered by their numeric id.
+    pub const ALL: [Biome; 14] = [
+        Biome::Void,
+        Biome::RainForest,
+        Biome::Swampland,
+        Biome::SeasonalForest,
+        Biome::Forest,
+        Biome::Savanna,
+        Biome::ShrubLand,
+        Biome::Taiga,
+        Biome::Desert,
+        Biome::Plains,
+        Biome::IceDesert,
+        Biome::Tundra,
+        Biome::Nether,
+        Biome::Sky,
+    ];
+
+    /// Get a biome from its numeric id, none is returned if the id is invalid.
+    #[inline]
+    pub fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.get(id as usize).copied()
+    }
+
+    /// Get the numeric id of this biome, the void biome has id 0 and other ones 
+    /// follow the Notchian declaration order.
+    #[inline]
+    pub fn to_id(self) -> u8 {
+        self as u8
+    }
+
+    /// Get the overworld biome for the given temperature and rainfall, both values 
+    /// should be in range 0..=1.
+    /// 
+    /// PARITY: Beta biomes have no fixed temperature and rainfall, these are sampled
+    /// from noises when generating the terrain and then mapped to a biome with this
+    /// function, so there is no such constants on the biome itself.
+    /// 
+    /// REF: BiomeGenBase::getBiome
+    pub fn from_climate(temperature: f32, rainfall: f32) -> Self {
+
+        let rainfall = rainfall * temperature;
+
+        if temperature < 0.1 {
+            Biome::Tundra
+        } else if rainfall < 0.2 {
+            if temperature < 0.5 {
+                Biome::Tundra
+            } else if temperature < 0.95 {
+                Biome::Savanna
+            } else {
+                Biome::Desert
+            }
+        } else if rainfall > 0.5 && temperature < 0.7 {
+            Biome::Swampland
+        } else if temperature < 0.5 {
+            Biome::Taiga
+        } else if temperature < 0.97 {
+            if rainfall < 0.35 {
+                Biome::ShrubLand
+            } else {
+                Biome::Forest
+            }
+        } else if rainfall < 0.45 {
+            Biome::Plains
+        } else if rainfall < 0.9 {
+            Biome::SeasonalForest
+        } else {
+            Biome::RainForest
+        }
+
+    }
+
     /// Return true if it is possible to rain in a chunk.
     #[inline]
     pub fn has_rain(self) -> bool {
@@ -51,6 +126,26 @@ impl Biome {
         }
     }
 
+    /// Return the block id used at the top of the terrain surface in this biome.
+    #[inline]
+    pub fn top_block(self) -> u8 {
+        match self {
+            Biome::Desert |
+            Biome::IceDesert => block::SAND,
+            _ => block::GRASS,
+        }
+    }
+
+    /// Return the block id used to fill the terrain surface below the top block.
+    #[inline]
+    pub fn filler_block(self) -> u8 {
+        match self {
+            Biome::Desert |
+            Biome::IceDesert => block::SAND,
+            _ => block::DIRT,
+        }
+    }
+
     /// Get the natural entity kinds for the given category and this current biome.
     pub fn natural_entity_kinds(self, category: EntityCategory) -> &'static [NaturalEntityKind] {
         
@@ -147,3 +242,39 @@ impl NaturalEntityKind {
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn id_mapping() {
+
+        for (id, biome) in Biome::ALL.into_iter().enumerate() {
+            assert_eq!(biome.to_id(), id as u8);
+            assert_eq!(Biome::from_id(id as u8), Some(biome));
+        }
+
+        assert_eq!(Biome::from_id(Biome::ALL.len() as u8), None);
+        assert_eq!(Biome::from_id(u8::MAX), None);
+
+    }
+
+    #[test]
+    fn weather() {
+
+        assert!(Biome::Tundra.has_snow());
+        assert!(Biome::RainForest.has_rain());
+        assert!(!Biome::RainForest.has_snow());
+        assert!(!Biome::Desert.has_rain());
+        assert!(!Biome::Nether.has_rain());
+
+        assert_eq!(Biome::from_climate(0.0, 0.5), Biome::Tundra);
+        assert_eq!(Biome::from_climate(1.0, 1.0), Biome::RainForest);
+        assert_eq!(Biome::from_climate(1.0, 0.0), Biome::Desert);
+
+    }
+
+}
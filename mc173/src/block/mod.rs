@@ -21,6 +21,7 @@ pub mod fluid;
 pub mod door;
 pub mod sign;
 pub mod bed;
+pub mod wool;
 
 
 /// Internal macro to easily define blocks registry.
@@ -162,6 +163,51 @@ pub fn from_name(name: &str) -> Option<u8> {
     NAMES.iter().position(|&n| n == name).map(|n| n as u8)
 }
 
+/// Return true if the given block id has a behavior on random ticks, other blocks are
+/// not dispatched when randomly picked by the world's random ticking.
+pub fn random_tick_enabled(id: u8) -> bool {
+    matches!(id, 
+        WATER_MOVING | LAVA_MOVING | LAVA_STILL |
+        SAPLING | WHEAT | CACTUS | SUGAR_CANES |
+        RED_MUSHROOM | BROWN_MUSHROOM |
+        GRASS | LEAVES | FARMLAND | FIRE |
+        ICE | SNOW | SNOW_BLOCK |
+        REDSTONE_ORE_LIT)
+}
+
+/// Get the sound group of the given block id, this group is used to choose the sound to
+/// play when the block is broken, placed or stepped on.
+pub fn sound_group(id: u8) -> SoundGroup {
+    match id {
+        WOOD | LOG | BOOKSHELF | WOOD_STAIR | CHEST | LOCKED_CHEST | CRAFTING_TABLE |
+        SIGN | WALL_SIGN | WOOD_DOOR | LADDER | FENCE | TRAPDOOR |
+        WOOD_PRESSURE_PLATE | TORCH | REDSTONE_TORCH | REDSTONE_TORCH_LIT | LEVER |
+        FIRE | PUMPKIN | PUMPKIN_LIT | REPEATER | REPEATER_LIT => SoundGroup::Wood,
+        DIRT | FARMLAND | GRAVEL | CLAY => SoundGroup::Gravel,
+        GRASS | LEAVES | SAPLING | TALL_GRASS | DEAD_BUSH | DANDELION | POPPY |
+        BROWN_MUSHROOM | RED_MUSHROOM | WHEAT | SUGAR_CANES | SPONGE | TNT => SoundGroup::Grass,
+        GOLD_BLOCK | IRON_BLOCK | DIAMOND_BLOCK | SPAWNER | IRON_DOOR |
+        RAIL | POWERED_RAIL | DETECTOR_RAIL => SoundGroup::Metal,
+        GLASS | GLOWSTONE | ICE | PORTAL => SoundGroup::Glass,
+        WOOL | SNOW | SNOW_BLOCK | CACTUS | CAKE => SoundGroup::Cloth,
+        SAND | SOULSAND => SoundGroup::Sand,
+        _ => SoundGroup::Stone,
+    }
+}
+
+/// Sound groups of blocks, each group has its own break, place and step sounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundGroup {
+    Stone,
+    Wood,
+    Gravel,
+    Grass,
+    Metal,
+    Glass,
+    Cloth,
+    Sand,
+}
+
 /// Find the item associated to the given block id. 
 /// TODO: This should be removed in the future when items module is reworked.
 #[inline]
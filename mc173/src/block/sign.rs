@@ -3,7 +3,7 @@
 use crate::geom::Face;
 
 
-/// Get the face where the stair leads to.
+/// Get the face of a wall sign, the sign is facing this direction, away from its wall.
 #[inline]
 pub fn get_wall_face(metadata: u8) -> Option<Face> {
     Some(match metadata {
@@ -15,6 +15,7 @@ pub fn get_wall_face(metadata: u8) -> Option<Face> {
     })
 }
 
+/// Set the face of a wall sign, panics if the face is not horizontal.
 #[inline]
 pub fn set_wall_face(metadata: &mut u8, face: Face) {
     *metadata = match face {
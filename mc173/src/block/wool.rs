@@ -0,0 +1,16 @@
+//! Wool block metadata functions.
+
+
+/// Get the wool color metadata from the damage of a dye item, wool colors and dye 
+/// damages are in reverse order, for example white wool is 0 and bone meal is 15, while
+/// black wool is 15 and ink sac is 0.
+#[inline]
+pub fn get_color_from_dye(damage: u16) -> u8 {
+    !(damage as u8) & 15
+}
+
+/// Get the dye item damage from a wool color metadata.
+#[inline]
+pub fn get_dye_from_color(color: u8) -> u16 {
+    (!color & 15) as u16
+}
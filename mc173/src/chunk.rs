@@ -20,6 +20,10 @@ pub const CHUNK_HEIGHT: usize = 128;
 pub const CHUNK_2D_SIZE: usize = CHUNK_WIDTH * CHUNK_WIDTH;
 /// Internal chunk 3D size, in number of block per chunk.
 pub const CHUNK_3D_SIZE: usize = CHUNK_HEIGHT * CHUNK_2D_SIZE;
+/// Height of a chunk section, sections are used to track empty parts of the chunk.
+pub const CHUNK_SECTION_HEIGHT: usize = 16;
+/// Number of sections in a chunk.
+pub const CHUNK_SECTION_COUNT: usize = CHUNK_HEIGHT / CHUNK_SECTION_HEIGHT;
 
 
 /// Calculate the index in the chunk's arrays for the given position (local or not). This
@@ -93,6 +97,10 @@ pub struct Chunk {
     /// why we can use a Rust enumeration for this one, and not raw value, because we
     /// don't need to deserialize it and therefore don't risk any unwanted value.
     pub biome: ChunkArray2<Biome>,
+    /// Number of non-air blocks in each section of the chunk, this is kept up-to-date
+    /// by block setters, but must be recomputed with [`Self::recompute_sections`] after
+    /// directly modifying the block array.
+    section_blocks: [u16; CHUNK_SECTION_COUNT],
 }
 
 impl Chunk {
@@ -114,6 +122,7 @@ impl Chunk {
             sky_light: ChunkNibbleArray3::new(15),
             height: [0; CHUNK_2D_SIZE],
             biome: [Biome::Void; CHUNK_2D_SIZE],
+            section_blocks: [0; CHUNK_SECTION_COUNT],
         })
     }
 
@@ -130,10 +139,58 @@ impl Chunk {
     #[inline]
     pub fn set_block(&mut self, pos: IVec3, id: u8, metadata: u8) {
         let index = calc_3d_index(pos);
-        self.block[index] = id;
+        self.set_block_id_at(index, id);
         self.metadata.set(index, metadata);
     }
 
+    /// Internal function to set a block id at the given index while keeping the count
+    /// of non-air blocks of its section up-to-date.
+    #[inline]
+    fn set_block_id_at(&mut self, index: usize, id: u8) {
+        let prev_id = std::mem::replace(&mut self.block[index], id);
+        if (prev_id == block::AIR) != (id == block::AIR) {
+            let count = &mut self.section_blocks[(index & 0b1111111) / CHUNK_SECTION_HEIGHT];
+            if id == block::AIR {
+                *count -= 1;
+            } else {
+                *count += 1;
+            }
+        }
+    }
+
+    /// Return true if the given section, indexed from the bottom of the chunk, contains
+    /// only air blocks. This can be used to skip entire sections when iterating blocks.
+    /// Panics if the section index is not below [`CHUNK_SECTION_COUNT`].
+    #[inline]
+    pub fn is_section_empty(&self, section_y: usize) -> bool {
+        self.section_blocks[section_y] == 0
+    }
+
+    /// Recompute the count of non-air blocks in every section, this must be called after
+    /// the block array has been directly modified.
+    pub fn recompute_sections(&mut self) {
+        self.section_blocks.fill(0);
+        for (index, &id) in self.block.iter().enumerate() {
+            if id != block::AIR {
+                self.section_blocks[(index & 0b1111111) / CHUNK_SECTION_HEIGHT] += 1;
+            }
+        }
+    }
+
+    /// Get block metadata at the given global position (rebased to chunk-local).
+    /// Panics if Y component of the position is not between 0 and 128 (excluded).
+    #[inline]
+    pub fn get_metadata(&self, pos: IVec3) -> u8 {
+        self.metadata.get(calc_3d_index(pos))
+    }
+
+    /// Set block metadata at the given global position (rebased to chunk-local).
+    /// Panics if Y component of the position is not between 0 and 128 (excluded).
+    #[inline]
+    pub fn set_metadata(&mut self, pos: IVec3, metadata: u8) {
+        self.metadata.set(calc_3d_index(pos), metadata);
+    }
+
     /// Get block light level at the given global position (rebased to chunk-local).
     /// Panics if Y component of the position is not between 0 and 128 (excluded).
     #[inline]
@@ -200,7 +257,7 @@ impl Chunk {
             for z in from.z..from.z + size.z {
                 for y in from.y..from.y + size.y {
                     let index = calc_3d_index(IVec3::new(x, y, z));
-                    self.block[index] = id;
+                    self.set_block_id_at(index, id);
                     self.metadata.set(index, metadata);
                 }
             }
@@ -292,6 +349,26 @@ impl Chunk {
         }
     }
 
+    /// Compute the whole height map by scanning each column from the top down to the 
+    /// first block that has a non-zero light opacity. Unlike 
+    /// [`recompute_all_height`](Self::recompute_all_height), this doesn't touch the sky
+    /// light, this can be used after bulk edits where the light is computed elsewhere.
+    pub fn compute_height_map(&mut self) {
+        for x in 0..CHUNK_WIDTH {
+            for z in 0..CHUNK_WIDTH {
+                let mut pos = IVec3::new(x as i32, CHUNK_HEIGHT as i32, z as i32);
+                while pos.y > 0 {
+                    let (id, _) = self.get_block(pos - IVec3::Y);
+                    if block::material::get_light_opacity(id) != 0 {
+                        break;
+                    }
+                    pos.y -= 1;
+                }
+                self.set_height(pos, pos.y as u8);
+            }
+        }
+    }
+
     /// Write this chunk's data to the given writer, the data is copied from the start
     /// point for the given size. Note that this function may change the start and size
     /// of the area to be more efficient while while writing data.
@@ -383,6 +460,7 @@ impl ChunkNibbleArray3 {
 
     #[inline]
     pub fn get(&self, index: usize) -> u8 {
+        debug_assert!(index < CHUNK_3D_SIZE, "nibble index out of bounds");
         let slot = self.inner[index >> 1];
         if index & 1 == 0 {
             slot & 0x0F
@@ -393,6 +471,7 @@ impl ChunkNibbleArray3 {
 
     #[inline]
     pub fn set(&mut self, index: usize, value: u8) {
+        debug_assert!(index < CHUNK_3D_SIZE, "nibble index out of bounds");
         debug_assert!(value <= 0x0F);
         let slot = &mut self.inner[index >> 1];
         if index & 1 == 0 {
@@ -403,3 +482,129 @@ impl ChunkNibbleArray3 {
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::gen::{ChunkGenerator, OverworldGenerator};
+
+    use super::*;
+
+    #[test]
+    fn compute_height_map() {
+
+        let generator = OverworldGenerator::new(9999);
+        let mut state = Default::default();
+        let mut chunk = Chunk::new();
+        let chunk_mut = Arc::make_mut(&mut chunk);
+        generator.gen_terrain(3, -2, chunk_mut, &mut state);
+
+        // Add some floating block, the height is the top one even with air below.
+        chunk_mut.set_block(IVec3::new(4, 120, 4), block::GLASS, 0);
+        chunk_mut.set_block(IVec3::new(5, 127, 5), block::STONE, 0);
+        chunk_mut.set_block(IVec3::new(6, 126, 6), block::LEAVES, 0);
+        chunk_mut.height.fill(0);
+        chunk_mut.compute_height_map();
+
+        for x in 0..CHUNK_WIDTH as i32 {
+            for z in 0..CHUNK_WIDTH as i32 {
+                let top = (0..CHUNK_HEIGHT as i32).rev()
+                    .find(|&y| block::material::get_light_opacity(chunk_mut.get_block(IVec3::new(x, y, z)).0) != 0)
+                    .map(|y| y + 1)
+                    .unwrap_or(0);
+                assert_eq!(chunk_mut.get_height(IVec3::new(x, 0, z)) as i32, top, "column {x}/{z}");
+            }
+        }
+
+        // Glass doesn't block light, but leaves do.
+        assert_ne!(chunk_mut.get_height(IVec3::new(4, 0, 4)), 121);
+        assert_eq!(chunk_mut.get_height(IVec3::new(5, 0, 5)), 128);
+        assert_eq!(chunk_mut.get_height(IVec3::new(6, 0, 6)), 127);
+
+    }
+
+    #[test]
+    fn section_empty() {
+
+        let mut chunk = Chunk::new();
+        let chunk_mut = Arc::make_mut(&mut chunk);
+        assert!((0..CHUNK_SECTION_COUNT).all(|section_y| chunk_mut.is_section_empty(section_y)));
+
+        chunk_mut.fill_block(IVec3::new(0, 32, 0), IVec3::new(16, 16, 16), block::STONE, 0);
+        for section_y in 0..CHUNK_SECTION_COUNT {
+            assert_eq!(chunk_mut.is_section_empty(section_y), section_y != 2, "section {section_y}");
+        }
+
+        // Filling again the same blocks doesn't change the count.
+        chunk_mut.fill_block(IVec3::new(0, 32, 0), IVec3::new(16, 16, 16), block::DIRT, 0);
+        chunk_mut.set_block(IVec3::new(4, 40, 4), block::STONE, 0);
+        chunk_mut.fill_block(IVec3::new(0, 32, 0), IVec3::new(16, 16, 16), block::AIR, 0);
+        assert!(chunk_mut.is_section_empty(2));
+
+        chunk_mut.set_block(IVec3::new(4, 127, 4), block::STONE, 0);
+        assert!(!chunk_mut.is_section_empty(7));
+        chunk_mut.set_block(IVec3::new(4, 127, 4), block::AIR, 0);
+        assert!(chunk_mut.is_section_empty(7));
+
+        // Direct modifications of the block array require recomputation.
+        chunk_mut.block[calc_3d_index(IVec3::new(1, 70, 1))] = block::STONE;
+        chunk_mut.recompute_sections();
+        for section_y in 0..CHUNK_SECTION_COUNT {
+            assert_eq!(chunk_mut.is_section_empty(section_y), section_y != 4, "section {section_y}");
+        }
+
+    }
+
+    #[test]
+    fn nibble_array() {
+
+        let mut array = ChunkNibbleArray3::new(0);
+        array.set(10, 0x3);
+        array.set(11, 0xC);
+        assert_eq!(array.get(10), 0x3);
+        assert_eq!(array.get(11), 0xC);
+        assert_eq!(array.inner[5], 0xC3);
+
+        // Setting one nibble doesn't alter the other one of the same byte.
+        array.set(10, 0xF);
+        assert_eq!(array.get(11), 0xC);
+        array.set(11, 0x0);
+        assert_eq!(array.get(10), 0xF);
+
+        assert_eq!(ChunkNibbleArray3::new(15).get(CHUNK_3D_SIZE - 1), 15);
+
+    }
+
+    #[test]
+    fn nibble_accessors() {
+
+        let mut chunk = Chunk::new();
+        let chunk_mut = Arc::make_mut(&mut chunk);
+
+        // Adjacent Y positions share the same byte, at even and odd indices.
+        let even = IVec3::new(3, 64, 7);
+        let odd = IVec3::new(3, 65, 7);
+
+        chunk_mut.set_metadata(even, 9);
+        chunk_mut.set_metadata(odd, 6);
+        chunk_mut.set_block_light(even, 14);
+        chunk_mut.set_block_light(odd, 1);
+        chunk_mut.set_sky_light(even, 0);
+        chunk_mut.set_sky_light(odd, 7);
+
+        assert_eq!(chunk_mut.get_metadata(even), 9);
+        assert_eq!(chunk_mut.get_metadata(odd), 6);
+        assert_eq!(chunk_mut.get_block(odd), (block::AIR, 6));
+        assert_eq!(chunk_mut.get_block_light(even), 14);
+        assert_eq!(chunk_mut.get_block_light(odd), 1);
+        assert_eq!(chunk_mut.get_sky_light(even), 0);
+        assert_eq!(chunk_mut.get_sky_light(odd), 7);
+
+        // Neighbors are untouched.
+        assert_eq!(chunk_mut.get_metadata(even - IVec3::Y), 0);
+        assert_eq!(chunk_mut.get_sky_light(odd + IVec3::Y), 15);
+
+    }
+
+}
@@ -9,9 +9,10 @@ use crate::world::bound::RayTraceKind;
 use crate::block::material::Material;
 use crate::geom::{Face, BoundingBox};
 use crate::world::{World, Light};
+use crate::item::ItemStack;
 use crate::block;
 
-use super::{Entity, LivingKind, Base};
+use super::{Entity, LivingKind, Base, Item};
 
 
 /// Internal macro to make a refutable pattern assignment that just panic if refuted.
@@ -35,6 +36,32 @@ thread_local! {
     pub(super) static BOUNDING_BOX: RefCell<Vec<BoundingBox>> = const { RefCell::new(Vec::new()) };
 }
 
+/// Spawn an item entity at the given position with the random velocity of newly 
+/// created items, the world's random number generator is used. The item entity cannot
+/// be picked up for 10 ticks, its id is returned.
+/// 
+/// PARITY: The Notchian implementation uses the global `Math.random`, which cannot be
+///  reproduced, for a random yaw and the velocity. Only the velocity is randomized 
+///  here, with two draws from the world's random and the same float precision, the 
+///  yaw is purely visual and is not drawn in order to not consume the world's random.
+/// 
+/// REF: EntityItem::EntityItem
+pub fn spawn_item_with_spread(world: &mut World, pos: DVec3, stack: ItemStack) -> u32 {
+
+    let rand = world.get_rand_mut();
+    let vel_x = (rand.next_double() * 0.2 - 0.1) as f32;
+    let vel_z = (rand.next_double() * 0.2 - 0.1) as f32;
+
+    world.spawn_entity(Item::new_with(|base, item| {
+        base.persistent = true;
+        base.pos = pos;
+        base.vel = DVec3::new(vel_x as f64, 0.2, vel_z as f64);
+        item.stack = stack;
+        item.frozen_time = 10;
+    }))
+
+}
+
 /// Calculate the eye position of the given entity.
 pub fn calc_eye_pos(base: &Base) -> DVec3 {
     let mut pos = base.pos;
@@ -42,6 +69,21 @@ pub fn calc_eye_pos(base: &Base) -> DVec3 {
     pos
 }
 
+/// Return true if the eye of the given entity is under the surface of a water block.
+/// 
+/// REF: Entity::isInsideOfMaterial
+pub fn is_eye_in_water(world: &World, base: &Base) -> bool {
+    let eye_pos = calc_eye_pos(base);
+    let block_pos = eye_pos.floor().as_ivec3();
+    match world.get_block(block_pos) {
+        Some((block, metadata)) if block::material::get_material(block) == Material::Water => {
+            let height = block::fluid::get_actual_height(metadata) as f64 + 1.0 / 9.0;
+            eye_pos.y < block_pos.y as f64 + height
+        }
+        _ => false
+    }
+}
+
 /// Return true if the given bounding box is colliding with any fluid (given material).
 pub fn has_fluids_colliding(world: &World, bb: BoundingBox, material: Material) -> bool {
     debug_assert!(material.is_fluid());
@@ -89,9 +131,22 @@ pub fn calc_fluid_vel(world: &World, pos: IVec3, material: Material, metadata: u
 
     }
 
-    // TODO: Things with falling water.
+    // Falling fluid next to a solid block is pushing downward.
+    if block::fluid::is_falling(metadata) {
+        let solid_around = Face::HORIZONTAL.into_iter()
+            .flat_map(|face| [pos + face.delta(), pos + face.delta() + IVec3::Y])
+            .any(|face_pos| {
+                let (face_block, _) = world.get_block(face_pos).unwrap_or_default();
+                let face_material = block::material::get_material(face_block);
+                face_material != material && face_material != Material::Ice && face_material.is_solid()
+            });
+        if solid_around {
+            vel = vel.normalize_or_zero() + DVec3::new(0.0, -6.0, 0.0);
+        }
+    }
 
-    vel.normalize()
+    // NOTE: The fluid velocity may be zero, for example in the middle of still fluid.
+    vel.normalize_or_zero()
 
 }
 
@@ -104,12 +159,8 @@ pub fn get_entity_light(world: &World, base: &Base) -> Light {
 
 /// Find a the closest player entity (as defined in [`World`]) within the given radius.
 pub fn find_closest_player_entity(world: &World, center: DVec3, max_dist: f64) -> Option<(u32, &Entity, f64)> {
-    let max_dist_sq = max_dist.powi(2);
-    world.iter_player_entities()
-        .map(|(entity_id, entity)| (entity_id, entity, entity.0.pos.distance_squared(center)))
-        .filter(|&(_, _, dist_sq)| dist_sq <= max_dist_sq)
-        .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
-        .map(|(entity_id, entity, dist_sq)| (entity_id, entity, dist_sq.sqrt()))
+    let (entity_id, dist_sq) = world.nearest_player_entity(center, max_dist)?;
+    Some((entity_id, world.get_entity(entity_id)?, dist_sq.sqrt()))
 }
 
 /// Modify the look angles of this entity, limited to the given step. 
@@ -185,18 +236,18 @@ fn path_weight_animal(world: &World, pos: IVec3) -> f32 {
     if world.is_block(pos - IVec3::Y, block::GRASS) {
         10.0
     } else {
-        world.get_light(pos).brightness() - 0.5
+        world.get_brightness(pos) - 0.5
     }
 }
 
 /// Path weight function for mobs.
 fn path_weight_mob(world: &World, pos: IVec3) -> f32 {
-    0.5 - world.get_light(pos).brightness()
+    0.5 - world.get_brightness(pos)
 }
 
 /// Path weight function for Giant.
 fn path_weight_giant(world: &World, pos: IVec3) -> f32 {
-    world.get_light(pos).brightness() - 0.5
+    world.get_brightness(pos) - 0.5
 }
 
 /// Path weight function by default.
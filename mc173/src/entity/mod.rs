@@ -17,7 +17,7 @@ mod tick_state;
 mod tick_ai;
 mod tick_attack;
 
-use tick_state::tick_state;
+use tick_state::{tick_state, AIR_MAX_TIME};
 use tick_ai::tick_ai;
 use tick_attack::tick_attack;
 
@@ -172,10 +172,16 @@ pub struct Base {
     pub fire_time: u32,
     /// Remaining air ticks to breathe.
     pub air_time: u32,
+    /// Ticks accumulated while standing in a portal block, when it reaches the portal
+    /// delay, a portal event is triggered and the timer is reset. This timer decreases
+    /// when the entity is no longer in a portal.
+    pub portal_time: u32,
     /// A list of hurts to apply to the entity.
     pub hurt: Vec<Hurt>,
     /// If this entity is ridden, this contains its entity id.
     pub rider_id: Option<u32>,
+    /// If this entity is riding another entity, this contains the vehicle's entity id.
+    pub vehicle_id: Option<u32>,
     /// If this entity has thrown a bobber for fishing, this contains its entity id.
     pub bobber_id: Option<u32>,
     /// The random number generator used for this entity.
@@ -230,6 +236,10 @@ pub struct Living {
     /// persistent living entities. When this time reaches 600 and there are players in
     /// the 128.0 block distance, then this entity has 1/800 chance of despawning.
     pub wander_time: u16,
+    /// Horizontal distance walked by the entity, used to play step sounds.
+    pub walk_distance: f32,
+    /// The walk distance to reach before playing the next step sound.
+    pub next_step_distance: f32,
 }
 
 /// The data common to all projectile entities.
@@ -293,7 +303,16 @@ impl Default for Painting {
 pub struct Boat { }
 
 #[derive(Debug, Clone, Default)]
-pub enum Minecart { 
+pub struct Minecart {
+    /// The kind of minecart.
+    pub kind: MinecartKind,
+    /// The damage accumulated by the minecart when hit, it decreases each tick and the
+    /// minecart is destroyed when it goes above 40.
+    pub damage: u16,
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum MinecartKind { 
     /// A normal minecart for living entity transportation.
     #[default]
     Normal,
@@ -363,6 +382,10 @@ pub struct Human {
     pub sleeping: bool,
     /// True when the player is sneaking.
     pub sneaking: bool,
+    /// The equipped armor, in order: helmet, chestplate, leggings and boots.
+    pub armor: [ItemStack; 4],
+    /// The remainder of the damage reduced by armor, added to the next damage.
+    pub armor_damage_remainder: u16,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -796,18 +819,20 @@ impl Entity {
     /// size or sheep color or make a spider with jokey.
     pub fn init_natural_spawn(&mut self, _world: &mut World) {
 
-        let Entity(base, BaseKind::Living(_, living_kind)) = self else {
+        let Entity(base, BaseKind::Living(living, living_kind)) = self else {
             // Non-living entities cannot naturally spawn.
             return;
         };
         
-        // TODO: Spawning spider has 1% chance of being spider jockey.
+        // NOTE: Spider jockeys are spawned by the world after the spider is spawned.
 
         match living_kind {
             LivingKind::Slime(slime) => {
-                slime.size = 1 << base.rand.next_int_bounded(3) as u8;
+                // Our size is one less than the Notchian size of 1, 2 or 4.
+                let size = 1u16 << base.rand.next_int_bounded(3);
+                slime.size = (size - 1) as u8;
+                living.health = size * size;
                 self.sync();
-                // TODO: Set health depending on size
             }
             LivingKind::Sheep(sheep) => {
                 let rand = base.rand.next_int_bounded(100) as u8;
@@ -869,6 +894,25 @@ impl LivingKind {
         }
     }
 
+    /// Get the maximum health of this living entity, healing is clamped to this value.
+    /// Slime maximum health depends on its size, and tamed wolves have more health,
+    /// other kinds use the canonical [`EntityKind::max_health`].
+    pub fn max_health(&self) -> u16 {
+        match self {
+            LivingKind::Slime(slime) => (slime.size as u16 + 1).pow(2),
+            LivingKind::Wolf(wolf) if wolf.owner.is_some() => 20,
+            _ => self.entity_kind().max_health(),
+        }
+    }
+
+    /// Return true if this living entity can breathe under water, such entities never
+    /// consume their air time.
+    /// 
+    /// REF: EntityLiving::canBreatheUnderwater
+    pub fn can_breathe_water(&self) -> bool {
+        matches!(self, LivingKind::Squid(_))
+    }
+
 }
 
 impl ProjectileKind {
@@ -884,6 +928,31 @@ impl ProjectileKind {
         }
     }
 
+    /// Get the velocity subtracted on the Y axis on each tick, the fireball has no 
+    /// gravity because it has its own acceleration.
+    pub fn gravity(&self) -> f64 {
+        match self {
+            // PARITY: Arrows have the same gravity as thrown items in beta 1.7.3, the 
+            //  0.05 gravity of arrows only comes in later versions.
+            ProjectileKind::Arrow(_) => 0.03,
+            ProjectileKind::Egg(_) |
+            ProjectileKind::Snowball(_) => 0.03,
+            ProjectileKind::Fireball(_) => 0.0,
+            ProjectileKind::Bobber(_) => 0.04,
+        }
+    }
+
+    /// Get the factor applied to the velocity on each tick when not in water.
+    pub fn drag(&self) -> f64 {
+        match self {
+            ProjectileKind::Arrow(_) |
+            ProjectileKind::Egg(_) |
+            ProjectileKind::Snowball(_) => 0.99,
+            ProjectileKind::Fireball(_) => 0.95,
+            ProjectileKind::Bobber(_) => 0.92,
+        }
+    }
+
 }
 
 impl EntityKind {
@@ -957,6 +1026,31 @@ impl EntityKind {
         }
     }
 
+    /// Get the canonical maximum health of this entity kind, this is also the initial 
+    /// health of newly created entities. Entities without health return zero. Slime 
+    /// maximum health depends on its size, so the smallest size is returned here.
+    pub fn max_health(self) -> u16 {
+        match self {
+            EntityKind::Item => 5,
+            EntityKind::Human => 20,
+            EntityKind::Ghast => 10,
+            EntityKind::Slime => 1,
+            EntityKind::Pig => 10,
+            EntityKind::Chicken => 4,
+            EntityKind::Cow => 10,
+            EntityKind::Sheep => 10,
+            EntityKind::Squid => 10,
+            EntityKind::Wolf => 8,
+            EntityKind::Creeper => 20,
+            EntityKind::Giant => 200,
+            EntityKind::PigZombie => 20,
+            EntityKind::Skeleton => 20,
+            EntityKind::Spider => 20,
+            EntityKind::Zombie => 20,
+            _ => 0
+        }
+    }
+
     /// Returns the maximum number of entities of that kind that can be spawned at once
     /// when natural spawning in a single chunk.
     pub fn natural_spawn_max_chunk_count(self) -> usize {
@@ -1033,7 +1127,7 @@ macro_rules! impl_new_with {
         })*
 
     };
-    ( Living: $( $kind:ident ($def_health:expr) $($def:expr)?),* ) => {
+    ( Living: $( $kind:ident $($def:expr)?),* ) => {
         
         $(impl $kind {
             
@@ -1043,7 +1137,8 @@ macro_rules! impl_new_with {
             pub fn new_raw_with(func: impl FnOnce(&mut Base, &mut Living, &mut $kind)) -> Box<Entity> {
                 let mut entity = Box::new(Entity(def(), BaseKind::Living(def(), LivingKind::$kind(def()))));
                 let Entity(base, BaseKind::Living(living, LivingKind::$kind(this))) = &mut *entity else { unreachable!() };
-                living.health = $def_health;
+                living.health = EntityKind::$kind.max_health();
+                base.air_time = AIR_MAX_TIME;
                 $( ($def)(base, living, this); )?
                 func(base, living, this);
                 entity
@@ -1103,7 +1198,7 @@ macro_rules! impl_new_with {
 
 impl_new_with!(Base: 
     Item |_: &mut Base, this: &mut Item| { 
-        this.health = 5; 
+        this.health = EntityKind::Item.max_health(); 
         this.stack = ItemStack::new_block(block::STONE, 0);
     },
     Painting, 
@@ -1116,21 +1211,21 @@ impl_new_with!(Base:
     Tnt);
 
 impl_new_with!(Living: 
-    Human(20),
-    Ghast(10),
-    Slime(1),
-    Pig(10),
-    Chicken(4),
-    Cow(10),
-    Sheep(10),
-    Squid(10),
-    Wolf(8),
-    Creeper(20),
-    Giant(200),
-    PigZombie(20),
-    Skeleton(20),
-    Spider(20),
-    Zombie(20));
+    Human,
+    Ghast,
+    Slime,
+    Pig,
+    Chicken,
+    Cow,
+    Sheep,
+    Squid,
+    Wolf,
+    Creeper,
+    Giant,
+    PigZombie,
+    Skeleton,
+    Spider,
+    Zombie);
     
 impl_new_with!(Projectile: 
     Arrow,
@@ -1138,3 +1233,54 @@ impl_new_with!(Projectile:
     Fireball,
     Snowball,
     Bobber);
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::world::Dimension;
+
+    use super::*;
+
+    #[test]
+    fn slime_size() {
+
+        let mut world = World::new(Dimension::Overworld);
+
+        // Force the random generator to the biggest size.
+        let seed = (0..).find(|&seed| JavaRandom::new(seed).next_int_bounded(3) == 2).unwrap();
+
+        let mut slime = Slime::new_default(DVec3::new(8.5, 64.0, 8.5));
+        slime.0.rand = JavaRandom::new(seed);
+        slime.init_natural_spawn(&mut world);
+
+        let Entity(base, BaseKind::Living(living, LivingKind::Slime(slime))) = &*slime else { panic!() };
+        assert_eq!(slime.size, 3);
+        assert_eq!(living.health, 16);
+        assert!((base.bb.size() - DVec3::splat(2.4)).abs().max_element() < 1e-6);
+
+    }
+
+    #[test]
+    fn max_health() {
+
+        assert_eq!(EntityKind::Human.max_health(), 20);
+        assert_eq!(EntityKind::Giant.max_health(), 200);
+        assert_eq!(EntityKind::Chicken.max_health(), 4);
+        assert_eq!(EntityKind::Wolf.max_health(), 8);
+        assert_eq!(EntityKind::Arrow.max_health(), 0);
+
+        // Newly created entities have their maximum health.
+        let Entity(_, BaseKind::Living(living, living_kind)) = &*Giant::new_default(DVec3::ZERO) else { panic!() };
+        assert_eq!(living.health, 200);
+        assert_eq!(living_kind.max_health(), 200);
+
+        // Slime and tamed wolves are handled by the living kind.
+        let Entity(_, BaseKind::Living(_, living_kind)) = &*Slime::new_with(|_, _, slime| slime.size = 3) else { panic!() };
+        assert_eq!(living_kind.max_health(), 16);
+        let Entity(_, BaseKind::Living(_, living_kind)) = &*Wolf::new_with(|_, _, wolf| wolf.owner = Some("Notch".to_string())) else { panic!() };
+        assert_eq!(living_kind.max_health(), 20);
+
+    }
+
+}
@@ -14,14 +14,14 @@ use tracing::trace;
 
 use crate::block::material::Material;
 use crate::world::bound::RayTraceKind;
-use crate::world::{World, Event, EntityEvent};
+use crate::world::{World, Event, EntityEvent, BlockEvent, GroupSoundKind};
 use crate::entity::Chicken;
 use crate::item::{self, ItemStack};
 use crate::geom::{Face, BoundingBox};
 use crate::block;
 
 use super::{Entity,
-    BaseKind, ProjectileKind, LivingKind, 
+    BaseKind, ProjectileKind, LivingKind, MinecartKind,
     Base, Living, Hurt, ProjectileHit};
 
 use super::common::{self, let_expect};
@@ -52,12 +52,16 @@ pub(super) fn tick(world: &mut World, id: u32, entity: &mut Entity) {
         Entity(_, BaseKind::Painting(_)) => tick_painting(world, id, entity),
         Entity(_, BaseKind::FallingBlock(_)) => tick_falling_block(world, id, entity),
         Entity(_, BaseKind::Tnt(_)) => tick_tnt(world, id, entity),
+        Entity(_, BaseKind::Minecart(_)) => tick_minecart(world, id, entity),
         Entity(_, BaseKind::Living(_, _)) => tick_living(world, id, entity),
         Entity(_, BaseKind::Projectile(_, _)) => tick_projectile(world, id, entity),
         Entity(_, BaseKind::LightningBolt(_)) => tick_lightning_bolt(world, id, entity),
         Entity(_, _) => tick_base(world, id, entity),
     }
 
+    tick_vehicle(world, id, entity);
+    tick_rider(world, id, entity);
+
     // Finally check all major changes and push events if needed.
     let Entity(base, _) = entity;
 
@@ -81,6 +85,88 @@ fn tick_base(world: &mut World, id: u32, entity: &mut Entity) {
     tick_state(world, id, entity);
 }
 
+/// Tick an entity that may be ridden, its rider is moved on top of it, and the rider 
+/// is forgotten if it no longer exists or no longer rides this entity.
+/// 
+/// REF: Entity::updateRiderPosition
+fn tick_vehicle(world: &mut World, id: u32, entity: &mut Entity) {
+
+    let Some(rider_id) = entity.0.rider_id else { return };
+
+    let rider_pos = match world.get_entity_mut(rider_id) {
+        Some(rider) if rider.0.vehicle_id == Some(id) => {
+            let pos = get_rider_pos(entity, rider);
+            if rider.0.pos != pos {
+                set_rider_pos(rider, pos);
+                Some(pos)
+            } else {
+                None
+            }
+        }
+        _ => {
+            entity.0.rider_id = None;
+            None
+        }
+    };
+
+    if let Some(pos) = rider_pos {
+        world.push_event(Event::Entity { id: rider_id, inner: EntityEvent::Position { pos } });
+    }
+
+}
+
+/// Tick an entity that may ride a vehicle, it is moved on top of its vehicle and it
+/// stops riding if the vehicle no longer exists or is no longer ridden by it.
+/// 
+/// REF: Entity::updateRidden
+fn tick_rider(world: &mut World, id: u32, entity: &mut Entity) {
+
+    let Some(vehicle_id) = entity.0.vehicle_id else { return };
+
+    match world.get_entity(vehicle_id) {
+        Some(vehicle) if vehicle.0.rider_id == Some(id) => {
+            let pos = get_rider_pos(vehicle, entity);
+            set_rider_pos(entity, pos);
+        }
+        _ => {
+            entity.0.vehicle_id = None;
+            world.push_event(Event::Entity { id, inner: EntityEvent::Ride { vehicle_id: None } });
+        }
+    }
+
+}
+
+/// Get the position of a rider entity when on top of the given vehicle entity.
+/// 
+/// REF: Entity::getMountedYOffset, Entity::getYOffset
+fn get_rider_pos(vehicle: &Entity, rider: &Entity) -> DVec3 {
+
+    let Entity(vehicle_base, vehicle_kind) = vehicle;
+    let height = vehicle_base.bb.size_y();
+
+    let mut offset = match vehicle_kind {
+        BaseKind::Boat(_) | BaseKind::Minecart(_) => -0.3,
+        BaseKind::Living(_, LivingKind::Spider(_)) => height * 0.75 - 0.5,
+        _ => height * 0.75,
+    };
+
+    // Humans are sitting in their vehicle.
+    if let BaseKind::Living(_, LivingKind::Human(_)) = rider.1 {
+        offset -= 0.5;
+    }
+
+    vehicle_base.pos + DVec3::Y * offset
+
+}
+
+/// Move a rider entity to the given position, the rider has no velocity of its own and
+/// cannot accumulate fall distance while riding.
+fn set_rider_pos(rider: &mut Entity, pos: DVec3) {
+    rider.teleport(pos);
+    rider.0.vel = DVec3::ZERO;
+    rider.0.fall_distance = 0.0;
+}
+
 /// REF: EntityItem::onUpdate
 fn tick_item(world: &mut World, id: u32, entity: &mut Entity) {
 
@@ -272,9 +358,49 @@ fn tick_tnt(world: &mut World, id: u32, entity: &mut Entity) {
 
 }
 
+/// REF: EntityMinecart::onUpdate, EntityMinecart::attackEntityFrom
+fn tick_minecart(world: &mut World, id: u32, entity: &mut Entity) {
+
+    tick_base(world, id, entity);
+    let_expect!(Entity(base, BaseKind::Minecart(minecart)) = entity);
+
+    while let Some(hurt) = base.hurt.pop() {
+        minecart.damage = minecart.damage.saturating_add(hurt.damage.saturating_mul(10));
+    }
+
+    if minecart.damage > 40 {
+
+        world.remove_entity(id, "minecart destroyed");
+        world.spawn_loot(base.pos, ItemStack::new_single(item::MINECART, 0), 0.0);
+
+        match &minecart.kind {
+            MinecartKind::Normal => {}
+            MinecartKind::Chest { inv } => {
+                for &stack in inv.iter() {
+                    if !stack.is_empty() {
+                        world.spawn_scattered_loot(base.pos, stack);
+                    }
+                }
+                world.spawn_loot(base.pos, ItemStack::new_block(block::CHEST, 0), 0.0);
+            }
+            MinecartKind::Furnace { .. } => {
+                world.spawn_loot(base.pos, ItemStack::new_block(block::FURNACE, 0), 0.0);
+            }
+        }
+
+        return;
+
+    }
+
+    minecart.damage = minecart.damage.saturating_sub(1);
+
+}
+
 /// REF: EntityLiving::onUpdate
 fn tick_living(world: &mut World, id: u32, entity: &mut Entity) {
 
+    let prev_pos = entity.0.pos;
+
     // Super call.
     tick_base(world, id, entity);
 
@@ -295,6 +421,7 @@ fn tick_living(world: &mut World, id: u32, entity: &mut Entity) {
     living.yaw_velocity *= 0.9;
 
     tick_living_pos(world, id, base, living, living_kind);
+    tick_living_step(world, base, living, prev_pos);
     tick_living_push(world, id, base);
     
 }
@@ -531,17 +658,7 @@ fn tick_projectile(world: &mut World, id: u32, entity: &mut Entity) {
     base.look.y = f64::atan2(base.vel.y, base.vel.xz().length()) as f32;
     
     // The velocity update depends on projectile kind.
-    if let ProjectileKind::Fireball(fireball) = projectile_kind {
-        
-        if base.in_water {
-            base.vel *= 0.8;
-        } else {
-            base.vel *= 0.95;
-        }
-
-        base.vel += fireball.accel;
-
-    } else if let ProjectileKind::Bobber(bobber) = projectile_kind {
+    if let ProjectileKind::Bobber(bobber) = projectile_kind {
 
         // PARITY: The bobber in Notchian implementation is really weird, so I just
         // tried here to make a better logic that do not use the block collision
@@ -594,10 +711,10 @@ fn tick_projectile(world: &mut World, id: u32, entity: &mut Entity) {
             base.vel.y -= (base.rand.next_float() * base.rand.next_float() * base.rand.next_float()) as f64 * 0.2;
         }
 
-        let mut vel_factor = 0.92;
+        let mut vel_factor = projectile_kind.drag();
         // TODO: vel_factor = 0.5 if collided.
 
-        base.vel.y += (fluid_boost * 2.0 - 1.0) * 0.04;
+        base.vel.y += (fluid_boost * 2.0 - 1.0) * projectile_kind.gravity();
         if fluid_boost > 0.0 {
             vel_factor *= 0.9;
             base.vel.y *= 0.8;
@@ -610,10 +727,14 @@ fn tick_projectile(world: &mut World, id: u32, entity: &mut Entity) {
         if base.in_water {
             base.vel *= 0.8;
         } else {
-            base.vel *= 0.99;
+            base.vel *= projectile_kind.drag();
         }
 
-        base.vel.y -= 0.03;
+        base.vel.y -= projectile_kind.gravity();
+
+        if let ProjectileKind::Fireball(fireball) = projectile_kind {
+            base.vel += fireball.accel;
+        }
     
     }
 
@@ -662,12 +783,14 @@ fn tick_lightning_bolt(world: &mut World, id: u32, entity: &mut Entity) {
 }
 
 /// Tick a living entity to push/being pushed an entity.
-fn tick_living_push(world: &mut World, _id: u32, base: &mut Base) {
+/// 
+/// REF: Entity::applyEntityCollision
+fn tick_living_push(world: &mut World, id: u32, base: &mut Base) {
 
     // TODO: pushing minecart
 
     // For each colliding entity, precalculate the velocity to add to both entities.
-    for (_, push_entity) in world.iter_entities_colliding_mut(base.bb.inflate(DVec3::new(0.2, 0.0, 0.2))) {
+    for (push_id, push_entity) in world.iter_entities_colliding_mut(base.bb.inflate(DVec3::new(0.2, 0.0, 0.2))) {
         
         let Entity(push_base, push_base_kind) = push_entity;
 
@@ -678,28 +801,44 @@ fn tick_living_push(world: &mut World, _id: u32, base: &mut Base) {
             _ => continue // Other entities cannot be pushed.
         }
 
+        // An entity doesn't push its rider or the entity it is riding.
+        if base.rider_id == Some(push_id) || push_base.rider_id == Some(id) {
+            continue;
+        }
+
+        if base.no_clip || push_base.no_clip {
+            continue;
+        }
+
         let mut dx = base.pos.x - push_base.pos.x;
         let mut dz = base.pos.z - push_base.pos.z;
         let mut delta = f64::max(dx.abs(), dz.abs());
-        
-        if delta >= 0.01 {
-            
-            delta = delta.sqrt();
-            dx /= delta;
-            dz /= delta;
 
-            let delta_inv = 1.0 / delta;
-            dx *= delta_inv;
-            dz *= delta_inv;
-            dx *= 0.05;
-            dz *= 0.05;
+        // PARITY: The Notchian implementation doesn't push entities that are exactly at
+        // the same horizontal position, we pick a random direction instead so that they
+        // don't stay stacked forever.
+        if delta < 0.01 {
+            let angle = base.rand.next_double() * std::f64::consts::TAU;
+            dx = angle.cos() * 0.01;
+            dz = angle.sin() * 0.01;
+            delta = 0.01;
+        }
 
-            let delta = DVec3::new(dx, 0.0, dz);
-            
-            push_base.vel -= delta;
-            base.vel += delta;
+        delta = delta.sqrt();
+        dx /= delta;
+        dz /= delta;
 
-        }
+        // The push is stronger when entities are closer, but capped.
+        let delta_inv = f64::min(1.0 / delta, 1.0);
+        dx *= delta_inv;
+        dz *= delta_inv;
+        dx *= 0.05;
+        dz *= 0.05;
+
+        let delta = DVec3::new(dx, 0.0, dz);
+        
+        push_base.vel -= delta;
+        base.vel += delta;
 
     }
 
@@ -763,11 +902,16 @@ fn tick_living_pos(world: &mut World, id: u32, base: &mut Base, living: &mut Liv
     // All living entities have step height 0.5;
     let step_height = 0.5;
 
+    // The fall distance just before moving, used to compute fall damage on landing, it
+    // must be read after any reset of the fall distance (water, ladder, cobweb).
+    let fall_distance;
+
     // REF: EntityFlying::moveEntityWithHeading
     let flying = matches!(living_kind, LivingKind::Ghast(_));
 
     if base.in_water {
         apply_living_accel(base, living, 0.02);
+        fall_distance = base.fall_distance;
         apply_base_vel(world, id, base, base.vel, step_height, false);
         base.vel *= 0.8;
         if !flying {
@@ -776,6 +920,7 @@ fn tick_living_pos(world: &mut World, id: u32, base: &mut Base, living: &mut Liv
         // TODO: If collided horizontally
     } else if base.in_lava {
         apply_living_accel(base, living, 0.02);
+        fall_distance = base.fall_distance;
         apply_base_vel(world, id, base, base.vel, step_height, false);
         base.vel *= 0.5;
         if !flying {
@@ -804,11 +949,29 @@ fn tick_living_pos(world: &mut World, id: u32, base: &mut Base, living: &mut Liv
 
         apply_living_accel(base, living, vel_factor);
         
-        // TODO: Is on ladder
+        // REF: EntityLiving::isOnLadder
+        let on_ladder = world.is_block(IVec3 {
+            x: base.pos.x.floor() as i32,
+            y: base.bb.min.y.floor() as i32,
+            z: base.pos.z.floor() as i32,
+        }, block::LADDER);
 
+        // Climbing a ladder limits the speed and continuously cancel fall.
+        if on_ladder {
+            base.vel.x = base.vel.x.clamp(-0.15, 0.15);
+            base.vel.z = base.vel.z.clamp(-0.15, 0.15);
+            base.vel.y = base.vel.y.max(-0.15);
+            base.fall_distance = 0.0;
+        }
+
+        let prev_vel = base.vel;
+        fall_distance = base.fall_distance;
         apply_base_vel(world, id, base, base.vel, step_height, false);
 
-        // TODO: Collided horizontally and on ladder
+        // If the entity collided horizontally while on ladder, it climbs it.
+        if on_ladder && (base.vel.x != prev_vel.x || base.vel.z != prev_vel.z) {
+            base.vel.y = 0.2;
+        }
 
         if flying {
             base.vel *= slipperiness as f64;
@@ -821,6 +984,56 @@ fn tick_living_pos(world: &mut World, id: u32, base: &mut Base, living: &mut Liv
 
     }
 
+    // The fall distance is reset when landing, we apply fall damage if the fall was 
+    // higher than 3 blocks.
+    // REF: EntityLiving::fall
+    if base.on_ground && fall_distance > 0.0 {
+        let damage = (fall_distance - 3.0).ceil();
+        if damage > 0.0 {
+            base.hurt.push(Hurt { damage: damage as u16, origin_id: None });
+        }
+    }
+
+}
+
+/// Accumulate the distance walked by a living entity and push a step sound event for 
+/// the block below when it has walked enough, only if step sounds are enabled.
+/// 
+/// REF: Entity::moveEntity
+fn tick_living_step(world: &mut World, base: &mut Base, living: &mut Living, prev_pos: DVec3) {
+
+    let delta = base.pos - prev_pos;
+    living.walk_distance += (delta.x * delta.x + delta.z * delta.z).sqrt() as f32 * 0.6;
+
+    if living.walk_distance <= living.next_step_distance {
+        return;
+    }
+
+    let ground_pos = DVec3::new(base.pos.x, base.pos.y - 0.2, base.pos.z).floor().as_ivec3();
+    let Some((ground_id, _)) = world.get_block(ground_pos) else { return };
+    if ground_id == block::AIR {
+        return;
+    }
+
+    living.next_step_distance += 1.0;
+    if !world.has_step_sounds() {
+        return;
+    }
+
+    // Snow layers above the ground block are stepped on instead.
+    let group = if world.is_block(ground_pos + IVec3::Y, block::SNOW) {
+        block::sound_group(block::SNOW)
+    } else if block::material::is_fluid(ground_id) {
+        return;
+    } else {
+        block::sound_group(ground_id)
+    };
+
+    world.push_event(Event::Block {
+        pos: ground_pos,
+        inner: BlockEvent::GroupSound { group, kind: GroupSoundKind::Step },
+    });
+
 }
 
 /// Update a living entity velocity according to its strafing/forward accel.
@@ -948,3 +1161,296 @@ pub fn apply_base_vel(world: &mut World, _id: u32, base: &mut Base, delta: DVec3
     };
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::test::new_floor_world;
+    use crate::entity::{Arrow, Item, Minecart, Pig};
+    use crate::world::{Dimension, Event, EntityEvent};
+    use crate::chunk::Chunk;
+    use crate::rand::JavaRandom;
+
+    use super::*;
+
+    #[test]
+    fn item_water_flow() {
+
+        let mut world = new_floor_world(block::STONE);
+
+        // Water flowing toward positive X.
+        world.set_block(IVec3::new(2, 64, 8), block::WATER_STILL, 0);
+        for distance in 1..8 {
+            world.set_block(IVec3::new(2 + distance, 64, 8), block::WATER_MOVING, distance as u8);
+        }
+
+        let id = world.spawn_entity(Item::new_with(|base, item| {
+            base.pos = DVec3::new(4.5, 64.0, 8.5);
+            item.stack = ItemStack::new_single(item::STICK, 0);
+        }));
+
+        for _ in 0..20 {
+            world.tick();
+        }
+
+        let Some(Entity(base, _)) = world.get_entity(id) else { panic!() };
+        assert!(base.pos.x > 5.0, "item should drift with the flow: {}", base.pos);
+        assert!((base.pos.z - 8.5).abs() < 0.1);
+
+    }
+
+    #[test]
+    fn fall_damage() {
+
+        let mut world = new_floor_world(block::STONE);
+
+        // A column of water where the first pig falls.
+        for y in 64..67 {
+            world.set_block(IVec3::new(4, y, 4), block::WATER_STILL, 0);
+        }
+
+        // Seed all random number generators so that pigs always wander the same way.
+        *world.get_rand_mut() = JavaRandom::new(0);
+
+        let water_id = world.spawn_entity(Pig::new_with(|base, _, _| {
+            base.pos = DVec3::new(4.5, 80.0, 4.5);
+            base.rand = JavaRandom::new(0);
+        }));
+
+        let ground_id = world.spawn_entity(Pig::new_with(|base, _, _| {
+            base.pos = DVec3::new(10.5, 72.0, 10.5);
+            base.rand = JavaRandom::new(1);
+        }));
+
+        for _ in 0..60 {
+            world.tick();
+        }
+
+        let Some(Entity(base, BaseKind::Living(living, _))) = world.get_entity(water_id) else { panic!() };
+        assert!(base.fall_distance < 1.0);
+        assert_eq!(living.health, 10);
+
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(ground_id) else { panic!() };
+        assert!(living.health < 10, "pig should take fall damage: {}", living.health);
+
+    }
+
+    #[test]
+    fn fall_damage_climb() {
+
+        let mut world = new_floor_world(block::STONE);
+
+        // A ladder and a cobweb on the ground, the last pig lands directly on ground.
+        world.set_block(IVec3::new(2, 64, 4), block::LADDER, 4);
+        world.set_block(IVec3::new(6, 64, 4), block::COBWEB, 0);
+
+        // Each pig is about to land after a long fall, offset on X to not collide with
+        // the ladder's bounding box.
+        let spawn_falling = |world: &mut World, x: f64| world.spawn_entity(Pig::new_with(|base, _, _| {
+            base.pos = DVec3::new(x, 64.1, 4.5);
+            base.vel.y = -0.5;
+            base.fall_distance = 10.0;
+        }));
+
+        let ladder_id = spawn_falling(&mut world, 2.4);
+        let cobweb_id = spawn_falling(&mut world, 6.4);
+        let ground_id = spawn_falling(&mut world, 10.4);
+
+        // Fall damage is applied on the tick after landing.
+        world.tick();
+        world.tick();
+
+        let health = |world: &World, id: u32| {
+            let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(id) else { panic!() };
+            living.health
+        };
+
+        assert_eq!(health(&world, ladder_id), 10);
+        assert_eq!(health(&world, cobweb_id), 10);
+        assert_eq!(health(&world, ground_id), 3);
+
+    }
+
+    #[test]
+    fn break_chest_minecart() {
+
+        let mut world = new_floor_world(block::STONE);
+
+        let mut inv = Box::<[ItemStack; 27]>::default();
+        inv[0] = ItemStack::new_block_sized(block::COBBLESTONE, 0, 40);
+        inv[13] = ItemStack::new_single(item::APPLE, 0);
+
+        let id = world.spawn_entity(Minecart::new_with(|base, minecart| {
+            base.pos = DVec3::new(8.5, 64.0, 8.5);
+            minecart.kind = MinecartKind::Chest { inv };
+        }));
+
+        // A single punch is not enough to break it.
+        world.damage_entity(id, 1, None);
+        world.tick();
+        assert!(world.get_entity(id).is_some());
+
+        for _ in 0..4 {
+            world.damage_entity(id, 1, None);
+            world.tick();
+        }
+
+        assert!(world.get_entity(id).is_none());
+
+        let count = |id: u16| world.iter_entities()
+            .filter_map(|(_, entity)| match entity {
+                Entity(_, BaseKind::Item(item)) if item.stack.id == id => Some(item.stack.size),
+                _ => None,
+            })
+            .sum::<u16>();
+
+        assert_eq!(count(item::MINECART), 1);
+        assert_eq!(count(block::CHEST as u16), 1);
+        assert_eq!(count(block::COBBLESTONE as u16), 40);
+        assert_eq!(count(item::APPLE), 1);
+
+    }
+
+    #[test]
+    fn living_push() {
+
+        let mut world = new_floor_world(block::STONE);
+
+        let pos = DVec3::new(8.5, 64.0, 8.5);
+        let id0 = world.spawn_entity(Pig::new_default(pos));
+        let id1 = world.spawn_entity(Pig::new_default(pos));
+
+        for _ in 0..10 {
+            world.tick();
+        }
+
+        let pos0 = world.get_entity(id0).unwrap().0.pos;
+        let pos1 = world.get_entity(id1).unwrap().0.pos;
+        assert!(pos0.xz().distance(pos1.xz()) > 0.1, "pigs have not been separated");
+
+    }
+
+    #[test]
+    fn item_despawn() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let id = world.spawn_entity(Item::new_with(|base, item| {
+            base.pos = DVec3::new(8.5, 64.0, 8.5);
+            base.lifetime = 5998;
+            item.stack = ItemStack::new_single(item::STICK, 0);
+        }));
+
+        world.tick();
+        assert!(world.contains_entity(id));
+
+        world.swap_events(Some(Vec::new()));
+        world.tick();
+        assert!(!world.contains_entity(id));
+
+        let events = world.swap_events(None).unwrap();
+        assert!(events.iter().any(|event| matches!(event, Event::Entity { id: event_id, inner: EntityEvent::Remove } if *event_id == id)));
+
+    }
+
+    #[test]
+    fn arrow_gravity() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let id = world.spawn_entity(Arrow::new_with(|base, _, _| {
+            base.pos = DVec3::new(2.5, 100.0, 8.5);
+            base.vel = DVec3::new(1.0, 0.0, 0.0);
+        }));
+
+        let mut vel = DVec3::new(1.0, 0.0, 0.0);
+        for _ in 0..5 {
+
+            world.tick();
+
+            let Some(Entity(base, _)) = world.get_entity(id) else { panic!() };
+            vel *= 0.99;
+            vel.y -= 0.03;
+            assert!(base.vel.abs_diff_eq(vel, 1e-9), "expected {vel}, got {}", base.vel);
+
+        }
+
+    }
+
+    #[test]
+    fn drowning() {
+
+        let mut chunk = Chunk::new();
+        let chunk_mut = std::sync::Arc::make_mut(&mut chunk);
+        chunk_mut.fill_block(IVec3::ZERO, IVec3::new(16, 64, 16), block::STONE, 0);
+        chunk_mut.fill_block(IVec3::new(0, 64, 0), IVec3::new(16, 3, 16), block::WATER_STILL, 0);
+        chunk_mut.fill_block(IVec3::new(0, 67, 0), IVec3::new(16, 1, 16), block::STONE, 0);
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, chunk);
+
+        let zombie_id = world.spawn_entity(crate::entity::Zombie::new_with(|base, _, _| {
+            base.pos = DVec3::new(4.5, 64.0, 4.5);
+            base.persistent = true;
+        }));
+
+        let squid_id = world.spawn_entity(crate::entity::Squid::new_with(|base, _, _| {
+            base.pos = DVec3::new(10.5, 65.0, 10.5);
+            base.persistent = true;
+        }));
+
+        world.tick();
+        let Some(Entity(base, BaseKind::Living(living, _))) = world.get_entity(zombie_id) else { panic!() };
+        assert!(base.air_time < 300);
+        assert_eq!(living.health, 20);
+
+        for _ in 0..320 {
+            world.tick();
+        }
+
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(zombie_id) else { panic!() };
+        assert!(living.health < 20, "zombie should be drowning");
+
+        let Some(Entity(base, BaseKind::Living(living, _))) = world.get_entity(squid_id) else { panic!() };
+        assert_eq!(base.air_time, 300);
+        assert_eq!(living.health, 10);
+
+    }
+
+    #[test]
+    fn step_sounds() {
+
+        /// Make a pig walk on stone and return the step sound events.
+        fn walk(step_sounds: bool) -> Vec<Event> {
+
+            let mut world = new_floor_world(block::STONE);
+            world.set_step_sounds(step_sounds);
+
+            world.spawn_entity(Pig::new_with(|base, _, _| {
+                base.pos = DVec3::new(2.5, 64.0, 8.5);
+                base.vel = DVec3::new(1.0, 0.0, 0.0);
+            }));
+
+            world.swap_events(Some(Vec::new()));
+            for _ in 0..10 {
+                world.tick();
+            }
+
+            world.swap_events(None).unwrap().into_iter()
+                .filter(|event| matches!(event, Event::Block { inner: BlockEvent::GroupSound { kind: GroupSoundKind::Step, .. }, .. }))
+                .collect()
+
+        }
+
+        assert!(walk(false).is_empty());
+
+        let events = walk(true);
+        assert!(!events.is_empty());
+        assert!(events.iter().all(|event| matches!(event, Event::Block { inner: BlockEvent::GroupSound { group: block::SoundGroup::Stone, .. }, .. })));
+
+    }
+
+}
@@ -6,7 +6,7 @@ use glam::{Vec2, DVec3, IVec3};
 use tracing::trace;
 
 use crate::entity::{Fireball, Path, LookTarget};
-use crate::world::{World, Event, EntityEvent};
+use crate::world::{World, Event, EntityEvent, Difficulty};
 
 use super::{Entity, BaseKind, LivingKind, EntityCategory};
 use super::common::{self, let_expect};
@@ -112,6 +112,11 @@ fn tick_ground_ai(world: &mut World, id: u32, entity: &mut Entity) {
     const PATH_FINDER_MAX_DIST: f32 = 16.0;
     /// Look step when looking at an attacked entity: 30/30 deg
     const LOOK_STEP: Vec2 = Vec2::new(0.5235987755982988, 0.5235987755982988);
+    /// Maximum distance for searching an attack target.
+    const TARGET_SEARCH_MAX_DIST: f64 = 16.0;
+    /// Maximum distance of the attack target before releasing it.
+    /// PARITY: Notchian implementation never release its target unless it's dead.
+    const TARGET_RELEASE_MAX_DIST: f64 = 32.0;
 
     /// Internal structure that defines the target for the path finder.
     struct Target {
@@ -136,9 +141,18 @@ fn tick_ground_ai(world: &mut World, id: u32, entity: &mut Entity) {
     // Start by finding an attack target, or attack the existing one.
     if let Some(target_id) = living.attack_target {
 
-        if let Some(Entity(target_base, BaseKind::Living(_, _))) = world.get_entity(target_id) {
+        // The target is released if it's dead or if it escaped too far away.
+        let target = match world.get_entity(target_id) {
+            Some(Entity(target_base, BaseKind::Living(target_living, _))) 
+            if target_living.health > 0 => {
+                let dist_squared = base.pos.distance_squared(target_base.pos);
+                (dist_squared <= TARGET_RELEASE_MAX_DIST.powi(2)).then_some((target_base, dist_squared))
+            }
+            _ => None
+        };
+
+        if let Some((target_base, dist_squared)) = target {
 
-            let dist_squared = base.pos.distance_squared(target_base.pos);
             let eye_track = common::can_eye_track(world, base, target_base);
 
             target_pos = Some(Target { 
@@ -149,33 +163,37 @@ fn tick_ground_ai(world: &mut World, id: u32, entity: &mut Entity) {
             tick_attack(world, id, entity, target_id, dist_squared, eye_track, &mut should_strafe);
 
         } else {
-            // Entity has been release by the attack function.
+            // Entity has been released because it's dead or escaped.
             trace!("entity #{id}, attack target released");
             living.attack_target = None;
         }
 
     } else  {
         
-        // Depending on the entity, we search an attack target or not...
-        let search_around = match living_kind {
-            LivingKind::Creeper(_) => true,
-            LivingKind::Giant(_) => true,
-            LivingKind::Skeleton(_) => true,
-            LivingKind::Zombie(_) => true,
-            LivingKind::PigZombie(pig_zombie) => pig_zombie.anger,
-            LivingKind::Wolf(wolf) => wolf.angry,
-            LivingKind::Spider(_) => common::get_entity_light(world, base).brightness() < 0.5,
-            _ => false,
+        // Depending on the entity, we search an attack target or not, mobs also need
+        // to see their target, but spiders and wolves don't.
+        let (search_around, need_sight) = match living_kind {
+            LivingKind::Creeper(_) => (true, true),
+            LivingKind::Giant(_) => (true, true),
+            LivingKind::Skeleton(_) => (true, true),
+            LivingKind::Zombie(_) => (true, true),
+            LivingKind::PigZombie(pig_zombie) => (pig_zombie.anger, true),
+            LivingKind::Wolf(wolf) => (wolf.angry, false),
+            LivingKind::Spider(_) => (common::get_entity_light(world, base).brightness(world.get_dimension()) < 0.5, false),
+            _ => (false, false),
         };
 
         if search_around {
-            if let Some((target_id, Entity(target_base, _), _)) = common::find_closest_player_entity(world, base.pos, 16.0) {
-                trace!("entity #{id}, attack target found: #{target_id}");
-                living.attack_target = Some(target_id);
-                target_pos = Some(Target { 
-                    pos: target_base.pos, 
-                    overwrite: true,
-                });
+            if let Some((target_id, _)) = world.nearest_player_entity(base.pos, TARGET_SEARCH_MAX_DIST) {
+                let Entity(target_base, _) = world.get_entity(target_id).unwrap();
+                if !need_sight || common::can_eye_track(world, base, target_base) {
+                    trace!("entity #{id}, attack target found: #{target_id}");
+                    living.attack_target = Some(target_id);
+                    target_pos = Some(Target { 
+                        pos: target_base.pos, 
+                        overwrite: true,
+                    });
+                }
             }
         }
 
@@ -342,8 +360,20 @@ fn tick_slime_ai(world: &mut World, id: u32, entity: &mut Entity) {
 
     // Searching the closest player entities behind 16.0 blocks.
     let closest_player = common::find_closest_player_entity(world, base.pos, 16.0);
-    if let Some((_, Entity(closest_base, _), _)) = closest_player {
+    let mut attack_player_id = None;
+    if let Some((closest_id, Entity(closest_base, _), closest_dist)) = closest_player {
         common::update_look_at_entity_by_step(base, closest_base, LOOK_STEP);
+        // Slimes bigger than the smallest ones are attacking the player when touching.
+        // REF: EntitySlime::onCollideWithPlayer
+        let size = slime.size as f64 + 1.0;
+        if slime.size > 0 && closest_dist < 0.6 * size && common::can_eye_track(world, base, closest_base) {
+            attack_player_id = Some(closest_id);
+        }
+    }
+
+    let closest_player = closest_player.is_some();
+    if let Some(player_id) = attack_player_id {
+        world.damage_entity(player_id, slime.size as u16 + 1, Some(id));
     }
 
     let mut set_jumping = false;
@@ -358,7 +388,7 @@ fn tick_slime_ai(world: &mut World, id: u32, entity: &mut Entity) {
         
         slime.jump_remaining_time = base.rand.next_int_bounded(20) as u32 + 10;
 
-        if closest_player.is_some() {
+        if closest_player {
             slime.jump_remaining_time /= 3;
         }
 
@@ -549,6 +579,14 @@ fn tick_natural_despawn(world: &mut World, id: u32, entity: &mut Entity) -> bool
         return false;
     };
 
+    // Hostile mobs are immediately removed in peaceful, even if persistent.
+    // REF: EntityMob::onUpdate, EntityGhast::onUpdate, EntitySlime::onUpdate
+    let hostile = living_kind.entity_kind().category() == EntityCategory::Mob || matches!(living_kind, LivingKind::Ghast(_));
+    if hostile && world.get_difficulty() == Difficulty::Peaceful {
+        world.remove_entity(id, "peaceful difficulty");
+        return true;
+    }
+
     // Can't despawn persistent entities.
     if living.artificial {
         return false;
@@ -565,7 +603,7 @@ fn tick_natural_despawn(world: &mut World, id: u32, entity: &mut Entity) -> bool
     // faster increment.
     living.wander_time = living.wander_time.saturating_add(1);
     if living_kind.entity_kind().category() == EntityCategory::Mob {
-        if common::get_entity_light(world, base).brightness() > 0.5 {
+        if common::get_entity_light(world, base).brightness(world.get_dimension()) > 0.5 {
             living.wander_time = living.wander_time.saturating_add(2);
         }
     }
@@ -575,7 +613,14 @@ fn tick_natural_despawn(world: &mut World, id: u32, entity: &mut Entity) -> bool
         return false;
     }
 
-    if let Some((_, _, dist)) = common::find_closest_player_entity(world, base.pos, 128.0) {
+    // Dead players are also accounted, so that entities are not despawned around a 
+    // player that is about to respawn.
+    let closest_dist = world.iter_player_entities()
+        .map(|(_, Entity(player_base, _))| player_base.pos.distance(base.pos))
+        .filter(|&dist| dist <= 128.0)
+        .min_by(f64::total_cmp);
+
+    if let Some(dist) = closest_dist {
         if dist < 32.0 {
             living.wander_time = 0;
             false
@@ -593,3 +638,85 @@ fn tick_natural_despawn(world: &mut World, id: u32, entity: &mut Entity) -> bool
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::test::new_floor_world;
+    use crate::entity::{Human, Zombie, Giant};
+    use crate::block;
+
+    use super::*;
+
+    #[test]
+    fn zombie_target() {
+
+        let mut world = new_floor_world(block::STONE);
+        world.set_time(18000);
+
+        let player_id = world.spawn_entity(Human::new_default(DVec3::new(8.5, 64.0, 12.5)));
+        world.set_player_entity(player_id, true);
+
+        let zombie_id = world.spawn_entity(Zombie::new_default(DVec3::new(8.5, 64.0, 4.5)));
+        world.tick();
+
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(zombie_id) else { panic!() };
+        assert_eq!(living.attack_target, Some(player_id));
+
+        // The target is released when it is dead.
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity_mut(player_id) else { panic!() };
+        living.health = 0;
+        world.tick();
+
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(zombie_id) else { panic!() };
+        assert_eq!(living.attack_target, None);
+
+    }
+
+    #[test]
+    fn zombie_target_sight() {
+
+        let mut world = new_floor_world(block::STONE);
+        world.set_time(18000);
+
+        // A wall between the zombie and the player.
+        for x in 0..16 {
+            for y in 64..68 {
+                world.set_block(IVec3::new(x, y, 8), block::STONE, 0);
+            }
+        }
+
+        let player_id = world.spawn_entity(Human::new_default(DVec3::new(8.5, 64.0, 12.5)));
+        world.set_player_entity(player_id, true);
+
+        let zombie_id = world.spawn_entity(Zombie::new_default(DVec3::new(8.5, 64.0, 4.5)));
+        world.tick();
+
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(zombie_id) else { panic!() };
+        assert_eq!(living.attack_target, None);
+
+    }
+
+    #[test]
+    fn giant_attack() {
+
+        let mut world = new_floor_world(block::STONE);
+        world.set_time(18000);
+
+        let player_id = world.spawn_entity(Human::new_default(DVec3::new(8.5, 64.0, 10.5)));
+        world.set_player_entity(player_id, true);
+
+        // The giant is adjacent to the player, its bounding box is 3.6 blocks wide.
+        world.spawn_entity(Giant::new_default(DVec3::new(8.5, 64.0, 8.4)));
+
+        for _ in 0..20 {
+            world.tick();
+        }
+
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(player_id) else { panic!() };
+        assert_eq!(living.health, 0);
+
+    }
+
+}
@@ -5,7 +5,7 @@ use glam::{Vec3Swizzles, DVec3};
 use crate::entity::{Hurt, Arrow};
 use crate::world::{World, Event, EntityEvent};
 
-use super::{Entity, BaseKind, LivingKind};
+use super::{Entity, BaseKind, LivingKind, EntityCategory};
 use super::common::{self, let_expect};
 
 
@@ -27,26 +27,38 @@ pub(super) fn tick_attack(world: &mut World, id: u32, entity: &mut Entity, targe
 fn tick_mob_attack(world: &mut World, id: u32, entity: &mut Entity, target_id: u32, dist_squared: f64, eye_track: bool, _should_strafe: &mut bool) {
 
     /// Maximum distance for the mob to attack.
-    const MAX_DIST_SQUARED: f64 = 2.0 * 2.0;
+    const MAX_DIST: f64 = 2.0;
 
     let_expect!(Entity(base, BaseKind::Living(living, living_kind)) = entity);
 
+    // PARITY: The Notchian implementation always use the same attack distance, so the
+    // giant can't attack anything because its bounding box is too large, we extend the
+    // attack distance to one block beyond the half width of large mobs.
+    let max_dist = MAX_DIST.max(base.bb.size_x() / 2.0 + 1.0);
+
     living.attack_time = living.attack_time.saturating_sub(1);
-    if eye_track && living.attack_time == 0 && dist_squared < MAX_DIST_SQUARED {
+    if eye_track && living.attack_time == 0 && dist_squared < max_dist * max_dist {
 
-        let Some(Entity(target_base, BaseKind::Living(_, _))) = world.get_entity_mut(target_id) else {
+        let difficulty = world.get_difficulty();
+        let Some(Entity(target_base, BaseKind::Living(_, target_living_kind))) = world.get_entity_mut(target_id) else {
             panic!("target entity should exists");
         };
 
         if base.bb.intersects_y(target_base.bb) {
         
-            let attack_damage = match living_kind {
+            let mut attack_damage = match living_kind {
                 LivingKind::Giant(_) => 50,
                 LivingKind::PigZombie(_) => 5,
                 LivingKind::Zombie(_) => 5,
                 _ => 2,
             };
 
+            // Damage dealt by hostile mobs to players depends on the difficulty.
+            let hostile = living_kind.entity_kind().category() == EntityCategory::Mob;
+            if hostile && matches!(target_living_kind, LivingKind::Human(_)) {
+                attack_damage = difficulty.scale_mob_damage(attack_damage);
+            }
+
             living.attack_time = 20;
 
             target_base.hurt.push(Hurt {
@@ -71,7 +83,7 @@ fn tick_spider_attack(world: &mut World, id: u32, entity: &mut Entity, target_id
     let_expect!(Entity(base, BaseKind::Living(living, LivingKind::Spider(_))) = entity);
     
     // If the brightness has changed, there if 1% chance to loose target.
-    if common::get_entity_light(world, base).brightness() > 0.5 && base.rand.next_int_bounded(100) == 0 {
+    if common::get_entity_light(world, base).brightness(world.get_dimension()) > 0.5 && base.rand.next_int_bounded(100) == 0 {
         // Loose target because it's too bright.
         living.attack_target = None;
     } else if dist_squared > MIN_DIST_SQUARED && dist_squared < MAX_DIST_SQUARED && base.rand.next_int_bounded(10) == 0 {
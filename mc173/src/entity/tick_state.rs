@@ -10,10 +10,17 @@ use crate::block::material::Material;
 use crate::item::{self, ItemStack};
 use crate::block;
 
-use super::{Entity, BaseKind, Base, Living};
+use super::{Entity, BaseKind, Human};
 use super::common::{self, let_expect};
 
 
+/// Number of ticks an entity should stay in a portal before triggering the portal event.
+const PORTAL_DELAY: u32 = 80;
+/// Maximum air time of living entities, refilled when not under water.
+pub(crate) const AIR_MAX_TIME: u32 = 300;
+/// Number of ticks between two drowning damages when out of air.
+const AIR_DROWN_DELAY: u32 = 20;
+
 /// Tick base method that is common to every entity kind, this is split in Notchian impl
 /// so we split it here.
 pub(super) fn tick_state(world: &mut World, id: u32, entity: &mut Entity) {
@@ -54,6 +61,13 @@ fn tick_state_base(world: &mut World, id: u32, entity: &mut Entity) {
         base.vel += water_vel * 0.014;
     }
 
+    // Cancel fall if in cobweb.
+    // REF: BlockWeb::onEntityCollidedWithBlock
+    let web_bb = base.bb.inflate(DVec3::splat(-0.001));
+    if world.iter_blocks_in_box(web_bb).any(|(_, block, _)| block == block::COBWEB) {
+        base.fall_distance = 0.0;
+    }
+
     // Extinguish and cancel fall if in water.
     if base.in_water {
         base.fire_time = 0;
@@ -74,6 +88,22 @@ fn tick_state_base(world: &mut World, id: u32, entity: &mut Entity) {
     base.in_lava = world.iter_blocks_in_box(lava_bb)
         .any(|(_, block, _)| block::material::get_material(block) == Material::Lava);
 
+    // Check if the entity is in a portal block, if so increment its portal timer and
+    // trigger the portal event when the delay is reached.
+    let in_portal = world.iter_blocks_in_box(base.bb.inflate(DVec3::splat(-0.001)))
+        .any(|(_, block, _)| block == block::PORTAL);
+    
+    if in_portal {
+        base.portal_time += 1;
+        if base.portal_time >= PORTAL_DELAY {
+            base.portal_time = 0;
+            world.push_event(Event::Entity { id, inner: EntityEvent::Portal });
+        }
+    } else {
+        // PARITY: Notchian decrease the timer 4 times faster than its increase.
+        base.portal_time = base.portal_time.saturating_sub(4);
+    }
+
     // If this entity can pickup other ones, trigger an event.
     if base.can_pickup {
 
@@ -151,8 +181,20 @@ fn tick_state_living(world: &mut World, id: u32, entity: &mut Entity) {
         }
     }
 
-    // TODO: Air time underwater
-
+    // Consume air time when the eye is under water, or refill it.
+    if living.health > 0 && !living_kind.can_breathe_water() && common::is_eye_in_water(world, base) {
+        if base.air_time > 0 {
+            base.air_time -= 1;
+        } else {
+            // PARITY: The Notchian implementation let the air time go down to -20 
+            // before dealing damage and resetting to 0, we can't go below zero so we
+            // directly deal damage and wait 20 ticks before the next one.
+            base.air_time = AIR_DROWN_DELAY;
+            base.hurt.push(Hurt { damage: 2, origin_id: None });
+        }
+    } else {
+        base.air_time = AIR_MAX_TIME;
+    }
 
     // If the zombie/skeleton see the sky light, set it on fire.
     if matches!(living_kind, LivingKind::Zombie(_) | LivingKind::Skeleton(_)) {
@@ -161,7 +203,7 @@ fn tick_state_living(world: &mut World, id: u32, entity: &mut Entity) {
         if block_pos.y >= height {
             let light = common::get_entity_light(world, base);
             if light.sky_real >= 12 {
-                if base.rand.next_float() * 30.0 < (light.brightness() - 0.4) * 2.0 {
+                if base.rand.next_float() * 30.0 < (light.brightness(world.get_dimension()) - 0.4) * 2.0 {
                     base.fire_time = 300;
                 }
             }
@@ -227,6 +269,10 @@ fn tick_state_living(world: &mut World, id: u32, entity: &mut Entity) {
 
         // Apply damage.
         if actual_damage != 0 {
+
+            if let LivingKind::Human(human) = living_kind {
+                actual_damage = damage_armor(world, id, human, actual_damage);
+            }
             
             living.health = living.health.saturating_sub(actual_damage);
             
@@ -235,8 +281,6 @@ fn tick_state_living(world: &mut World, id: u32, entity: &mut Entity) {
                 killer_id = hurt.origin_id;
             }
 
-            // TODO: For players, take armor into account.
-
         }
 
     }
@@ -247,7 +291,7 @@ fn tick_state_living(world: &mut World, id: u32, entity: &mut Entity) {
         if living.death_time == 0 {
             
             world.push_event(Event::Entity { id, inner: EntityEvent::Dead });
-            spawn_living_loot(world, base, living, living_kind);
+            world.spawn_living_loot(base, living_kind);
 
             // If we know the killer id and we are a creeper, check if this the killer
             // is a skeleton, in which case we drop a music disk.
@@ -265,8 +309,10 @@ fn tick_state_living(world: &mut World, id: u32, entity: &mut Entity) {
 
         }
 
-        living.death_time += 1;
-        if living.death_time > 20 {
+        // PARITY: The Notchian server recreates the player entity when respawning, here
+        // player entities are kept in the world until respawned by the server.
+        living.death_time = living.death_time.saturating_add(1);
+        if living.death_time > 20 && !world.is_player_entity(id) {
             world.remove_entity(id, "health dead");
         }
 
@@ -275,56 +321,160 @@ fn tick_state_living(world: &mut World, id: u32, entity: &mut Entity) {
 }
 
 
-fn spawn_living_loot(world: &mut World, base: &mut Base, _living: &mut Living, living_kind: &mut LivingKind) {
-    
-    let stack = match living_kind {
-        LivingKind::Chicken(_) => 
-            ItemStack::new_single(item::FEATHER, 0),
-        LivingKind::Cow(_) => 
-            ItemStack::new_single(item::LEATHER, 0),
-        LivingKind::Creeper(_) => 
-            ItemStack::new_single(item::GUNPOWDER, 0),
-        LivingKind::Ghast(_) => 
-            ItemStack::new_single(item::GUNPOWDER, 0),
-        LivingKind::Pig(_) => {
-            if base.fire_time == 0 {
-                ItemStack::new_single(item::RAW_PORKCHOP, 0)
-            } else {
-                ItemStack::new_single(item::COOKED_PORKCHOP, 0)
-            }
+/// Reduce the given damage depending on the armor of the human entity and damage the 
+/// armor, returning the reduced damage to apply to the entity. Each armor point reduces
+/// the damage by 4%. A storage event is pushed for each damaged armor stack.
+/// 
+/// REF: EntityPlayer::damageEntity
+fn damage_armor(world: &mut World, id: u32, human: &mut Human, damage: u16) -> u16 {
+
+    // REF: InventoryPlayer::getTotalArmorValue
+    let mut points = 0;
+    let mut durability = 0;
+    let mut max_durability = 0;
+    for stack in &human.armor {
+        let stack_points = item::armor::get_armor_points(stack.id);
+        if !stack.is_empty() && stack_points != 0 {
+            let max_damage = item::from_id(stack.id).max_damage;
+            points += stack_points;
+            durability += max_damage.saturating_sub(stack.damage) as u32;
+            max_durability += max_damage as u32;
         }
-        LivingKind::PigZombie(_) => 
-            ItemStack::new_single(item::COOKED_PORKCHOP, 0),
-        LivingKind::Sheep(sheep) if !sheep.sheared => 
-            ItemStack::new_block(block::WOOL, sheep.color),
-        LivingKind::Skeleton(_) => {
-            spawn_many_loot(world, base.pos, ItemStack::new_single(item::ARROW, 0), base.rand.next_int_bounded(3) as usize);
-            spawn_many_loot(world, base.pos, ItemStack::new_single(item::BONE, 0), base.rand.next_int_bounded(3) as usize);
-            return;
-        }
-        LivingKind::Slime(slime) if slime.size == 0 => 
-            ItemStack::new_single(item::SLIMEBALL, 0),
-        LivingKind::Spider(_) => 
-            ItemStack::new_single(item::STRING, 0),
-        LivingKind::Squid(_) => 
-            ItemStack::new_single(item::DYE, 0),
-        LivingKind::Zombie(_) => 
-            ItemStack::new_single(item::FEATHER, 0),
-        _ => return
-    };
+    }
 
-    let count = match living_kind {
-        LivingKind::Squid(_) => 1 + base.rand.next_int_bounded(3) as usize,
-        _ => base.rand.next_int_bounded(3) as usize,
-    };
+    // The armor value is weighted by the remaining durability of the armor.
+    let value = (points.saturating_sub(1) as u32 * durability)
+        .checked_div(max_durability)
+        .map_or(0, |value| value + 1);
+
+    // REF: InventoryPlayer::damageArmor
+    for (index, stack) in human.armor.iter_mut().enumerate() {
+        if !stack.is_empty() && item::armor::get_armor_points(stack.id) != 0 {
+            *stack = stack.inc_damage(damage).to_non_empty().unwrap_or_default();
+            world.push_event(Event::Entity { id, inner: EntityEvent::Armor { index: index as u8, stack: *stack } });
+        }
+    }
 
-    spawn_many_loot(world, base.pos, stack, count);
+    let total = damage as u32 * (25 - value) + human.armor_damage_remainder as u32;
+    human.armor_damage_remainder = (total % 25) as u16;
+    (total / 25) as u16
 
 }
 
 
-fn spawn_many_loot(world: &mut World, pos: DVec3, stack: ItemStack, count: usize) {
-    for _ in 0..count {
-        world.spawn_loot(pos, stack, 0.0);
+#[cfg(test)]
+mod tests {
+
+    use crate::test::new_floor_world;
+    use glam::IVec3;
+
+    use crate::entity::{Pig, Human};
+    use crate::chunk::Chunk;
+    use crate::item::attack::get_base_damage;
+    use crate::world::Dimension;
+
+    use super::*;
+
+    /// Hurt the given entity with the given damage and tick the world, returning the 
+    /// resulting health of the entity.
+    fn hurt(world: &mut World, id: u32, damage: u16) -> u16 {
+        let Some(Entity(base, _)) = world.get_entity_mut(id) else { panic!() };
+        base.hurt.push(Hurt { damage, origin_id: None });
+        world.tick();
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(id) else { panic!() };
+        living.health
     }
+
+    #[test]
+    fn hurt_cooldown() {
+
+        let mut world = new_floor_world(block::STONE);
+
+        let id = world.spawn_entity(Pig::new_with(|base, _, _| {
+            base.pos = DVec3::new(8.5, 64.0, 8.5);
+        }));
+
+        // First hit applies fully, the second weaker hit within the cooldown is ignored.
+        assert_eq!(hurt(&mut world, id, get_base_damage(item::WOOD_SWORD)), 5);
+        assert_eq!(hurt(&mut world, id, get_base_damage(0)), 5);
+        // A larger hit within the cooldown only applies the difference.
+        assert_eq!(hurt(&mut world, id, get_base_damage(item::STONE_SWORD)), 4);
+
+        // Wait for the cooldown to expire.
+        for _ in 0..10 {
+            world.tick();
+        }
+
+        assert_eq!(hurt(&mut world, id, get_base_damage(0)), 2);
+
+    }
+
+    #[test]
+    fn armor() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let id = world.spawn_entity(Human::new_with(|base, _, human| {
+            base.pos = DVec3::new(8.5, 64.0, 8.5);
+            human.armor = [
+                ItemStack::new_single(item::IRON_HELMET, 0),
+                ItemStack::new_single(item::IRON_CHESTPLATE, 0),
+                ItemStack::new_single(item::IRON_LEGGINGS, 0),
+                ItemStack::new_single(item::IRON_BOOTS, 0),
+            ];
+        }));
+
+        // Full armor gives 20 points, reducing damage by 80%.
+        world.swap_events(Some(Vec::new()));
+        assert_eq!(hurt(&mut world, id, 10), 18);
+
+        let events = world.swap_events(None).unwrap();
+        let Some(Entity(_, BaseKind::Living(_, LivingKind::Human(human)))) = world.get_entity(id) else { panic!() };
+        assert!(human.armor.iter().all(|stack| stack.damage == 10));
+
+        // Each damaged armor stack is notified so that frontends can sync inventories.
+        for (index, &stack) in human.armor.iter().enumerate() {
+            assert!(events.contains(&Event::Entity { id, inner: EntityEvent::Armor { index: index as u8, stack } }));
+        }
+
+    }
+
+    #[test]
+    fn portal() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        // A portal surrounded by stone, to ensure that the entity stays in it.
+        for x in 5..12 {
+            for z in 5..12 {
+                for y in 63..67 {
+                    let border = x == 5 || x == 11 || z == 5 || z == 11 || y == 63;
+                    let id = if border { block::STONE } else { block::PORTAL };
+                    world.set_block(IVec3::new(x, y, z), id, 0);
+                }
+            }
+        }
+
+        let id = world.spawn_entity(Pig::new_with(|base, _, _| {
+            base.pos = DVec3::new(8.5, 64.0, 8.5);
+        }));
+
+        world.swap_events(Some(Vec::new()));
+
+        let portal = |world: &mut World| world.swap_events(Some(Vec::new())).unwrap()
+            .into_iter()
+            .any(|event| matches!(event, Event::Entity { id: event_id, inner: EntityEvent::Portal } if event_id == id));
+
+        for _ in 0..PORTAL_DELAY - 1 {
+            world.tick();
+            assert!(!portal(&mut world));
+        }
+
+        world.tick();
+        assert!(portal(&mut world));
+
+    }
+
 }
@@ -6,6 +6,8 @@
 //! not give exact same world on each generation, just like Minecraft. Terrain however,
 //! should be exactly the same on same run.
 
+use std::sync::Arc;
+
 use glam::IVec3;
 
 use crate::rand::JavaRandom;
@@ -64,3 +66,46 @@ pub trait FeatureGenerator {
     fn generate(&mut self, world: &mut World, pos: IVec3, rand: &mut JavaRandom) -> bool;
 
 }
+
+
+/// Generate the terrain of the given chunk with a new generator state and serialize its
+/// blocks, metadata and biomes in a deterministic byte order. This is intended for 
+/// regression testing of terrain generation, by comparing against captured outputs.
+pub fn dump_chunk<G: ChunkGenerator>(generator: &G, cx: i32, cz: i32) -> Vec<u8> {
+
+    let mut chunk = Chunk::new();
+    let chunk_mut = Arc::make_mut(&mut chunk);
+    let mut state = G::State::default();
+    generator.gen_terrain(cx, cz, chunk_mut, &mut state);
+
+    let mut data = Vec::with_capacity(chunk_mut.block.len() + chunk_mut.metadata.inner.len() + chunk_mut.biome.len());
+    data.extend_from_slice(&chunk_mut.block[..]);
+    data.extend_from_slice(&chunk_mut.metadata.inner[..]);
+    data.extend(chunk_mut.biome.iter().map(|&biome| biome as u8));
+    data
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn dump_chunk_deterministic() {
+
+        let generator = OverworldGenerator::new(9999);
+        let dump = dump_chunk(&generator, 3, -7);
+        assert_eq!(dump, dump_chunk(&generator, 3, -7));
+
+        // Another generator with the same seed gives the same terrain.
+        assert_eq!(dump, dump_chunk(&OverworldGenerator::new(9999), 3, -7));
+
+        // But different chunks or seeds give different terrain.
+        assert_ne!(dump, dump_chunk(&generator, 4, -7));
+        assert_ne!(dump, dump_chunk(&OverworldGenerator::new(1234), 3, -7));
+
+    }
+
+}
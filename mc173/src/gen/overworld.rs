@@ -98,35 +98,7 @@ impl OverworldGenerator {
             
             let t = (i % 64) as f32 / 63.0;
             let h = (i / 64) as f32 / 63.0;
-            let h = h * t;
-
-            if t < 0.1 {
-                Biome::Tundra
-            } else if h < 0.2 {
-                if t < 0.5 {
-                    Biome::Tundra
-                } else if t < 0.95 {
-                    Biome::Savanna
-                } else {
-                    Biome::Desert
-                }
-            } else if h > 0.5 && t < 0.7 {
-                Biome::Swampland
-            } else if t < 0.5 {
-                Biome::Taiga
-            } else if t < 0.97 {
-                if h < 0.35 {
-                    Biome::ShrubLand
-                } else {
-                    Biome::Forest
-                }
-            } else if h < 0.45 {
-                Biome::Plains
-            } else if h < 0.9 {
-                Biome::SeasonalForest
-            } else {
-                Biome::RainForest
-            }
+            Biome::from_climate(t, h)
 
         }));
 
@@ -418,14 +390,8 @@ impl OverworldGenerator {
                 let have_gravel = gravel.get(x, 0, z) + rand.next_double() * 0.2 > 3.0;
                 let thickness = (thickness.get(x, z, 0) / 3.0 + 3.0 + rand.next_double() * 0.25) as i32;
 
-                let (
-                    biome_top_id, 
-                    biome_filler_id
-                ) = match biome {
-                    Biome::Desert |
-                    Biome::IceDesert => (block::SAND, block::SAND),
-                    _ => (block::GRASS, block::DIRT),
-                };
+                let biome_top_id = biome.top_block();
+                let biome_filler_id = biome.filler_block();
 
                 let mut top_id = biome_top_id;
                 let mut filler_id = biome_filler_id;
@@ -886,3 +852,68 @@ impl ChunkGenerator for OverworldGenerator {
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn desert_surface() {
+
+        let generator = OverworldGenerator::new(9999);
+        let mut state = OverworldState::default();
+
+        // Search for a desert column well above sea level, where beach sand and gravel
+        // are not generated.
+        let mut desert_columns = 0;
+        'search: for cx in -8..8 {
+            for cz in -8..8 {
+
+                let mut chunk = Chunk::new();
+                let chunk_mut = std::sync::Arc::make_mut(&mut chunk);
+                ChunkGenerator::gen_terrain(&generator, cx, cz, chunk_mut, &mut state);
+
+                for x in 0..16 {
+                    for z in 0..16 {
+
+                        let mut pos = IVec3::new(x, 0, z);
+                        if chunk_mut.get_biome(pos) != Biome::Desert {
+                            continue;
+                        }
+
+                        pos.y = chunk_mut.get_height(pos) as i32 - 1;
+                        if pos.y < 70 {
+                            continue;
+                        }
+
+                        // Carved columns are ignored.
+                        let column = (pos.y - 8..=pos.y)
+                            .map(|y| chunk_mut.get_block(IVec3::new(x, y, z)).0)
+                            .collect::<Vec<_>>();
+
+                        if column.contains(&block::AIR) {
+                            continue;
+                        }
+
+                        assert_eq!(column.last(), Some(&block::SAND));
+                        assert!(column.iter().all(|&id| id != block::GRASS && id != block::DIRT));
+                        assert!(column.iter().all(|&id| matches!(id, block::SAND | block::SANDSTONE | block::STONE)));
+
+                        desert_columns += 1;
+                        if desert_columns >= 16 {
+                            break 'search;
+                        }
+
+                    }
+                }
+
+            }
+        }
+
+        assert!(desert_columns > 0, "no desert column found");
+
+    }
+
+}
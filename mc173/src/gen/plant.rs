@@ -212,4 +212,91 @@ impl FeatureGenerator for CactusGenerator {
 
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::world::Dimension;
+    use crate::chunk::Chunk;
+
+    use super::*;
+
+    /// Create a world with a grass ground at Y=63, with some stone and sand patches.
+    fn new_ground_world() -> World {
+
+        let mut world = World::new(Dimension::Overworld);
+        for cx in -1..=1 {
+            for cz in -1..=1 {
+                world.set_chunk(cx, cz, Chunk::new());
+            }
+        }
+
+        for x in -16i32..32 {
+            for z in -16i32..32 {
+                let id = match (x.rem_euclid(6), z.rem_euclid(6)) {
+                    (0, _) => block::STONE,
+                    (_, 0) => block::SAND,
+                    _ => block::GRASS,
+                };
+                world.set_block(IVec3::new(x, 63, z), id, 0);
+            }
+        }
+
+        world
+
+    }
+
+    #[test]
+    fn plant_support() {
+
+        let mut world = new_ground_world();
+        let mut rand = JavaRandom::new(1234);
+        let pos = IVec3::new(8, 64, 8);
+
+        PlantGenerator::new_flower(block::DANDELION).generate(&mut world, pos, &mut rand);
+        PlantGenerator::new_flower(block::POPPY).generate(&mut world, pos, &mut rand);
+        PlantGenerator::new_tall_grass(1).generate(&mut world, pos + IVec3::Y * 10, &mut rand);
+
+        let plants = world.iter_blocks_in(IVec3::new(-16, 0, -16), IVec3::new(32, 128, 32))
+            .filter(|&(_, id, _)| matches!(id, block::DANDELION | block::POPPY | block::TALL_GRASS))
+            .collect::<Vec<_>>();
+
+        for id in [block::DANDELION, block::POPPY, block::TALL_GRASS] {
+            assert!(plants.iter().any(|&(_, plant_id, _)| plant_id == id), "no plant {id}");
+        }
+
+        for &(plant_pos, _, _) in &plants {
+            assert_eq!(plant_pos.y, 64);
+            assert_eq!(world.get_block(plant_pos - IVec3::Y), Some((block::GRASS, 0)));
+        }
+
+    }
+
+    #[test]
+    fn sugar_canes_near_water() {
+
+        let mut world = new_ground_world();
+        let mut rand = JavaRandom::new(1234);
+
+        // A small water pool, where the sand patches are.
+        for x in 4..12 {
+            world.set_block(IVec3::new(x, 63, 6), block::WATER_STILL, 0);
+        }
+
+        for _ in 0..10 {
+            SugarCanesGenerator::new().generate(&mut world, IVec3::new(8, 64, 6), &mut rand);
+        }
+
+        let canes = world.iter_blocks_in(IVec3::new(-16, 64, -16), IVec3::new(32, 65, 32))
+            .filter(|&(_, id, _)| id == block::SUGAR_CANES)
+            .collect::<Vec<_>>();
+
+        assert!(!canes.is_empty());
+        for (cane_pos, _, _) in canes {
+            assert!(Face::HORIZONTAL.into_iter().any(|face| world.get_block_material(cane_pos - IVec3::Y + face.delta()) == Material::Water));
+        }
+
+    }
+
+}
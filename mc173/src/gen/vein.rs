@@ -10,7 +10,9 @@ use super::math::MinecraftMath;
 use super::FeatureGenerator;
 
 
-/// A generator for mob spawner dungeon.
+/// A generator for ellipsoidal veins of ore or clay, only replacing a given block.
+/// 
+/// REF: WorldGenMinable, WorldGenClay
 pub struct VeinGenerator {
     replace_id: u8,
     place_id: u8,
@@ -97,3 +99,52 @@ impl FeatureGenerator for VeinGenerator {
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::world::Dimension;
+    use crate::chunk::Chunk;
+
+    use super::*;
+
+    #[test]
+    fn iron_vein() {
+
+        /// Generate an iron vein of 8 blocks in a stone chunk with the given seed, and
+        /// return the sorted positions of the iron ore.
+        fn generate(seed: i64) -> Vec<[i32; 3]> {
+
+            let mut chunk = Chunk::new();
+            std::sync::Arc::make_mut(&mut chunk).fill_block(IVec3::ZERO, IVec3::new(16, 64, 16), block::STONE, 0);
+            let mut world = World::new(Dimension::Overworld);
+            world.set_chunk(0, 0, chunk);
+
+            let mut rand = JavaRandom::new(seed);
+            assert!(VeinGenerator::new_ore(block::IRON_ORE, 8).generate(&mut world, IVec3::new(0, 30, 0), &mut rand));
+
+            let mut ores = world.iter_blocks_in(IVec3::ZERO, IVec3::new(16, 64, 16))
+                .filter(|&(_, id, _)| id == block::IRON_ORE)
+                .map(|(pos, _, _)| pos.to_array())
+                .collect::<Vec<_>>();
+            ores.sort();
+            ores
+
+        }
+
+        // Reference positions produced by the Notchian WorldGenMinable::generate with 
+        // the same seeds, vein origin and stone chunk.
+        assert_eq!(generate(12345), [
+            [7, 31, 7], [7, 32, 7], [7, 32, 8], 
+            [8, 32, 7], [8, 32, 8], [8, 33, 8],
+        ]);
+
+        assert_eq!(generate(2), [
+            [7, 32, 7], [7, 33, 7], [7, 33, 8], [7, 34, 8],
+            [8, 31, 7], [8, 32, 7], [8, 32, 8], [8, 33, 7],
+        ]);
+
+    }
+
+}
@@ -107,11 +107,15 @@ impl<'a> InventoryHandle<'a> {
         while let Some(index) = if back { range.next_back() } else { range.next() } {
             let slot = &mut self.inv[index];
             if slot.is_empty() {
-                // We found an empty slot, insert the whole remaining stack size.
-                *slot = *stack;
-                stack.size = 0;
+                // We found an empty slot, insert as much as possible of the remaining 
+                // stack size, a stack bigger than the max stack size is split.
+                let to_add = item.max_stack_size.min(stack.size);
+                *slot = stack.with_size(to_add);
+                stack.size -= to_add;
                 self.changes |= 1 << index;
-                return;
+                if stack.size == 0 {
+                    return;
+                }
             }
         }
         
@@ -129,15 +133,16 @@ impl<'a> InventoryHandle<'a> {
         let item = item::from_id(stack.id);
 
         for slot in &self.inv[..] {
-            if slot.is_empty() {
+            let available = if slot.is_empty() {
+                item.max_stack_size
+            } else if slot.id == stack.id && slot.damage == stack.damage && slot.size < item.max_stack_size {
+                item.max_stack_size - slot.size
+            } else {
+                continue;
+            };
+            stack.size -= available.min(stack.size);
+            if stack.size == 0 {
                 return true;
-            } else if slot.size != 0 && slot.id == stack.id && slot.damage == stack.damage && slot.size < item.max_stack_size {
-                let available = item.max_stack_size - slot.size;
-                let to_add = available.min(stack.size);
-                stack.size -= to_add;
-                if stack.size == 0 {
-                    return true;
-                }
             }
         }
 
@@ -160,6 +165,39 @@ impl<'a> InventoryHandle<'a> {
 
     }
 
+    /// Find the index of the first slot containing the same item and damage as the given
+    /// stack, the stack size is ignored.
+    pub fn find(&self, stack: ItemStack) -> Option<usize> {
+        self.inv.iter().position(|slot| !slot.is_empty() && slot.id == stack.id && slot.damage == stack.damage)
+    }
+
+    /// Remove the equivalent of the given item stack from the inventory, starting by the
+    /// first slots and possibly across many slots.
+    /// 
+    /// The given item stack is modified according to the amount of items actually 
+    /// removed from the inventory, its size will be set to zero if fully removed.
+    pub fn remove(&mut self, stack: &mut ItemStack) {
+
+        for (index, slot) in self.inv.iter_mut().enumerate() {
+
+            if stack.size == 0 {
+                return;
+            }
+
+            if !slot.is_empty() && slot.id == stack.id && slot.damage == stack.damage {
+                let to_remove = slot.size.min(stack.size);
+                slot.size -= to_remove;
+                stack.size -= to_remove;
+                if slot.size == 0 {
+                    *slot = ItemStack::EMPTY;
+                }
+                self.changes |= 1 << index;
+            }
+
+        }
+
+    }
+
     /// Get an iterator for changes that happened in this inventory.
     pub fn iter_changes(&self) -> ChangesIter {
         ChangesIter {
@@ -197,3 +235,76 @@ impl Iterator for ChangesIter {
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn push_merge() {
+
+        let mut stacks = [ItemStack::EMPTY; 4];
+        stacks[1] = ItemStack::new_sized(item::DIAMOND, 0, 60);
+        stacks[3] = ItemStack::new_sized(item::DIAMOND, 0, 10);
+
+        let mut inv = InventoryHandle::new(&mut stacks);
+        let mut stack = ItemStack::new_sized(item::DIAMOND, 0, 20);
+        inv.push_front(&mut stack);
+        assert_eq!(stack.size, 0);
+        assert_eq!(inv.iter_changes().collect::<Vec<_>>(), [1, 3]);
+
+        assert_eq!(stacks[0], ItemStack::EMPTY);
+        assert_eq!(stacks[1].size, 64);
+        assert_eq!(stacks[3].size, 26);
+
+    }
+
+    #[test]
+    fn push_overflow() {
+
+        let mut stacks = [ItemStack::EMPTY; 2];
+        stacks[0] = ItemStack::new_sized(item::SNOWBALL, 0, 10);
+
+        let mut inv = InventoryHandle::new(&mut stacks);
+        assert!(inv.can_push(ItemStack::new_sized(item::SNOWBALL, 0, 22)));
+        assert!(!inv.can_push(ItemStack::new_sized(item::SNOWBALL, 0, 23)));
+
+        // Snowballs are stacked by 16, so the empty slot cannot take everything.
+        let mut stack = ItemStack::new_sized(item::SNOWBALL, 0, 30);
+        inv.push_front(&mut stack);
+        assert_eq!(stack.size, 8);
+        assert_eq!(stacks[0].size, 16);
+        assert_eq!(stacks[1], ItemStack::new_sized(item::SNOWBALL, 0, 16));
+
+    }
+
+    #[test]
+    fn remove_partial() {
+
+        let mut stacks = [ItemStack::EMPTY; 3];
+        stacks[0] = ItemStack::new_sized(item::ARROW, 0, 5);
+        stacks[1] = ItemStack::new_sized(item::STICK, 0, 5);
+        stacks[2] = ItemStack::new_sized(item::ARROW, 0, 5);
+
+        let mut inv = InventoryHandle::new(&mut stacks);
+        assert_eq!(inv.find(ItemStack::new_single(item::STICK, 0)), Some(1));
+        assert_eq!(inv.find(ItemStack::new_single(item::STICK, 1)), None);
+
+        let mut stack = ItemStack::new_sized(item::ARROW, 0, 7);
+        inv.remove(&mut stack);
+        assert_eq!(stack.size, 0);
+        assert_eq!(inv.find(ItemStack::new_single(item::ARROW, 0)), Some(2));
+
+        let mut stack = ItemStack::new_sized(item::ARROW, 0, 7);
+        inv.remove(&mut stack);
+        assert_eq!(stack.size, 4);
+
+        assert_eq!(stacks[0], ItemStack::EMPTY);
+        assert_eq!(stacks[1].size, 5);
+        assert_eq!(stacks[2], ItemStack::EMPTY);
+
+    }
+
+}
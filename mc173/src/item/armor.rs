@@ -0,0 +1,33 @@
+//! Module to query armor properties of items.
+
+use crate::item;
+
+
+/// Get the armor points given by an armor item, this returns 0 if the item is not an
+/// armor. The points only depends on the armor piece, the material only changes the
+/// durability of the armor.
+pub fn get_armor_points(item: u16) -> u16 {
+    match item {
+        item::LEATHER_HELMET |
+        item::CHAIN_HELMET |
+        item::IRON_HELMET |
+        item::DIAMOND_HELMET |
+        item::GOLD_HELMET => 3,
+        item::LEATHER_CHESTPLATE |
+        item::CHAIN_CHESTPLATE |
+        item::IRON_CHESTPLATE |
+        item::DIAMOND_CHESTPLATE |
+        item::GOLD_CHESTPLATE => 8,
+        item::LEATHER_LEGGINGS |
+        item::CHAIN_LEGGINGS |
+        item::IRON_LEGGINGS |
+        item::DIAMOND_LEGGINGS |
+        item::GOLD_LEGGINGS => 6,
+        item::LEATHER_BOOTS |
+        item::CHAIN_BOOTS |
+        item::IRON_BOOTS |
+        item::DIAMOND_BOOTS |
+        item::GOLD_BOOTS => 3,
+        _ => 0,
+    }
+}
@@ -3,7 +3,7 @@
 use crate::item;
 
 
-/// Get base attack damage of an item.
+/// Get base attack damage of an item, the item id is 0 if the attacker has no item.
 pub fn get_base_damage(item: u16) -> u16 {
     
     const DIAMOND_DAMAGE: u16 = 3;
@@ -15,11 +15,11 @@ pub fn get_base_damage(item: u16) -> u16 {
     // Calculate the damage from the item.
     match item {
         // Sword
-        item::DIAMOND_SWORD     => 4 + DIAMOND_DAMAGE * 2,
-        item::IRON_SWORD        => 4 + IRON_DAMAGE * 2,
-        item::STONE_SWORD       => 4 + STONE_DAMAGE * 2,
-        item::WOOD_SWORD        => 4 + WOOD_DAMAGE * 2,
-        item::GOLD_SWORD        => 4 + GOLD_DAMAGE * 2,
+        item::DIAMOND_SWORD     => 9,
+        item::IRON_SWORD        => 7,
+        item::STONE_SWORD       => 6,
+        item::WOOD_SWORD        => 5,
+        item::GOLD_SWORD        => 5,
         // Axe
         item::DIAMOND_AXE       => 3 + DIAMOND_DAMAGE,
         item::IRON_AXE          => 3 + IRON_DAMAGE,
@@ -38,6 +38,10 @@ pub fn get_base_damage(item: u16) -> u16 {
         item::STONE_SHOVEL      => 1 + STONE_DAMAGE,
         item::WOOD_SHOVEL       => 1 + WOOD_DAMAGE,
         item::GOLD_SHOVEL       => 1 + GOLD_DAMAGE,
+        // PARITY: Notchian implementation makes 1 damage with the empty hand, like 
+        // any other item (InventoryPlayer::getDamageVsEntity), we intentionally make 2
+        // damage to stay consistent with the per-weapon values above.
+        0 => 2,
         // All other items make 1 damage.
         _ => 1,
     }
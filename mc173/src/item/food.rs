@@ -0,0 +1,20 @@
+//! Module to query health restored by food items.
+
+use crate::item;
+
+
+/// Get the health restored when eating the given item, none if the item is not food.
+pub fn get_heal(item: u16) -> Option<u16> {
+    Some(match item {
+        item::APPLE             => 4,
+        item::MUSHROOM_STEW     => 10,
+        item::BREAD             => 5,
+        item::RAW_PORKCHOP      => 3,
+        item::COOKED_PORKCHOP   => 8,
+        item::GOLD_APPLE        => 42,
+        item::RAW_FISH          => 2,
+        item::COOKED_FISH       => 5,
+        item::COOKIE            => 1,
+        _ => return None,
+    })
+}
@@ -3,6 +3,8 @@
 use crate::block;
 
 pub mod attack;
+pub mod armor;
+pub mod food;
 
 
 /// Internal macro to easily define blocks registry.
@@ -33,7 +35,7 @@ items! {
     IRON_PICKAXE/1:         Item::new("iron_pickaxe").set_tool(IRON_MAX_USES),
     IRON_AXE/2:             Item::new("iron_axe").set_tool(IRON_MAX_USES),
     FLINT_AND_STEEL/3:      Item::new("flint_and_steel").set_tool(64),
-    APPLE/4:                Item::new("apple"),
+    APPLE/4:                Item::new("apple").set_food(),
     BOW/5:                  Item::new("bow").set_max_stack_size(1),
     ARROW/6:                Item::new("arrow"),
     COAL/7:                 Item::new("coal"), // .set_max_damage(1),
@@ -18,5 +18,9 @@ pub mod smelt;
 pub mod chunk;
 pub mod world;
 pub mod storage;
+pub mod source;
 pub mod serde;
 pub mod gen;
+
+#[cfg(test)]
+mod test;
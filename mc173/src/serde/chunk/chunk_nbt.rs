@@ -2,8 +2,10 @@
 
 use std::sync::Arc;
 
+use glam::IVec3;
+
 use crate::serde::nbt::{NbtCompoundParse, NbtCompound, NbtParseError, Nbt};
-use crate::world::ChunkSnapshot;
+use crate::world::{ChunkSnapshot, ScheduledBlockTick};
 
 use super::block_entity_nbt;
 use super::entity_nbt;
@@ -24,6 +26,7 @@ pub fn from_nbt(comp: NbtCompoundParse) -> Result<ChunkSnapshot, NbtParseError>
     chunk.block_light.inner.copy_from_slice(level.get_byte_array("BlockLight")?);
     chunk.sky_light.inner.copy_from_slice(level.get_byte_array("SkyLight")?);
     chunk.height.copy_from_slice(level.get_byte_array("HeightMap")?);
+    chunk.recompute_sections();
 
     for item in level.get_list("Entities")?.iter() {
         let entity = entity_nbt::from_nbt(item.as_compound()?)?;
@@ -35,6 +38,18 @@ pub fn from_nbt(comp: NbtCompoundParse) -> Result<ChunkSnapshot, NbtParseError>
         snapshot.block_entities.insert(pos, block_entity);
     }
 
+    // Scheduled ticks are optional because not saved by the Notchian implementation.
+    if let Ok(ticks) = level.get("TileTicks") {
+        for item in ticks.as_list()?.iter() {
+            let tick = item.as_compound()?;
+            snapshot.block_ticks.push(ScheduledBlockTick {
+                pos: IVec3::new(tick.get_int("x")?, tick.get_int("y")?, tick.get_int("z")?),
+                id: tick.get_int("i")? as u8,
+                delay: tick.get_int("t")?.max(0) as u64,
+            });
+        }
+    }
+
     Ok(snapshot)
 
 }
@@ -71,7 +86,65 @@ pub fn to_nbt<'a>(comp: &'a mut NbtCompound, snapshot: &ChunkSnapshot) -> &'a mu
         })
         .collect::<Vec<_>>());
 
+    level.insert("TileTicks", snapshot.block_ticks.iter()
+        .map(|tick| {
+            let mut comp = NbtCompound::new();
+            comp.insert("i", tick.id as i32);
+            comp.insert("x", tick.pos.x);
+            comp.insert("y", tick.pos.y);
+            comp.insert("z", tick.pos.z);
+            comp.insert("t", tick.delay.min(i32::MAX as u64) as i32);
+            Nbt::Compound(comp)
+        })
+        .collect::<Vec<_>>());
+
     comp.insert("Level", level);
     comp
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::world::{World, Dimension};
+    use crate::serde::chunk;
+    use crate::block;
+
+    use super::*;
+
+    #[test]
+    fn block_ticks_round_trip() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, crate::chunk::Chunk::new());
+
+        // Advance the world time to check that delays are relative.
+        for _ in 0..10 {
+            world.tick();
+        }
+
+        let pos = IVec3::new(8, 64, 8);
+        world.set_block(pos, block::WATER_MOVING, 0);
+        world.schedule_block_tick(pos, block::WATER_MOVING, 5);
+        // A tick in another chunk that should not be saved.
+        world.schedule_block_tick(IVec3::new(20, 64, 8), block::WATER_MOVING, 5);
+        world.tick();
+
+        let snapshot = world.remove_chunk_snapshot(0, 0).unwrap();
+        assert_eq!(snapshot.block_ticks, [ScheduledBlockTick { pos, id: block::WATER_MOVING, delay: 4 }]);
+
+        let mut data = Vec::new();
+        crate::serde::nbt::to_writer(&mut data, &chunk::to_nbt(&snapshot)).unwrap();
+        let nbt = crate::serde::nbt::from_reader(&data[..]).unwrap();
+        let snapshot = chunk::from_nbt(&nbt).unwrap();
+        assert_eq!(snapshot.block_ticks, [ScheduledBlockTick { pos, id: block::WATER_MOVING, delay: 4 }]);
+
+        let mut world = World::new(Dimension::Overworld);
+        world.insert_chunk_snapshot(snapshot);
+        assert_eq!(world.get_block_tick_count(), 1);
+        assert_eq!(world.take_chunk_snapshot(0, 0).unwrap().block_ticks, [ScheduledBlockTick { pos, id: block::WATER_MOVING, delay: 4 }]);
+
+    }
+
+}
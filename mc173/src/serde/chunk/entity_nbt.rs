@@ -75,21 +75,22 @@ pub fn from_nbt(comp: NbtCompoundParse) -> Result<Box<Entity>, NbtParseError> {
             ..Default::default()
         }),
         "Minecart" => {
-            BaseKind::Minecart(match comp.get_int("Type")? {
+            let kind = match comp.get_int("Type")? {
                 1 => {
                     let mut inv: Box<[ItemStack; 27]> = Box::default();
                     slot_nbt::from_nbt_to_inv(comp.get_list("Items")?, &mut inv[..])?;
-                    e::Minecart::Chest { inv }
+                    e::MinecartKind::Chest { inv }
                 }
                 2 => {
-                    e::Minecart::Furnace { 
+                    e::MinecartKind::Furnace { 
                         fuel: comp.get_short("fuel")?.max(0) as u32,
                         push_x: comp.get_double("PushX")?,
                         push_z: comp.get_double("PushZ")?,
                     }
                 }
-                _ => e::Minecart::Normal
-            })
+                _ => e::MinecartKind::Normal
+            };
+            BaseKind::Minecart(e::Minecart { kind, ..Default::default() })
         }
         "Boat" => BaseKind::Boat(e::Boat::default()),
         "Arrow" |
@@ -229,16 +230,16 @@ pub fn to_nbt<'a>(comp: &'a mut NbtCompound, entity: &Entity) -> Option<&'a mut
         BaseKind::Boat(_) => {
             comp.insert("id", "Boat");
         }
-        BaseKind::Minecart(e::Minecart::Normal) => {
+        BaseKind::Minecart(e::Minecart { kind: e::MinecartKind::Normal, .. }) => {
             comp.insert("id", "Minecart");
             comp.insert("Type", 0i32);
         }
-        BaseKind::Minecart(e::Minecart::Chest { inv }) => {
+        BaseKind::Minecart(e::Minecart { kind: e::MinecartKind::Chest { inv }, .. }) => {
             comp.insert("id", "Minecart");
             comp.insert("Type", 1i32);
             comp.insert("Items", slot_nbt::to_nbt_from_inv(&inv[..]));
         }
-        &BaseKind::Minecart(e::Minecart::Furnace { push_x, push_z, fuel }) => {
+        &BaseKind::Minecart(e::Minecart { kind: e::MinecartKind::Furnace { push_x, push_z, fuel }, .. }) => {
             comp.insert("id", "Minecart");
             comp.insert("Type", 2i32);
             comp.insert("fuel", fuel.min(i16::MAX as _) as i16);
@@ -0,0 +1,162 @@
+//! Level data serialization and deserialization from NBT compound, this is the content
+//! of the `level.dat` file of a world.
+
+use glam::IVec3;
+
+use crate::world::{World, Weather};
+
+use super::nbt::{Nbt, NbtParseError, NbtCompound, NbtCompoundParse};
+
+
+/// The level data version used by beta 1.7.3 (McRegion format).
+const LEVEL_VERSION: i32 = 19132;
+
+
+/// Global data of a world that is not stored in chunks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelData {
+    /// The seed used by the world generator.
+    pub seed: i64,
+    /// The world spawn position.
+    pub spawn_pos: IVec3,
+    /// The world time, in ticks.
+    pub time: u64,
+    /// The current weather.
+    pub weather: Weather,
+    /// Absolute world time when the weather will be recomputed.
+    pub weather_next_time: u64,
+}
+
+impl LevelData {
+
+    /// Capture the level data of the given world, the seed is not known by the world
+    /// so it needs to be given.
+    pub fn from_world(world: &World, seed: i64) -> Self {
+        Self {
+            seed,
+            spawn_pos: world.get_spawn_pos(),
+            time: world.get_time(),
+            weather: world.get_weather(),
+            weather_next_time: world.get_weather_next_time(),
+        }
+    }
+
+    /// Restore this level data into the given world, the rain and thunder strengths are
+    /// directly set to match the restored weather.
+    pub fn apply_to_world(&self, world: &mut World) {
+        world.set_spawn_pos(self.spawn_pos);
+        world.set_time(self.time);
+        world.set_weather(self.weather);
+        world.set_weather_next_time(self.weather_next_time);
+        world.reset_weather_strength();
+    }
+
+}
+
+
+pub fn from_nbt(root: &Nbt) -> Result<LevelData, NbtParseError> {
+    from_nbt_compound(root.parse().as_compound()?)
+}
+
+pub fn to_nbt(data: &LevelData) -> Nbt {
+    let mut comp = NbtCompound::new();
+    to_nbt_compound(&mut comp, data);
+    Nbt::Compound(comp)
+}
+
+fn from_nbt_compound(comp: NbtCompoundParse) -> Result<LevelData, NbtParseError> {
+
+    let data = comp.get_compound("Data")?;
+    let time = data.get_long("Time")?.max(0) as u64;
+
+    // The Notchian level stores two independent states with their own remaining
+    // time, we only have one weather so thunder is only effective while raining.
+    // PARITY: The remaining time of the state that ends first is kept.
+    let raining = data.get_boolean("raining")?;
+    let thundering = data.get_boolean("thundering")?;
+    let rain_time = data.get_int("rainTime")?.max(0) as u64;
+    let thunder_time = data.get_int("thunderTime")?.max(0) as u64;
+
+    let (weather, remaining_time) = match (raining, thundering) {
+        (false, _) => (Weather::Clear, rain_time),
+        (true, false) => (Weather::Rain, rain_time),
+        (true, true) => (Weather::Thunder, rain_time.min(thunder_time)),
+    };
+
+    Ok(LevelData {
+        seed: data.get_long("RandomSeed")?,
+        spawn_pos: IVec3::new(data.get_int("SpawnX")?, data.get_int("SpawnY")?, data.get_int("SpawnZ")?),
+        time,
+        weather,
+        weather_next_time: time + remaining_time,
+    })
+
+}
+
+fn to_nbt_compound<'a>(comp: &'a mut NbtCompound, data: &LevelData) -> &'a mut NbtCompound {
+
+    let remaining_time = data.weather_next_time.saturating_sub(data.time).min(i32::MAX as u64) as i32;
+
+    let mut level = NbtCompound::new();
+    level.insert("RandomSeed", data.seed);
+    level.insert("SpawnX", data.spawn_pos.x);
+    level.insert("SpawnY", data.spawn_pos.y);
+    level.insert("SpawnZ", data.spawn_pos.z);
+    level.insert("Time", data.time as i64);
+    level.insert("version", LEVEL_VERSION);
+    level.insert("raining", data.weather != Weather::Clear);
+    level.insert("rainTime", remaining_time);
+    level.insert("thundering", data.weather == Weather::Thunder);
+    level.insert("thunderTime", remaining_time);
+
+    comp.insert("Data", level);
+    comp
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::world::Dimension;
+    use crate::serde::nbt;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_time(24123);
+        world.set_weather(Weather::Thunder);
+        world.set_weather_next_time(30000);
+        world.set_spawn_pos(IVec3::new(12, 70, -5));
+
+        let data = LevelData::from_world(&world, 1234);
+        let root = to_nbt(&data);
+
+        let comp = root.as_compound().unwrap().get_compound("Data").unwrap();
+        assert_eq!(comp.get_long("Time"), Some(24123));
+        assert_eq!(comp.get_boolean("raining"), Some(true));
+        assert_eq!(comp.get_boolean("thundering"), Some(true));
+        assert_eq!(comp.get_int("rainTime"), Some(30000 - 24123));
+        assert_eq!(comp.get_int("thunderTime"), Some(30000 - 24123));
+
+        let mut bytes = Vec::new();
+        nbt::to_writer(&mut bytes, &root).unwrap();
+        let root = nbt::from_reader(&bytes[..]).unwrap();
+        let read_data = from_nbt(&root).unwrap();
+        assert_eq!(read_data, data);
+
+        let mut new_world = World::new(Dimension::Overworld);
+        read_data.apply_to_world(&mut new_world);
+        assert_eq!(new_world.get_time(), 24123);
+        assert_eq!(new_world.get_weather(), Weather::Thunder);
+        assert_eq!(new_world.get_rain_strength(), 1.0);
+        assert_eq!(new_world.get_thunder_strength(), 1.0);
+        assert_eq!(new_world.get_weather_next_time(), 30000);
+        assert_eq!(new_world.get_spawn_pos(), IVec3::new(12, 70, -5));
+
+    }
+
+}
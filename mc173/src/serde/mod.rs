@@ -2,4 +2,5 @@
 
 pub mod region;
 pub mod chunk;
+pub mod level;
 pub mod nbt;
@@ -0,0 +1,78 @@
+//! Chunk sources are synchronous providers of chunk snapshots, they can be used to load
+//! chunks on demand into a world, see [`World::load_chunk_from_source`]. Unlike the 
+//! threaded [`ChunkStorage`](crate::storage::ChunkStorage), a source is directly called
+//! by the world.
+//! 
+//! [`World::load_chunk_from_source`]: crate::world::World::load_chunk_from_source
+
+use std::collections::HashMap;
+
+use crate::storage::StorageError;
+use crate::world::ChunkSnapshot;
+
+
+/// A trait for all synchronous chunk sources.
+pub trait ChunkSource {
+
+    /// Load the chunk at the given coordinates, the [`ChunkSourceError::Unsupported`]
+    /// error should be returned if this source cannot provide this chunk.
+    fn load(&mut self, cx: i32, cz: i32) -> Result<ChunkSnapshot, ChunkSourceError>;
+
+    /// Save the given chunk snapshot, by default saving is unsupported.
+    fn save(&mut self, snapshot: ChunkSnapshot) -> Result<(), ChunkSourceError> {
+        let _ = snapshot;
+        Err(ChunkSourceError::Unsupported)
+    }
+
+}
+
+/// A chunk source that keeps all saved chunks in memory, a chunk that has never been
+/// saved cannot be loaded from this source.
+#[derive(Default, Clone)]
+pub struct MemoryChunkSource {
+    /// All snapshots saved in this source.
+    snapshots: HashMap<(i32, i32), ChunkSnapshot>,
+}
+
+impl MemoryChunkSource {
+
+    /// Create a new empty memory chunk source.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the number of chunks saved in this source.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Return true if no chunk is saved in this source.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+}
+
+impl ChunkSource for MemoryChunkSource {
+
+    fn load(&mut self, cx: i32, cz: i32) -> Result<ChunkSnapshot, ChunkSourceError> {
+        self.snapshots.get(&(cx, cz)).cloned().ok_or(ChunkSourceError::Unsupported)
+    }
+
+    fn save(&mut self, snapshot: ChunkSnapshot) -> Result<(), ChunkSourceError> {
+        self.snapshots.insert((snapshot.cx, snapshot.cz), snapshot);
+        Ok(())
+    }
+
+}
+
+/// Error type returned by chunk sources.
+#[derive(thiserror::Error, Debug)]
+pub enum ChunkSourceError {
+    #[error("unsupported")]
+    Unsupported,
+    #[error("storage: {0}")]
+    Storage(#[from] StorageError),
+}
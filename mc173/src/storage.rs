@@ -269,11 +269,11 @@ impl<G: ChunkGenerator> StorageWorker<G> {
             Err(err) => {
                 // Immediately send error, we don't want to load the chunk if there is
                 // an error in the region file, in order to avoid overwriting the error.
-                self.storage_reply_sender.send(ChunkStorageReply::Load { cx, cz, res: Err(err) }).is_ok()
+                self.storage_reply_sender.send(ChunkStorageReply::Load { cx, cz, res: Err(err), generated: false }).is_ok()
             }
             Ok(Some(snapshot)) => {
                 // Immediately send the loaded chunk.
-                self.storage_reply_sender.send(ChunkStorageReply::Load { cx, cz, res: Ok(snapshot) }).is_ok()
+                self.storage_reply_sender.send(ChunkStorageReply::Load { cx, cz, res: Ok(snapshot), generated: false }).is_ok()
             }
             Ok(None) => {
                 // The chunk has not been found in region files, generate it.
@@ -479,7 +479,7 @@ impl<G: ChunkGenerator> StorageWorker<G> {
                         }
 
                         // Finally return the chunk snapshot!
-                        if self.storage_reply_sender.send(ChunkStorageReply::Load { cx, cz, res: Ok(snapshot) }).is_err() {
+                        if self.storage_reply_sender.send(ChunkStorageReply::Load { cx: current_cx, cz: current_cz, res: Ok(snapshot), generated: true }).is_err() {
                             // Directly abort to stop the thread because the handle is dropped.
                             return false;
                         }
@@ -572,9 +572,11 @@ enum StorageRequest {
     Save { snapshot: ChunkSnapshot },
 }
 
-/// A reply from the storage for a previously requested chunk loading or saving.
+/// A reply from the storage for a previously requested chunk loading or saving. The
+/// generated flag of a load reply is true if the chunk has just been generated instead
+/// of being loaded from its region file.
 pub enum ChunkStorageReply {
-    Load { cx: i32, cz: i32, res: Result<ChunkSnapshot, StorageError> },
+    Load { cx: i32, cz: i32, res: Result<ChunkSnapshot, StorageError>, generated: bool },
     Save { cx: i32, cz: i32, res: Result<(), StorageError> },
 }
 
@@ -597,3 +599,127 @@ pub enum StorageError {
     #[error("nbt parse: {0}")]
     NbtParse(#[from] NbtParseError),
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::Duration;
+
+    use glam::IVec3;
+
+    use crate::gen::OverworldGenerator;
+    use crate::gen::ChunkGenerator;
+    use crate::block;
+
+    use super::*;
+
+    /// Poll the storage until a reply is received, panicking after a few seconds.
+    fn poll_reply(storage: &mut ChunkStorage) -> ChunkStorageReply {
+        let start = Instant::now();
+        loop {
+            if let Some(reply) = storage.poll() {
+                return reply;
+            }
+            assert!(start.elapsed() < Duration::from_secs(10), "storage reply timed out");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn load_biomes() {
+
+        let dir = std::env::temp_dir().join(format!("mc173-test-load-biomes-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut storage = ChunkStorage::new(&dir, OverworldGenerator::new(9999), 1);
+
+        // Biomes are never serialized, so the saved chunk only has void biomes.
+        storage.request_save(ChunkSnapshot::new(3, -7));
+        match poll_reply(&mut storage) {
+            ChunkStorageReply::Save { cx: 3, cz: -7, res } => res.unwrap(),
+            _ => panic!("expected save reply"),
+        }
+
+        storage.request_load(3, -7);
+        let snapshot = match poll_reply(&mut storage) {
+            ChunkStorageReply::Load { cx: 3, cz: -7, res, generated: false } => res.unwrap(),
+            _ => panic!("expected load reply"),
+        };
+
+        let generator = OverworldGenerator::new(9999);
+        let mut expected = Chunk::new();
+        let expected_access = Arc::get_mut(&mut expected).unwrap();
+        generator.gen_biomes(3, -7, expected_access, &mut Default::default());
+
+        assert!(snapshot.chunk.biome.iter().all(|&biome| biome != crate::biome::Biome::Void));
+        assert_eq!(snapshot.chunk.biome, expected.biome);
+
+        drop(storage);
+        let _ = std::fs::remove_dir_all(&dir);
+
+    }
+
+    #[test]
+    fn populate_neighbors() {
+
+        let dir = std::env::temp_dir().join(format!("mc173-test-populate-neighbors-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut storage = ChunkStorage::new(&dir, OverworldGenerator::new(9999), 2);
+        let mut world = World::new(Dimension::Overworld);
+
+        for cx in 4..4 + 2 {
+            for cz in 4..4 + 2 {
+                storage.request_load(cx, cz);
+            }
+        }
+
+        // Neighbor chunks may also be returned when fully populated, so we wait until
+        // all requested chunks are received.
+        while storage.request_load_count() != 0 {
+            match poll_reply(&mut storage) {
+                ChunkStorageReply::Load { cx, cz, res, generated } => {
+                    assert!(generated);
+                    let snapshot = res.unwrap();
+                    assert_eq!((snapshot.cx, snapshot.cz), (cx, cz), "incoherent reply coordinates");
+                    world.insert_chunk_snapshot(snapshot);
+                }
+                ChunkStorageReply::Save { res, .. } => res.unwrap(),
+            }
+        }
+
+        // Every tree should have its leaves complete around the top of its trunk, even
+        // when the tree straddles the border between two chunks. Leaves are not placed
+        // where the block is already opaque, so we just check that there is no air in
+        // the layer under the top of the trunk, except for the four random corners.
+        let min = IVec3::new(4 * 16 + 2, 0, 4 * 16 + 2);
+        let max = IVec3::new(4 * 16 + 30, 128, 4 * 16 + 30);
+
+        let mut trees = 0;
+        for (pos, id, _) in world.iter_blocks_in(min, max) {
+            if id == block::LOG && world.is_block(pos - IVec3::Y, block::DIRT) {
+
+                let mut top = pos;
+                while world.is_block(top + IVec3::Y, block::LOG) {
+                    top += IVec3::Y;
+                }
+
+                let filled = world.iter_blocks_in(top - IVec3::new(2, 1, 2), top + IVec3::new(3, 0, 3))
+                    .filter(|&(_, id, _)| id != block::AIR)
+                    .count();
+
+                assert!(filled >= 21, "tree at {pos} has been cut off");
+                trees += 1;
+
+            }
+        }
+
+        assert_ne!(trees, 0, "no tree has been generated");
+
+        drop(storage);
+        let _ = std::fs::remove_dir_all(&dir);
+
+    }
+
+}
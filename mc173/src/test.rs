@@ -0,0 +1,24 @@
+//! Common utilities for unit tests.
+
+use std::sync::Arc;
+
+use glam::IVec3;
+
+use crate::world::{World, Dimension};
+use crate::chunk::Chunk;
+
+
+/// Create an overworld with a single chunk at 0/0 that has a floor of the given block
+/// at Y 63, so that entities can stand on it at Y 64.
+pub fn new_floor_world(floor_id: u8) -> World {
+
+    let mut chunk = Chunk::new();
+    let chunk_mut = Arc::make_mut(&mut chunk);
+    chunk_mut.fill_block(IVec3::new(0, 63, 0), IVec3::new(16, 1, 16), floor_id, 0);
+    chunk_mut.recompute_all_height();
+
+    let mut world = World::new(Dimension::Overworld);
+    world.set_chunk(0, 0, chunk);
+    world
+
+}
@@ -1,5 +1,7 @@
 //! Various uncategorized utilities.
 
+use std::time::{Duration, Instant};
+
 
 /// A function to better inline the default function call.
 #[inline(always)]
@@ -29,6 +31,59 @@ impl FadingAverage {
 }
 
 
+/// A timer used to pace ticks at a fixed rate, by sleeping the remaining time of each 
+/// tick. It also keeps a fading average of the ratio between the actual tick duration
+/// and the target duration, a ratio greater than 1.0 means that ticks are lagging.
+#[derive(Debug, Clone)]
+pub struct TickTimer {
+    /// The target duration of a single tick.
+    duration: Duration,
+    /// Fading average of the actual tick duration divided by the target duration.
+    load: FadingAverage,
+}
+
+impl TickTimer {
+
+    /// Create a new tick timer with the given target ticks per second.
+    pub fn new(tps: u32) -> Self {
+        Self {
+            duration: Duration::from_secs(1) / tps,
+            load: FadingAverage::default(),
+        }
+    }
+
+    /// Get the target duration of a single tick.
+    #[inline]
+    pub fn get_duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Get the fading average of the tick duration relative to the target duration.
+    #[inline]
+    pub fn get_load(&self) -> f32 {
+        self.load.get()
+    }
+
+    /// Pad the tick that started at the given instant by sleeping the remaining time to
+    /// reach the target duration. If the tick was already longer than the target, this
+    /// doesn't sleep and returns the duration exceeding the target.
+    pub fn pad(&mut self, start: Instant) -> Option<Duration> {
+        
+        let elapsed = start.elapsed();
+        self.load.push(elapsed.as_secs_f32() / self.duration.as_secs_f32(), 0.05);
+
+        if let Some(missing) = self.duration.checked_sub(elapsed) {
+            std::thread::sleep(missing);
+            None
+        } else {
+            Some(elapsed - self.duration)
+        }
+
+    }
+
+}
+
+
 /// Internal utility function to split a string at a given byte index, but while keeping
 /// utf8 boundary and not panicking like [`str::split_at`]. A value greater than `s.len()`
 /// will panic.
@@ -40,3 +95,34 @@ pub fn split_at_utf8_boundary(s: &str, mut index: usize) -> (&str, &str) {
     }
     s.split_at(index)
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn tick_timer() {
+
+        let mut timer = TickTimer::new(20);
+        assert_eq!(timer.get_duration(), Duration::from_millis(50));
+
+        // A short tick is padded up to the target duration.
+        let start = Instant::now();
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(timer.pad(start), None);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert!(timer.get_load() > 0.0);
+
+        // A long tick reports the exceeding duration and doesn't sleep.
+        let start = Instant::now();
+        std::thread::sleep(Duration::from_millis(80));
+        let exceeding = timer.pad(start).unwrap();
+        let elapsed = start.elapsed();
+        assert!(exceeding >= Duration::from_millis(30));
+        assert!(elapsed - exceeding - Duration::from_millis(50) < Duration::from_millis(10));
+
+    }
+
+}
@@ -323,6 +323,62 @@ impl World {
 
     }
 
+    /// Return true if there is no opaque cube block between the two given positions, 
+    /// this steps through every block crossed by the segment between the two points.
+    /// Fluids and non-opaque blocks are not blocking the sight, blocks in unloaded 
+    /// chunks are not blocking the sight either.
+    pub fn has_line_of_sight(&self, from: DVec3, to: DVec3) -> bool {
+
+        let ray = to - from;
+        let mut block_pos = from.floor().as_ivec3();
+        let stop_pos = to.floor().as_ivec3();
+
+        // For each axis, the step of the block position when crossing a block boundary, 
+        // the ray fraction between two boundaries and the ray fraction to the next one.
+        let mut step = IVec3::ZERO;
+        let mut delta = DVec3::INFINITY;
+        let mut next = DVec3::INFINITY;
+
+        for axis in 0..3 {
+            if ray[axis] > 0.0 {
+                step[axis] = 1;
+                delta[axis] = 1.0 / ray[axis];
+                next[axis] = (block_pos[axis] as f64 + 1.0 - from[axis]) * delta[axis];
+            } else if ray[axis] < 0.0 {
+                step[axis] = -1;
+                delta[axis] = -1.0 / ray[axis];
+                next[axis] = (from[axis] - block_pos[axis] as f64) * delta[axis];
+            }
+        }
+
+        loop {
+
+            if self.is_block_opaque_cube(block_pos) {
+                return false;
+            } else if block_pos == stop_pos {
+                return true;
+            }
+
+            // Cross the nearest block boundary.
+            let axis = if next.x < next.y && next.x < next.z { 
+                0 
+            } else if next.y < next.z { 
+                1 
+            } else { 
+                2 
+            };
+
+            if next[axis] > 1.0 {
+                return true;
+            }
+
+            block_pos[axis] += step[axis];
+            next[axis] += delta[axis];
+
+        }
+
+    }
+
 }
 
 
@@ -448,3 +504,50 @@ pub struct RayTraceHit {
     /// The face of the block.
     pub face: Face,
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::world::Dimension;
+    use crate::chunk::Chunk;
+
+    use super::*;
+
+    #[test]
+    fn line_of_sight() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let from = DVec3::new(2.5, 64.5, 2.5);
+        let to = DVec3::new(13.2, 66.7, 10.4);
+
+        assert!(world.has_line_of_sight(from, to));
+        assert!(world.has_line_of_sight(to, from));
+        assert!(world.has_line_of_sight(from, from));
+
+        // Fluids and transparent blocks don't block sight.
+        for y in 60..70 {
+            for z in 0..16 {
+                world.set_block(IVec3::new(6, y, z), block::WATER_STILL, 0);
+                world.set_block(IVec3::new(8, y, z), block::GLASS, 0);
+            }
+        }
+
+        assert!(world.has_line_of_sight(from, to));
+
+        // A wall between the two points.
+        for y in 60..70 {
+            for z in 0..16 {
+                world.set_block(IVec3::new(10, y, z), block::STONE, 0);
+            }
+        }
+
+        assert!(!world.has_line_of_sight(from, to));
+        assert!(!world.has_line_of_sight(to, from));
+        assert!(world.has_line_of_sight(from, DVec3::new(9.9, 64.5, 2.5)));
+
+    }
+
+}
@@ -4,23 +4,60 @@
 use glam::IVec3;
 
 use crate::block::material::Material;
+use crate::item::ItemStack;
 use crate::{block, item};
 
-use super::World;
+use super::{World, Event, BlockEvent, GroupSoundKind};
 
 
 /// Methods related to block breaking.
 impl World {
 
-    /// Break a block naturally and loot its items. This returns true if successful, false
-    /// if the chunk/pos was not valid. It also notifies blocks around, this is basically
-    /// a wrapper around [`set_block_notify`](Self::set_block_notify) method.
-    pub fn break_block(&mut self, pos: IVec3) -> Option<(u8, u8)> {
+    /// Break a block and loot its items if `drops` is true. This returns the previous 
+    /// block and metadata if successful, none if the chunk/pos was not valid. It also 
+    /// notifies blocks around, this is basically a wrapper around 
+    /// [`set_block_notify`](Self::set_block_notify) method, so block change events are
+    /// pushed in both cases.
+    /// 
+    /// Note that container blocks always drop their contents, only the block's own loot
+    /// is controlled by `drops`. When dropping, this is the same as breaking the block 
+    /// with an empty hand, see [`break_block_with`](Self::break_block_with).
+    pub fn break_block(&mut self, pos: IVec3, drops: bool) -> Option<(u8, u8)> {
+        self.break_block_inner(pos, drops.then_some(0))
+    }
+
+    /// Break a block naturally with the given item in hand and loot its items, the item
+    /// id is 0 if there is no item in hand. Some blocks have special loots depending on
+    /// the item used, such as tall grass and dead bush dropping themselves when broken
+    /// with shears. 
+    pub fn break_block_with(&mut self, pos: IVec3, item_id: u16) -> Option<(u8, u8)> {
+        self.break_block_inner(pos, Some(item_id))
+    }
+
+    /// Internal function to break a block, the block's loot is only spawned if an item
+    /// id is given, this item id is 0 if there is no item in hand.
+    fn break_block_inner(&mut self, pos: IVec3, drop_item_id: Option<u16>) -> Option<(u8, u8)> {
         let (prev_id, prev_metadata) = self.set_block_notify(pos, block::AIR, 0)?;
-        self.spawn_block_loot(pos, prev_id, prev_metadata, 1.0);
+        self.push_break_sound(pos, prev_id);
+        match (prev_id, drop_item_id) {
+            (_, None) => {}
+            (block::TALL_GRASS | block::DEAD_BUSH, Some(item::SHEARS)) => 
+                self.spawn_loot(pos.as_dvec3() + 0.5, ItemStack::new_block(prev_id, prev_metadata), 0.7),
+            _ => self.spawn_block_loot(pos, prev_id, prev_metadata, 1.0),
+        }
         Some((prev_id, prev_metadata))
     }
 
+    /// Internal function to push the break sound event of the given broken block id.
+    fn push_break_sound(&mut self, pos: IVec3, id: u8) {
+        if id != block::AIR {
+            self.push_event(Event::Block {
+                pos,
+                inner: BlockEvent::GroupSound { group: block::sound_group(id), kind: GroupSoundKind::Break },
+            });
+        }
+    }
+
     /// Get the minimum ticks duration required to break the block given its id.
     pub fn get_break_duration(&self, item_id: u16, block_id: u8, in_water: bool, on_ground: bool) -> f32 {
 
@@ -213,3 +250,222 @@ impl World {
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::block_entity::chest::ChestBlockEntity;
+    use crate::block_entity::furnace::FurnaceBlockEntity;
+    use crate::block_entity::BlockEntity;
+    use crate::entity::{Entity, BaseKind};
+    use crate::chunk::Chunk;
+    use crate::world::{Dimension, Event, BlockEvent, GroupSoundKind};
+    use crate::block::SoundGroup;
+
+    use super::*;
+
+    /// Take all item stacks of item entities in the world, removing these entities.
+    fn take_items(world: &mut World) -> Vec<ItemStack> {
+        let items = world.iter_entities()
+            .filter_map(|(id, entity)| match entity {
+                Entity(_, BaseKind::Item(item)) => Some((id, item.stack)),
+                _ => None
+            })
+            .collect::<Vec<_>>();
+        for &(id, _) in &items {
+            world.remove_entity(id, "test");
+        }
+        items.into_iter().map(|(_, stack)| stack).collect()
+    }
+
+    #[test]
+    fn break_tall_grass() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let pos = IVec3::new(8, 64, 8);
+        world.set_block(pos - IVec3::Y, block::GRASS, 0);
+
+        // Tall grass breaks instantly.
+        assert_eq!(world.get_break_duration(0, block::TALL_GRASS, false, true), 0.0);
+
+        // Bare hands only drop seeds, by chance.
+        for _ in 0..200 {
+            world.set_block(pos, block::TALL_GRASS, 1);
+            assert_eq!(world.break_block_with(pos, 0), Some((block::TALL_GRASS, 1)));
+        }
+
+        let items = take_items(&mut world);
+        assert!(!items.is_empty() && items.len() < 200);
+        assert!(items.iter().all(|&stack| stack == ItemStack::new_single(item::WHEAT_SEEDS, 0)));
+
+        // Shears drop the tall grass itself.
+        world.set_block(pos, block::TALL_GRASS, 1);
+        world.break_block_with(pos, item::SHEARS);
+        assert_eq!(take_items(&mut world), [ItemStack::new_block(block::TALL_GRASS, 1)]);
+
+        // Same for dead bush, that never drops anything otherwise.
+        world.set_block(pos - IVec3::Y, block::SAND, 0);
+        world.set_block(pos, block::DEAD_BUSH, 0);
+        world.break_block_with(pos, 0);
+        assert_eq!(take_items(&mut world), []);
+        world.set_block(pos, block::DEAD_BUSH, 0);
+        world.break_block_with(pos, item::SHEARS);
+        assert_eq!(take_items(&mut world), [ItemStack::new_block(block::DEAD_BUSH, 0)]);
+
+        // Tall grass pops off when its support is removed.
+        world.set_block(pos - IVec3::Y, block::DIRT, 0);
+        world.set_block(pos, block::TALL_GRASS, 1);
+        world.set_block_notify(pos - IVec3::Y, block::AIR, 0);
+        assert!(world.is_block(pos, block::AIR));
+
+    }
+
+    #[test]
+    fn break_chest() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let pos = IVec3::new(8, 64, 8);
+        let mut chest = ChestBlockEntity::default();
+        chest.inv[0] = ItemStack::new_block(block::DIRT, 0).with_size(64);
+        chest.inv[5] = ItemStack::new_single(item::DIAMOND, 0).with_size(3);
+        chest.inv[26] = ItemStack::new_single(item::IRON_PICKAXE, 0).with_damage(12);
+        world.set_block_entity_notify(pos, block::CHEST, 0, BlockEntity::Chest(chest));
+
+        assert_eq!(world.break_block(pos, true), Some((block::CHEST, 0)));
+        assert!(world.get_block_entity(pos).is_none());
+
+        let items = take_items(&mut world);
+        let count = |stack: ItemStack| items.iter()
+            .filter(|s| s.id == stack.id && s.damage == stack.damage)
+            .map(|s| s.size)
+            .sum::<u16>();
+
+        assert_eq!(count(ItemStack::new_block(block::CHEST, 0)), 1);
+        assert_eq!(count(ItemStack::new_block(block::DIRT, 0)), 64);
+        assert_eq!(count(ItemStack::new_single(item::DIAMOND, 0)), 3);
+        assert_eq!(count(ItemStack::new_single(item::IRON_PICKAXE, 12)), 1);
+        assert!(items.iter().all(|s| s.size <= 30));
+
+        // The furnace being lit should not drop anything.
+        let mut furnace = FurnaceBlockEntity::default();
+        furnace.fuel_stack = ItemStack::new_single(item::COAL, 0).with_size(8);
+        world.set_block_entity_notify(pos, block::FURNACE, 0, BlockEntity::Furnace(furnace));
+        world.set_block_notify(pos, block::FURNACE_LIT, 0);
+        assert!(take_items(&mut world).is_empty());
+        assert!(world.get_block_entity(pos).is_some());
+
+        world.break_block(pos, true);
+        assert_eq!(take_items(&mut world).iter().map(|s| s.size).sum::<u16>(), 9);
+
+    }
+
+    #[test]
+    fn break_support_cascade() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let pos = IVec3::new(8, 63, 8);
+        world.set_block(pos, block::DIRT, 0);
+        world.set_block(pos + IVec3::X, block::WATER_STILL, 0);
+        for y in 1..=3 {
+            world.set_block(pos + IVec3::Y * y, block::SUGAR_CANES, 0);
+        }
+
+        // A torch attached on the side of the dirt, it must stay in place while its
+        // support is present.
+        world.set_block(pos - IVec3::X, block::TORCH, 2);
+        world.notify_block(pos - IVec3::X, block::DIRT);
+        assert!(world.is_block(pos - IVec3::X, block::TORCH));
+
+        world.swap_events(Some(Vec::new()));
+        assert_eq!(world.break_block(pos, true), Some((block::DIRT, 0)));
+
+        for y in 0..=3 {
+            assert!(world.is_block(pos + IVec3::Y * y, block::AIR));
+        }
+        assert!(world.is_block(pos - IVec3::X, block::AIR));
+
+        // Every removed block has its own block event.
+        let events = world.swap_events(None).unwrap();
+        let removed = events.iter()
+            .filter(|event| matches!(event, Event::Block { inner: BlockEvent::Set { id: block::AIR, .. }, .. }))
+            .count();
+        assert_eq!(removed, 5);
+
+        let items = take_items(&mut world);
+        let count = |id: u16| items.iter().filter(|s| s.id == id).map(|s| s.size).sum::<u16>();
+        assert_eq!(count(item::SUGAR_CANES), 3);
+        assert_eq!(count(block::TORCH as u16), 1);
+        assert_eq!(count(block::DIRT as u16), 1);
+
+    }
+
+    #[test]
+    fn break_drops() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let pos = IVec3::new(8, 64, 8);
+
+        world.set_block(pos, block::COBBLESTONE, 0);
+        world.swap_events(Some(Vec::new()));
+        assert_eq!(world.break_block(pos, true), Some((block::COBBLESTONE, 0)));
+        assert_eq!(take_items(&mut world), [ItemStack::new_block(block::COBBLESTONE, 0)]);
+        assert!(world.swap_events(Some(Vec::new())).unwrap().iter()
+            .any(|event| matches!(event, Event::Block { inner: BlockEvent::Set { id: block::AIR, prev_id: block::COBBLESTONE, .. }, .. })));
+
+        world.set_block(pos, block::COBBLESTONE, 0);
+        world.swap_events(Some(Vec::new()));
+        assert_eq!(world.break_block(pos, false), Some((block::COBBLESTONE, 0)));
+        assert!(world.is_block(pos, block::AIR));
+        assert!(take_items(&mut world).is_empty());
+        assert!(world.swap_events(None).unwrap().iter()
+            .any(|event| matches!(event, Event::Block { inner: BlockEvent::Set { id: block::AIR, prev_id: block::COBBLESTONE, .. }, .. })));
+
+        // Unloaded chunk.
+        assert_eq!(world.break_block(IVec3::new(100, 64, 100), true), None);
+
+    }
+
+    #[test]
+    fn break_sound() {
+
+        assert_eq!(block::sound_group(block::LOG), SoundGroup::Wood);
+        assert_eq!(block::sound_group(block::GRASS), SoundGroup::Grass);
+        assert_eq!(block::sound_group(block::GLASS), SoundGroup::Glass);
+        assert_eq!(block::sound_group(block::STONE), SoundGroup::Stone);
+        assert_eq!(block::sound_group(block::JUKEBOX), SoundGroup::Stone);
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let pos = IVec3::new(8, 64, 8);
+        world.set_block(pos, block::LOG, 0);
+        world.swap_events(Some(Vec::new()));
+        world.break_block(pos, true);
+
+        let sounds = world.swap_events(None).unwrap().into_iter()
+            .filter_map(|event| match event {
+                Event::Block { pos, inner: BlockEvent::GroupSound { group, kind } } => Some((pos, group, kind)),
+                _ => None
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(sounds, [(pos, SoundGroup::Wood, GroupSoundKind::Break)]);
+
+        // Breaking air plays no sound.
+        world.swap_events(Some(Vec::new()));
+        world.break_block(pos, true);
+        assert!(!world.swap_events(None).unwrap().iter()
+            .any(|event| matches!(event, Event::Block { inner: BlockEvent::GroupSound { .. }, .. })));
+
+    }
+
+}
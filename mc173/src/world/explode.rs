@@ -1,5 +1,7 @@
 //! Make explosion in world.
 
+use std::collections::HashSet;
+
 use glam::{DVec3, IVec3};
 
 use tracing::trace;
@@ -8,13 +10,18 @@ use crate::geom::BoundingBox;
 use crate::rand::JavaRandom;
 
 use crate::world::bound::RayTraceKind;
-use crate::entity::{Entity, Hurt};
+use crate::entity::{Entity, Tnt};
 use crate::world::Event;
 use crate::block;
 
 use super::World;
 
 
+/// The chance for each destroyed block's loot to be dropped by an explosion.
+/// PARITY: Later versions use the inverse of the radius, but beta 1.7.3 uses a fixed
+///  chance whatever the explosion radius.
+const LOOT_CHANCE: f32 = 0.3;
+
 /// Methods related to explosions.
 impl World {
 
@@ -29,6 +36,7 @@ impl World {
 
         let mut rand = JavaRandom::new_seeded();
         let mut affected_pos = Vec::new();
+        let mut affected_set = HashSet::new();
 
         // Start by computing each destroyed block.
         for dx in 0..16 {
@@ -65,7 +73,10 @@ impl World {
                                     self.set_block_notify(block_pos, block::FIRE, 0);
                                 }
 
-                                affected_pos.push((block_pos, block != block::AIR));
+                                // Many rays can go through the same block.
+                                if affected_set.insert(block_pos) {
+                                    affected_pos.push((block_pos, block != block::AIR));
+                                }
 
                             }
 
@@ -135,30 +146,186 @@ impl World {
 
         }
 
+        let mut entities = Vec::with_capacity(damaged_entities.len());
+
         // Finally alter entities.
         for (eid, damage, accel) in damaged_entities {
             
-            let Entity(base, _) = self.get_entity_mut(eid).unwrap();
-
-            base.hurt.push(Hurt {
-                damage,
-                origin_id,
-            });
+            self.damage_entity(eid, damage, origin_id);
 
+            let Entity(base, _) = self.get_entity_mut(eid).unwrap();
             base.vel += accel;
+            entities.push((eid, accel));
 
         }
 
+        let mut blocks = Vec::with_capacity(affected_pos.len());
+
         // Finally drain the destroyed pos and remove blocks.
         for (pos, should_destroy) in affected_pos {
             if should_destroy {
                 // We can unwrap because these position were previously checked.
                 let (prev_block, prev_metadata) = self.set_block_notify(pos, block::AIR, 0).unwrap();
-                self.spawn_block_loot(pos, prev_block, prev_metadata, 0.3);
+                // REF: Explosion::doExplosionB
+                self.spawn_block_loot(pos, prev_block, prev_metadata, LOOT_CHANCE);
+                // Destroyed TNT is ignited with a short random fuse for chain reactions.
+                // REF: BlockTNT::onBlockDestroyedByExplosion
+                if prev_block == block::TNT {
+                    let fuse_time = self.rand.next_int_bounded(20) as u32 + 10;
+                    self.spawn_primed_tnt(pos, fuse_time);
+                }
             }
+            blocks.push(pos);
         }
 
-        self.push_event(Event::Explode { center, radius });
+        self.push_event(Event::Explode { center, radius, blocks, entities });
+
+    }
+
+    /// Spawn a primed TNT entity at the given block position with the given fuse time,
+    /// a fuse of 80 ticks is used when ignited by players, redstone or fire. The TNT 
+    /// block itself is not removed by this function. The entity id is returned.
+    /// 
+    /// REF: BlockTNT::onBlockDestroyedByPlayer, EntityTNTPrimed::EntityTNTPrimed
+    pub fn spawn_primed_tnt(&mut self, pos: IVec3, fuse_time: u32) -> u32 {
+        let angle = self.rand.next_double() * std::f64::consts::TAU;
+        self.spawn_entity(Tnt::new_with(|base, tnt| {
+            base.pos = pos.as_dvec3() + 0.5;
+            base.vel = DVec3::new(-angle.sin() * 0.02, 0.2, -angle.cos() * 0.02);
+            tnt.fuse_time = fuse_time;
+        }))
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::entity::{Pig, BaseKind};
+    use crate::item::ItemStack;
+    use crate::chunk::Chunk;
+    use crate::world::Dimension;
+
+    use super::*;
+
+    #[test]
+    fn primed_tnt() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let id = world.spawn_primed_tnt(IVec3::new(4, 64, 4), 80);
+        let Some(Entity(base, BaseKind::Tnt(tnt))) = world.get_entity(id) else { panic!() };
+        assert_eq!(tnt.fuse_time, 80);
+        assert_eq!(base.pos, DVec3::new(4.5, 64.5, 4.5));
+        assert_eq!(base.vel.y, 0.2);
+
+    }
+
+    #[test]
+    fn explode_tnt_chain() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let center = IVec3::new(8, 64, 8);
+        world.set_block(center - IVec3::X, block::TNT, 0);
+        world.set_block(center + IVec3::X, block::TNT, 0);
+
+        world.explode(center.as_dvec3() + 0.5, 4.0, false, None);
+        assert!(world.is_block(center - IVec3::X, block::AIR));
+        assert!(world.is_block(center + IVec3::X, block::AIR));
+
+        // Neighbor TNT blocks are primed with a short fuse instead of being destroyed.
+        let mut tnt = world.iter_entities()
+            .filter_map(|(_, entity)| match entity {
+                Entity(base, BaseKind::Tnt(tnt)) => Some((base.pos.floor().as_ivec3(), tnt.fuse_time)),
+                _ => None
+            })
+            .collect::<Vec<_>>();
+        
+        tnt.sort_by_key(|&(pos, _)| pos.x);
+        assert_eq!(tnt.len(), 2);
+        assert_eq!(tnt[0].0, center - IVec3::X);
+        assert_eq!(tnt[1].0, center + IVec3::X);
+        assert!(tnt.iter().all(|&(_, fuse_time)| (10..30).contains(&fuse_time)));
+
+    }
+
+    #[test]
+    fn explode_event() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+        world.swap_events(Some(Vec::new()));
+
+        for x in 5..12 {
+            for y in 60..67 {
+                for z in 5..12 {
+                    world.set_block(IVec3::new(x, y, z), block::DIRT, 0);
+                }
+            }
+        }
+
+        let pig_id = world.spawn_entity(Pig::new_with(|base, _, _| {
+            base.pos = DVec3::new(8.5, 67.0, 8.5);
+        }));
+
+        let center = IVec3::new(8, 63, 8);
+        world.explode(center.as_dvec3() + 0.5, 4.0, false, None);
+
+        let events = world.swap_events(None).unwrap();
+        let Some(Event::Explode { blocks, entities, .. }) = events.iter()
+            .find(|event| matches!(event, Event::Explode { .. })) else {
+            panic!("missing explode event");
+        };
+
+        // The center block and its direct neighbors are always destroyed.
+        for pos in [center, center + IVec3::X, center - IVec3::X, center + IVec3::Y, center - IVec3::Y, center + IVec3::Z, center - IVec3::Z] {
+            assert!(blocks.contains(&pos), "missing {pos}");
+            assert_eq!(world.get_block(pos), Some((block::AIR, 0)));
+        }
+
+        // Every position is only present once.
+        assert_eq!(blocks.iter().collect::<HashSet<_>>().len(), blocks.len());
+
+        assert!(entities.iter().any(|&(id, _)| id == pig_id));
+
+    }
+
+    #[test]
+    fn explode_loot_chance() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+        world.swap_events(Some(Vec::new()));
+        *world.get_rand_mut() = JavaRandom::new(1234);
+
+        for x in 2..15 {
+            for y in 57..70 {
+                for z in 2..15 {
+                    world.set_block(IVec3::new(x, y, z), block::DIRT, 0);
+                }
+            }
+        }
+
+        world.explode(DVec3::new(8.5, 63.5, 8.5), 4.0, false, None);
+
+        let events = world.swap_events(None).unwrap();
+        let Some(Event::Explode { blocks, .. }) = events.iter()
+            .find(|event| matches!(event, Event::Explode { .. })) else {
+            panic!("missing explode event");
+        };
+
+        let destroyed = blocks.len();
+        let dropped = world.iter_entities()
+            .filter(|(_, entity)| matches!(entity, Entity(_, BaseKind::Item(item)) if item.stack == ItemStack::new_block(block::DIRT, 0)))
+            .count();
+
+        assert!(destroyed > 100, "too few destroyed blocks: {destroyed}");
+        let ratio = dropped as f32 / destroyed as f32;
+        assert!((LOOT_CHANCE - 0.1..LOOT_CHANCE + 0.1).contains(&ratio), "unexpected drop ratio: {ratio}");
 
     }
 
@@ -1,13 +1,16 @@
-//! Interaction of players with blocks in the world.
+//! Interaction of players with blocks and entities in the world.
 
-use glam::IVec3;
+use glam::{DVec3, IVec3};
 
+use crate::entity::{Entity, BaseKind, LivingKind, Minecart, MinecartKind};
 use crate::block::material::Material;
 use crate::block_entity::BlockEntity;
+use crate::inventory::InventoryHandle;
+use crate::item::{self, ItemStack};
 use crate::geom::Face;
 use crate::block;
 
-use super::{Event, World};
+use super::{Event, EntityEvent, World};
 
 
 /// Methods related to block interactions when client clicks on a block.
@@ -198,6 +201,198 @@ impl World {
 
 }
 
+/// Methods related to entity interactions when client right clicks on an entity.
+impl World {
+
+    /// Interact with an entity given its id, the interacting entity is also given with
+    /// its inventory and the index of the item stack being used. The inventory handle
+    /// records changes to the stacks, and the caller should update them if needed. This
+    /// function returns the interaction result to indicate if the interaction was 
+    /// handled.
+    pub fn interact_entity(&mut self, inv: &mut InventoryHandle, index: usize, entity_id: u32, target_id: u32) -> Interaction {
+        match self.get_entity(target_id) {
+            Some(Entity(_, BaseKind::Living(_, LivingKind::Pig(_)))) => self.interact_pig(inv, index, entity_id, target_id),
+            Some(Entity(_, BaseKind::Living(_, LivingKind::Cow(_)))) => self.interact_cow(inv, index),
+            Some(Entity(_, BaseKind::Living(_, LivingKind::Sheep(_)))) => self.interact_sheep(inv, index, target_id),
+            Some(Entity(_, BaseKind::Living(_, LivingKind::Wolf(_)))) => self.interact_wolf(inv, index, entity_id, target_id),
+            Some(Entity(_, BaseKind::Boat(_) | BaseKind::Minecart(Minecart { kind: MinecartKind::Normal, .. }))) => self.interact_mount(entity_id, target_id),
+            Some(Entity(_, BaseKind::Minecart(Minecart { kind: MinecartKind::Chest { .. }, .. }))) => return Interaction::ChestMinecart { id: target_id },
+            Some(Entity(_, BaseKind::Minecart(Minecart { kind: MinecartKind::Furnace { .. }, .. }))) => self.interact_furnace_minecart(inv, index, entity_id, target_id),
+            _ => return Interaction::None
+        }.into()
+    }
+
+    /// Interact with a pig, mounting it if saddled or saddling it.
+    fn interact_pig(&mut self, inv: &mut InventoryHandle, index: usize, entity_id: u32, target_id: u32) -> bool {
+
+        let Some(Entity(_, BaseKind::Living(_, LivingKind::Pig(pig)))) = self.get_entity_mut(target_id) else {
+            return false;
+        };
+
+        if pig.saddle {
+            return self.interact_mount(entity_id, target_id);
+        }
+
+        let stack = inv.get(index);
+        if stack.id != item::SADDLE {
+            return false;
+        }
+
+        pig.saddle = true;
+        inv.set(index, stack.with_size(stack.size - 1));
+        self.push_event(Event::Entity { id: target_id, inner: EntityEvent::Metadata });
+        true
+
+    }
+
+    /// Interact with a cow, milking it if an empty bucket is used.
+    fn interact_cow(&mut self, inv: &mut InventoryHandle, index: usize) -> bool {
+        if inv.get(index).id == item::BUCKET {
+            inv.set(index, ItemStack::new_single(item::MILK_BUCKET, 0));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Interact with a sheep, shearing it with shears or changing its color with dye.
+    fn interact_sheep(&mut self, inv: &mut InventoryHandle, index: usize, target_id: u32) -> bool {
+
+        let Some(Entity(base, BaseKind::Living(_, LivingKind::Sheep(sheep)))) = self.get_entity_mut(target_id) else {
+            return false;
+        };
+
+        if sheep.sheared {
+            return false;
+        }
+
+        let stack = inv.get(index);
+        match stack.id {
+            item::SHEARS => {
+
+                sheep.sheared = true;
+
+                let pos = base.pos + DVec3::Y;
+                let wool_stack = ItemStack::new_block(block::WOOL, sheep.color);
+                let count = 2 + base.rand.next_int_bounded(3);
+
+                for _ in 0..count {
+                    self.spawn_loot(pos, wool_stack, 0.0);
+                }
+
+                inv.set(index, stack.inc_damage(1));
+
+            }
+            item::DYE => {
+
+                let color = block::wool::get_color_from_dye(stack.damage);
+                if sheep.color == color {
+                    return false;
+                }
+
+                sheep.color = color;
+                inv.set(index, stack.with_size(stack.size - 1));
+
+            }
+            _ => return false
+        }
+
+        self.push_event(Event::Entity { id: target_id, inner: EntityEvent::Metadata });
+        true
+
+    }
+
+    /// Interact with a wolf, trying to tame it with a bone or toggle its sitting state
+    /// if the interacting entity is its owner.
+    fn interact_wolf(&mut self, inv: &mut InventoryHandle, index: usize, entity_id: u32, target_id: u32) -> bool {
+
+        // Only human entities can own wolves.
+        let Some(Entity(_, BaseKind::Living(_, LivingKind::Human(human)))) = self.get_entity(entity_id) else {
+            return false;
+        };
+
+        let username = human.username.clone();
+
+        let Some(Entity(base, BaseKind::Living(living, LivingKind::Wolf(wolf)))) = self.get_entity_mut(target_id) else {
+            return false;
+        };
+
+        if let Some(owner) = &wolf.owner {
+            if *owner != username {
+                return false;
+            }
+            wolf.sitting = !wolf.sitting;
+        } else {
+
+            let stack = inv.get(index);
+            if stack.id != item::BONE || wolf.angry {
+                return false;
+            }
+
+            inv.set(index, stack.with_size(stack.size - 1));
+
+            if base.rand.next_int_bounded(3) == 0 {
+                wolf.owner = Some(username);
+                wolf.sitting = true;
+                living.health = 20;
+            }
+
+        }
+
+        self.push_event(Event::Entity { id: target_id, inner: EntityEvent::Metadata });
+        true
+
+    }
+
+    /// Interact with a furnace minecart, adding fuel if coal is used and pushing it 
+    /// away from the interacting entity.
+    /// 
+    /// REF: EntityMinecart::interact
+    fn interact_furnace_minecart(&mut self, inv: &mut InventoryHandle, index: usize, entity_id: u32, target_id: u32) -> bool {
+
+        let Some(Entity(entity_base, _)) = self.get_entity(entity_id) else {
+            return false;
+        };
+
+        let entity_pos = entity_base.pos;
+
+        let Some(Entity(base, BaseKind::Minecart(Minecart { kind: MinecartKind::Furnace { push_x, push_z, fuel }, .. }))) = self.get_entity_mut(target_id) else {
+            return false;
+        };
+
+        let stack = inv.get(index);
+        if stack.id == item::COAL {
+            *fuel += 1200;
+            inv.set(index, stack.with_size(stack.size - 1));
+        }
+
+        *push_x = base.pos.x - entity_pos.x;
+        *push_z = base.pos.z - entity_pos.z;
+        true
+
+    }
+
+    /// Interact with a rideable entity, the interacting entity is mounting it if there
+    /// is no rider yet, or dismounting it if it was already riding it.
+    /// 
+    /// REF: EntityMinecart::interact, EntityPig::interact
+    fn interact_mount(&mut self, entity_id: u32, target_id: u32) -> bool {
+
+        let Some(Entity(base, _)) = self.get_entity(target_id) else {
+            return false;
+        };
+
+        match base.rider_id {
+            None => self.set_entity_vehicle(entity_id, Some(target_id)),
+            Some(rider_id) if rider_id == entity_id => self.set_entity_vehicle(entity_id, None),
+            Some(_) => true,
+        }
+
+    }
+
+}
+
+
 
 /// The result of an interaction with a block in the world.
 #[derive(Debug, Clone)]
@@ -233,6 +428,12 @@ pub enum Interaction {
         /// exists.
         pos: IVec3,
     },
+    /// A chest minecart has been interacted, the front-end should interpret this and
+    /// open the chest window.
+    ChestMinecart {
+        /// Id of the chest minecart entity to connect to, it has been checked to exists.
+        id: u32,
+    },
 }
 
 impl From<bool> for Interaction {
@@ -241,3 +442,191 @@ impl From<bool> for Interaction {
         if value { Self::Handled } else { Self::None }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::entity::{Cow, Pig, Sheep, Human};
+    use crate::world::Dimension;
+    use crate::chunk::Chunk;
+
+    use super::*;
+
+    #[test]
+    fn dye_sheep() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let id = world.spawn_entity(Sheep::new_default(DVec3::new(8.5, 64.0, 8.5)));
+
+        // Red dye has damage 1 and gives red wool color 14.
+        let mut stacks = [ItemStack::new_sized(item::DYE, 1, 2)];
+        let mut inv = InventoryHandle::new(&mut stacks);
+
+        assert!(matches!(world.interact_entity(&mut inv, 0, 0, id), Interaction::Handled));
+        let Some(Entity(_, BaseKind::Living(_, LivingKind::Sheep(sheep)))) = world.get_entity(id) else { panic!() };
+        assert_eq!(sheep.color, 14);
+        assert_eq!(inv.get(0), ItemStack::new_sized(item::DYE, 1, 1));
+
+        // Already red, the dye is not consumed.
+        assert!(matches!(world.interact_entity(&mut inv, 0, 0, id), Interaction::None));
+        assert_eq!(inv.get(0), ItemStack::new_sized(item::DYE, 1, 1));
+
+    }
+
+    #[test]
+    fn dye_sheep_lapis() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let id = world.spawn_entity(Sheep::new_default(DVec3::new(8.5, 64.0, 8.5)));
+
+        // Lapis lazuli is the blue dye, with damage 4, blue wool has metadata 11.
+        let mut stacks = [ItemStack::new_single(item::DYE, 4)];
+        let mut inv = InventoryHandle::new(&mut stacks);
+
+        assert!(matches!(world.interact_entity(&mut inv, 0, 0, id), Interaction::Handled));
+        let Some(Entity(_, BaseKind::Living(_, LivingKind::Sheep(sheep)))) = world.get_entity(id) else { panic!() };
+        assert_eq!(sheep.color, 11);
+        assert_eq!(block::wool::get_dye_from_color(sheep.color), 4);
+        assert!(inv.get(0).is_empty());
+
+    }
+
+    #[test]
+    fn milk_cow() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let id = world.spawn_entity(Cow::new_default(DVec3::new(8.5, 64.0, 8.5)));
+        world.swap_events(Some(Vec::new()));
+
+        let mut stacks = [ItemStack::new_single(item::BUCKET, 0)];
+        let mut inv = InventoryHandle::new(&mut stacks);
+
+        assert!(matches!(world.interact_entity(&mut inv, 0, 0, id), Interaction::Handled));
+        assert_eq!(inv.get(0), ItemStack::new_single(item::MILK_BUCKET, 0));
+
+        // The cow is unchanged.
+        assert!(world.swap_events(None).unwrap().is_empty());
+        assert!(matches!(world.get_entity(id), Some(Entity(_, BaseKind::Living(_, LivingKind::Cow(_))))));
+
+        // A milk bucket cannot be filled again.
+        assert!(matches!(world.interact_entity(&mut inv, 0, 0, id), Interaction::None));
+
+    }
+
+    #[test]
+    fn saddle_pig() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let player_id = world.spawn_entity(Human::new_default(DVec3::new(6.5, 64.0, 8.5)));
+        let id = world.spawn_entity(Pig::new_default(DVec3::new(8.5, 64.0, 8.5)));
+
+        let mut stacks = [ItemStack::new_single(item::SADDLE, 0)];
+        let mut inv = InventoryHandle::new(&mut stacks);
+
+        assert!(matches!(world.interact_entity(&mut inv, 0, player_id, id), Interaction::Handled));
+        let Some(Entity(base, BaseKind::Living(_, LivingKind::Pig(pig)))) = world.get_entity(id) else { panic!() };
+        assert!(pig.saddle);
+        assert_eq!(base.rider_id, None);
+        assert!(inv.get(0).is_empty());
+
+        // Once saddled, the pig can be mounted.
+        assert!(matches!(world.interact_entity(&mut inv, 0, player_id, id), Interaction::Handled));
+        let Some(Entity(base, _)) = world.get_entity(id) else { panic!() };
+        assert_eq!(base.rider_id, Some(player_id));
+        let Some(Entity(base, _)) = world.get_entity(player_id) else { panic!() };
+        assert_eq!(base.vehicle_id, Some(id));
+
+    }
+
+    #[test]
+    fn mount_minecart() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let player_id = world.spawn_entity(Human::new_default(DVec3::new(6.5, 64.0, 8.5)));
+        let other_id = world.spawn_entity(Human::new_default(DVec3::new(10.5, 64.0, 8.5)));
+        let minecart_id = world.spawn_entity(Minecart::new_default(DVec3::new(8.5, 64.35, 8.5)));
+
+        let mut stacks = [ItemStack::EMPTY];
+        let mut inv = InventoryHandle::new(&mut stacks);
+
+        world.swap_events(Some(Vec::new()));
+        assert!(matches!(world.interact_entity(&mut inv, 0, player_id, minecart_id), Interaction::Handled));
+        let events = world.swap_events(Some(Vec::new())).unwrap();
+        assert!(events.contains(&Event::Entity { id: player_id, inner: EntityEvent::Ride { vehicle_id: Some(minecart_id) } }));
+
+        // Another entity cannot mount the minecart while ridden.
+        assert!(!world.set_entity_vehicle(other_id, Some(minecart_id)));
+        assert_eq!(world.get_entity(other_id).unwrap().0.vehicle_id, None);
+
+        // The rider is moved with its vehicle.
+        world.get_entity_mut(minecart_id).unwrap().teleport(DVec3::new(4.5, 64.35, 4.5));
+        world.tick();
+        let minecart_pos = world.get_entity(minecart_id).unwrap().0.pos;
+        let Some(Entity(base, _)) = world.get_entity(player_id) else { panic!() };
+        assert_eq!(base.pos, minecart_pos - DVec3::Y * 0.8);
+        assert_eq!(base.vel, DVec3::ZERO);
+
+        // Interacting again dismounts the minecart, the rider is moved on top of it.
+        assert!(matches!(world.interact_entity(&mut inv, 0, player_id, minecart_id), Interaction::Handled));
+        let events = world.swap_events(Some(Vec::new())).unwrap();
+        assert!(events.contains(&Event::Entity { id: player_id, inner: EntityEvent::Ride { vehicle_id: None } }));
+        let Some(Entity(minecart_base, _)) = world.get_entity(minecart_id) else { panic!() };
+        assert_eq!(minecart_base.rider_id, None);
+        let minecart_top = minecart_base.bb.max.y;
+        let Some(Entity(base, _)) = world.get_entity(player_id) else { panic!() };
+        assert_eq!(base.vehicle_id, None);
+        assert_eq!(base.pos.y, minecart_top);
+
+        // The rider stops riding when its vehicle is removed.
+        assert!(world.set_entity_vehicle(other_id, Some(minecart_id)));
+        world.remove_entity(minecart_id, "test");
+        world.swap_events(Some(Vec::new()));
+        world.tick();
+        assert_eq!(world.get_entity(other_id).unwrap().0.vehicle_id, None);
+        let events = world.swap_events(None).unwrap();
+        assert!(events.contains(&Event::Entity { id: other_id, inner: EntityEvent::Ride { vehicle_id: None } }));
+
+    }
+
+    #[test]
+    fn interact_minecarts() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let player_id = world.spawn_entity(Human::new_default(DVec3::new(6.5, 64.0, 8.5)));
+        let chest_id = world.spawn_entity(Minecart::new_with(|base, minecart| {
+            base.pos = DVec3::new(8.5, 64.0, 4.5);
+            minecart.kind = MinecartKind::Chest { inv: Box::default() };
+        }));
+        let furnace_id = world.spawn_entity(Minecart::new_with(|base, minecart| {
+            base.pos = DVec3::new(8.5, 64.0, 8.5);
+            minecart.kind = MinecartKind::Furnace { push_x: 0.0, push_z: 0.0, fuel: 0 };
+        }));
+
+        let mut stacks = [ItemStack::new_sized(item::COAL, 0, 2)];
+        let mut inv = InventoryHandle::new(&mut stacks);
+
+        assert!(matches!(world.interact_entity(&mut inv, 0, player_id, chest_id), Interaction::ChestMinecart { id } if id == chest_id));
+        assert_eq!(inv.get(0), ItemStack::new_sized(item::COAL, 0, 2));
+
+        // Coal fuels the furnace minecart and it is pushed away from the player.
+        assert!(matches!(world.interact_entity(&mut inv, 0, player_id, furnace_id), Interaction::Handled));
+        assert_eq!(inv.get(0), ItemStack::new_single(item::COAL, 0));
+        let Some(Entity(_, BaseKind::Minecart(Minecart { kind: MinecartKind::Furnace { push_x, push_z, fuel }, .. }))) = world.get_entity(furnace_id) else { panic!() };
+        assert_eq!((*push_x, *push_z, *fuel), (2.0, 0.0, 1200));
+
+    }
+
+}
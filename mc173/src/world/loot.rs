@@ -1,22 +1,41 @@
 //! Looting functions to spawn items in a world, also contains the loots for each block.
 
-use std::ops::{Mul, Sub};
+use std::ops::{Add, Mul, Sub};
 
 use glam::{IVec3, DVec3};
 
-use crate::entity::Item;
+use crate::block_entity::BlockEntity;
+use crate::entity::{Item, Base, LivingKind, common};
 use crate::item::ItemStack;
 use crate::{block, item};
 
 use super::World;
 
 
+/// Default chance for some living entities to drop a rare item on death, rare loots 
+/// are disabled by default because the Notchian server has none.
+pub const DEFAULT_RARE_LOOT_CHANCE: f32 = 0.0;
+
+
 /// Methods related to loot spawning in the world and block loot randomization.
 impl World {
 
+    /// Get the chance for some living entities to drop a rare item on death.
+    pub fn get_rare_loot_chance(&self) -> f32 {
+        self.rare_loot_chance
+    }
+
+    /// Set the chance for some living entities to drop a rare item on death, a zero 
+    /// chance disables rare loots.
+    pub fn set_rare_loot_chance(&mut self, chance: f32) {
+        self.rare_loot_chance = chance;
+    }
+
     /// Spawn item entity in the world containing the given stack. The velocity of the 
     /// spawned item stack is random and the initial position depends on the given spread.
     /// This item entity will be impossible to pickup for 10 ticks.
+    /// 
+    /// REF: Block::dropBlockAsItem_do
     pub fn spawn_loot(&mut self, mut pos: DVec3, stack: ItemStack, spread: f32) {
         
         if spread != 0.0 {
@@ -26,20 +45,138 @@ impl World {
                 .sub(spread as f64 * 0.5);
         }
 
-        let entity = Item::new_with(|base, item| {
-            base.persistent = true;
-            base.pos = pos;
-            base.vel.x = self.rand.next_double() * 0.2 - 0.1;
-            base.vel.y = 0.2;
-            base.vel.z = self.rand.next_double() * 0.2 - 0.1;
-            item.stack = stack;
-            item.frozen_time = 10;
-        });
+        common::spawn_item_with_spread(self, pos, stack);
+
+    }
+
+    /// Spawn item entities in the world for every stack stored in the given block 
+    /// entity, this is typically called when a container block is removed. Each stack
+    /// is scattered around the block in randomly-sized piles.
+    pub fn spawn_block_entity_loot(&mut self, pos: IVec3, block_entity: &BlockEntity) {
+        let stacks: &[ItemStack] = match block_entity {
+            BlockEntity::Chest(chest) => &chest.inv[..],
+            BlockEntity::Dispenser(dispenser) => &dispenser.inv[..],
+            BlockEntity::Furnace(furnace) => 
+                &[furnace.input_stack, furnace.fuel_stack, furnace.output_stack],
+            _ => &[],
+        };
+        for &stack in stacks {
+            if !stack.is_empty() {
+                self.spawn_scattered_loot(pos.as_dvec3(), stack);
+            }
+        }
+    }
+
+    /// Spawn the given stack at a random position in the unit cube starting at the given
+    /// position, split in random piles of 10 to 30 items. This is used when containers 
+    /// are destroyed.
+    /// 
+    /// REF: BlockChest::onBlockRemoval, EntityMinecart::attackEntityFrom
+    pub fn spawn_scattered_loot(&mut self, mut pos: DVec3, mut stack: ItemStack) {
+
+        pos += self.rand.next_float_vec().mul(0.8).add(0.1).as_dvec3();
+
+        while stack.size != 0 {
+
+            let size = (self.rand.next_int_bounded(21) as u16 + 10).min(stack.size);
+            stack.size -= size;
+
+            let entity = Item::new_with(|base, item| {
+                base.persistent = true;
+                base.pos = pos;
+                base.vel = self.rand.next_gaussian_vec() * 0.05;
+                base.vel.y += 0.2;
+                item.stack = stack.with_size(size);
+            });
+
+            self.spawn_entity(entity);
+
+        }
+
+    }
+
+    /// Spawn item entities in the world depending on the loot of the given living entity
+    /// kind, this is typically called when a living entity dies. The entity's random 
+    /// number generator is used to randomize the loot count.
+    pub fn spawn_living_loot(&mut self, base: &mut Base, living_kind: &LivingKind) {
+
+        self.spawn_living_rare_loot(base, living_kind);
+
+        let stack = match living_kind {
+            LivingKind::Chicken(_) => 
+                ItemStack::new_single(item::FEATHER, 0),
+            LivingKind::Cow(_) => 
+                ItemStack::new_single(item::LEATHER, 0),
+            LivingKind::Creeper(_) => 
+                ItemStack::new_single(item::GUNPOWDER, 0),
+            LivingKind::Ghast(_) => 
+                ItemStack::new_single(item::GUNPOWDER, 0),
+            LivingKind::Pig(_) => {
+                if base.fire_time == 0 {
+                    ItemStack::new_single(item::RAW_PORKCHOP, 0)
+                } else {
+                    ItemStack::new_single(item::COOKED_PORKCHOP, 0)
+                }
+            }
+            LivingKind::PigZombie(_) => 
+                ItemStack::new_single(item::COOKED_PORKCHOP, 0),
+            LivingKind::Sheep(sheep) if !sheep.sheared => 
+                ItemStack::new_block(block::WOOL, sheep.color),
+            LivingKind::Skeleton(_) => {
+                let arrow_count = base.rand.next_int_bounded(3) as usize;
+                self.spawn_many_loot(base.pos, ItemStack::new_single(item::ARROW, 0), arrow_count);
+                let bone_count = base.rand.next_int_bounded(3) as usize;
+                self.spawn_many_loot(base.pos, ItemStack::new_single(item::BONE, 0), bone_count);
+                return;
+            }
+            LivingKind::Slime(slime) if slime.size == 0 => 
+                ItemStack::new_single(item::SLIMEBALL, 0),
+            LivingKind::Spider(_) => 
+                ItemStack::new_single(item::STRING, 0),
+            LivingKind::Squid(_) => 
+                ItemStack::new_single(item::DYE, 0),
+            LivingKind::Zombie(_) => 
+                ItemStack::new_single(item::FEATHER, 0),
+            _ => return
+        };
+
+        let count = match living_kind {
+            LivingKind::Squid(_) => 1 + base.rand.next_int_bounded(3) as usize,
+            _ => base.rand.next_int_bounded(3) as usize,
+        };
+
+        self.spawn_many_loot(base.pos, stack, count);
+
+    }
+
+    /// Spawn a rare item entity depending on the living entity kind, with the world's 
+    /// rare loot chance. The entity's random number generator is not used if rare loots
+    /// are disabled, so the Notchian loot sequence is preserved.
+    fn spawn_living_rare_loot(&mut self, base: &mut Base, living_kind: &LivingKind) {
+
+        if self.rare_loot_chance <= 0.0 {
+            return;
+        }
+
+        let stack = match living_kind {
+            LivingKind::Skeleton(_) => ItemStack::new_single(item::BOW, 0),
+            LivingKind::Zombie(_) => ItemStack::new_single(item::IRON_INGOT, 0),
+            _ => return
+        };
 
-        self.spawn_entity(entity);
+        if base.rand.next_float() < self.rare_loot_chance {
+            self.spawn_loot(base.pos, stack, 0.0);
+        }
 
     }
 
+    /// Spawn the given number of item entities containing the given stack.
+    fn spawn_many_loot(&mut self, pos: DVec3, stack: ItemStack, count: usize) {
+        for _ in 0..count {
+            self.spawn_loot(pos, stack, 0.0);
+        }
+    }
+
     /// Spawn item entities in the world depending on the loot of the given block id and
     /// metadata. Each block has a different random try count and loots, the given chance
     /// if looting is checked on each try, typically used for explosions.
@@ -179,3 +316,120 @@ impl World {
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::test::new_floor_world;
+    use crate::entity::{Creeper, Skeleton, Zombie, Entity, BaseKind};
+    use crate::rand::JavaRandom;
+    use crate::world::Dimension;
+    use crate::chunk::Chunk;
+
+    use super::*;
+
+    #[test]
+    fn creeper_loot() {
+
+        // These seeds are chosen to cover every possible gunpowder count.
+        for (seed, expected_count) in [(0, 0), (2, 1), (3, 2)] {
+
+            // The count is the first value drawn from the creeper's random on death.
+            assert_eq!(JavaRandom::new(seed).next_int_bounded(3), expected_count);
+
+            let mut world = new_floor_world(block::STONE);
+
+            let id = world.spawn_entity(Creeper::new_with(|base, _, _| {
+                base.pos = DVec3::new(8.5, 64.0, 8.5);
+                base.rand = JavaRandom::new(seed);
+            }));
+
+            world.damage_entity(id, 100, None);
+            world.tick();
+
+            let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(id) else { panic!() };
+            assert_eq!(living.health, 0);
+
+            let mut count = 0;
+            for (_, entity) in world.iter_entities() {
+                if let Entity(_, BaseKind::Item(item)) = entity {
+                    assert_eq!(item.stack, ItemStack::new_single(item::GUNPOWDER, 0));
+                    count += 1;
+                }
+            }
+
+            assert_eq!(count, expected_count);
+
+        }
+
+    }
+
+    #[test]
+    fn rare_loot() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+        world.set_rare_loot_chance(0.1);
+
+        let Entity(mut base, BaseKind::Living(_, living_kind)) = *Skeleton::new_with(|base, _, _| {
+            base.pos = DVec3::new(8.5, 64.0, 8.5);
+            base.rand = JavaRandom::new(0);
+        }) else { panic!() };
+
+        let Entity(mut zombie_base, BaseKind::Living(_, zombie_kind)) = *Zombie::new_with(|base, _, _| {
+            base.pos = DVec3::new(8.5, 64.0, 8.5);
+            base.rand = JavaRandom::new(1);
+        }) else { panic!() };
+
+        for _ in 0..2000 {
+            world.spawn_living_loot(&mut base, &living_kind);
+            world.spawn_living_loot(&mut zombie_base, &zombie_kind);
+        }
+
+        let count = |id: u16| world.iter_entities()
+            .filter(|(_, entity)| matches!(entity, Entity(_, BaseKind::Item(item)) if item.stack.id == id))
+            .count();
+
+        // Expecting about 200 bows and 200 iron ingots.
+        let bow_count = count(item::BOW);
+        let iron_count = count(item::IRON_INGOT);
+        assert!((150..250).contains(&bow_count), "unexpected bow count: {bow_count}");
+        assert!((150..250).contains(&iron_count), "unexpected iron ingot count: {iron_count}");
+
+        // Rare loots are disabled by default and don't use the entity's random.
+        world.set_rare_loot_chance(DEFAULT_RARE_LOOT_CHANCE);
+        let seed = base.rand.get_seed();
+        world.spawn_living_rare_loot(&mut base, &living_kind);
+        assert_eq!(base.rand.get_seed(), seed);
+
+    }
+
+    #[test]
+    fn item_spread() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+        *world.get_rand_mut() = JavaRandom::new(1234);
+
+        let stack = ItemStack::new_block(block::DIRT, 0);
+        let id = common::spawn_item_with_spread(&mut world, DVec3::new(8.5, 64.5, 8.5), stack);
+
+        // The velocity is made of the next two random doubles, rounded to float.
+        let mut rand = JavaRandom::new(1234);
+        let vel_x = (rand.next_double() * 0.2 - 0.1) as f32 as f64;
+        let vel_z = (rand.next_double() * 0.2 - 0.1) as f32 as f64;
+
+        let Some(Entity(base, BaseKind::Item(item))) = world.get_entity(id) else { panic!() };
+        assert_eq!(item.stack, stack);
+        assert_eq!(item.frozen_time, 10);
+        assert_eq!(base.pos, DVec3::new(8.5, 64.5, 8.5));
+        assert_eq!(base.look.x, 0.0);
+        assert_eq!(base.vel, DVec3::new(vel_x, 0.2, vel_z));
+
+        // No other random value has been drawn from the world.
+        assert_eq!(world.get_rand_mut().next_int(), rand.next_int());
+
+    }
+
+}
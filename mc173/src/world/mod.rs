@@ -8,6 +8,8 @@ use std::iter::FusedIterator;
 use std::cmp::Ordering;
 use std::hash::Hash;
 use std::cell::Cell;
+use std::time::Instant;
+use std::fmt;
 use std::sync::Arc;
 use std::slice;
 use std::mem;
@@ -17,17 +19,19 @@ use indexmap::IndexMap;
 
 use tracing::trace;
 
-use crate::entity::{Entity, EntityCategory, EntityKind, LightningBolt};
+use crate::entity::{BaseKind, Entity, EntityCategory, EntityKind, Hurt, LightningBolt, Skeleton};
 use crate::block_entity::BlockEntity;
 use crate::biome::Biome;
 use crate::chunk::{Chunk,
     calc_chunk_pos, calc_chunk_pos_unchecked, calc_entity_chunk_pos,
-    CHUNK_HEIGHT, CHUNK_WIDTH};
+    CHUNK_HEIGHT, CHUNK_WIDTH, CHUNK_SECTION_HEIGHT};
 
 use crate::geom::{BoundingBox, Face};
 use crate::rand::JavaRandom;
 use crate::item::ItemStack;
-use crate::block;
+use crate::util::FadingAverage;
+use crate::source::{ChunkSource, ChunkSourceError};
+use crate::block::{self, SoundGroup};
 
 
 // Following modules are order by order of importance, last modules depends on first ones.
@@ -45,6 +49,25 @@ pub mod explode;
 pub mod path;
 
 
+/// Internal function to find the nearest entity from the given iterator, used by the
+/// various nearest entity queries of the world.
+fn nearest_entity<'a>(
+    iter: impl Iterator<Item = (u32, &'a Entity)>, 
+    pos: DVec3, 
+    max_dist: f64, 
+    mut predicate: impl FnMut(u32, &Entity) -> bool
+) -> Option<(u32, f64)> {
+    let max_dist_sq = max_dist.powi(2);
+    iter.filter(|&(id, entity)| predicate(id, entity))
+        .map(|(id, entity)| (id, entity.0.pos.distance_squared(pos)))
+        .filter(|&(_, dist_sq)| dist_sq <= max_dist_sq)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+/// Minimum number of removed entity ids to keep before starting to recycle them.
+const ENTITY_ID_RECYCLE_THRESHOLD: usize = 1024;
+
+
 // Various thread local vectors that are used to avoid frequent reallocation of 
 // temporary vector used in the logic code.
 thread_local! {
@@ -126,6 +149,9 @@ pub struct World {
     /// borrowing issues, by temporarily taking ownership of events, the caller can get
     /// a mutable reference to that world at the same time.
     events: Option<Vec<Event>>,
+    /// An optional listener called synchronously on each pushed event, in addition to
+    /// the events queue. This listener is not cloned with the world.
+    event_listener: EventListener,
     /// The dimension
     dimension: Dimension,
     /// The world time, increasing on each tick. This is used for day/night cycle but 
@@ -139,8 +165,11 @@ pub struct World {
     /// to a world chunk.
     chunks: HashMap<(i32, i32), ChunkComponent>,
     /// Total entities count spawned since the world is running. Also used to give 
-    /// entities a unique id.
+    /// entities a unique id when no id can be recycled.
     entities_count: u32,
+    /// Queue of ids of removed entities, in order of removal. These ids are recycled 
+    /// when enough of them are queued, see [`ENTITY_ID_RECYCLE_THRESHOLD`].
+    entities_free_ids: VecDeque<u32>,
     /// The internal list of all loaded entities.
     entities: TickVec<EntityComponent>,
     /// Entities' index mapping from their unique id.
@@ -160,6 +189,9 @@ pub struct World {
     block_ticks_states: HashSet<BlockTickState>,
     /// Queue of pending light updates to be processed.
     light_updates: VecDeque<LightUpdate>,
+    /// Recent redstone torches turning off, with the time of the toggle. This is used to
+    /// burn out torches that are toggled too quickly. Older toggles are at the front.
+    redstone_torch_toggles: VecDeque<(IVec3, u64)>,
     /// This is the wrapping seed used by random ticks to compute random block positions.
     random_ticks_seed: i32,
     /// The current weather in that world, note that the Notchian server do not work like
@@ -168,9 +200,23 @@ pub struct World {
     weather: Weather,
     /// Next time when the weather should be recomputed.
     weather_next_time: u64,
+    /// The rain strength, smoothly following the current weather, in range 0..=1.
+    rain_strength: f32,
+    /// The thunder strength, smoothly following the current weather, in range 0..=1.
+    thunder_strength: f32,
     /// The current sky light level, depending on the current time. This value is used
     /// when subtracted from a chunk sky light level.
     sky_light_subtracted: u8,
+    /// When enabled, this contains the timings of each phase of the world tick.
+    metrics: Option<TickMetrics>,
+    /// The chance for some living entities to drop a rare item on death.
+    rare_loot_chance: f32,
+    /// True when step sound events are pushed for living entities walking on blocks.
+    step_sounds: bool,
+    /// The spawn position of this world, where players are respawned.
+    spawn_pos: IVec3,
+    /// The difficulty of the world, affecting hostile mobs.
+    difficulty: Difficulty,
 }
 
 /// Core methods for worlds.
@@ -181,11 +227,13 @@ impl World {
     pub fn new(dimension: Dimension) -> Self {
         Self {
             events: None,
+            event_listener: EventListener(None),
             dimension,
             time: 0,
             rand: JavaRandom::new_seeded(),
             chunks: HashMap::new(),
             entities_count: 0,
+            entities_free_ids: VecDeque::new(),
             entities: TickVec::new(),
             entities_id_map: HashMap::new(),
             player_entities_map: IndexMap::new(),
@@ -195,10 +243,18 @@ impl World {
             block_ticks: BTreeSet::new(),
             block_ticks_states: HashSet::new(),
             light_updates: VecDeque::new(),
+            redstone_torch_toggles: VecDeque::new(),
             random_ticks_seed: JavaRandom::new_seeded().next_int(),
             weather: Weather::Clear,
             weather_next_time: 0,
+            rain_strength: 0.0,
+            thunder_strength: 0.0,
             sky_light_subtracted: 0,
+            metrics: None,
+            rare_loot_chance: loot::DEFAULT_RARE_LOOT_CHANCE,
+            step_sounds: false,
+            spawn_pos: IVec3::new(0, 64, 0),
+            difficulty: Difficulty::Normal,
         }
     }
 
@@ -220,16 +276,84 @@ impl World {
         self.events.is_some()
     }
 
+    /// Set or remove the event listener of this world, returning the previous one. The
+    /// listener is called synchronously by [`push_event`] for every event, whether or 
+    /// not the events queue is enabled, and before the event is pushed to the queue.
+    /// 
+    /// Because the listener is called while the world is mutably borrowed, it cannot 
+    /// access the world, it should only record or forward the events it receives. Note
+    /// that the listener is not cloned when the world is cloned, the clone has none.
+    /// 
+    /// [`push_event`]: Self::push_event
+    pub fn set_event_listener(&mut self, listener: Option<Box<EventListenerFn>>) -> Option<Box<EventListenerFn>> {
+        mem::replace(&mut self.event_listener.0, listener)
+    }
+
+    /// Return true if this world has an event listener.
+    pub fn has_event_listener(&self) -> bool {
+        self.event_listener.0.is_some()
+    }
+
     /// Push an event in this world. This only actually push the event if events are 
     /// enabled. Events queue can be swapped using [`swap_events`](Self::swap_events) 
-    /// method.
+    /// method. The event listener, if any, is also called with the event, see 
+    /// [`set_event_listener`](Self::set_event_listener).
     #[inline]
     pub fn push_event(&mut self, event: Event) {
+        if let Some(listener) = &mut self.event_listener.0 {
+            listener(&event);
+        }
         if let Some(events) = &mut self.events {
             events.push(event);
         }
     }
 
+    /// This function can be used to swap in new tick metrics and return the previous
+    /// ones if relevant. Giving *None* metrics disable the recording of tick timings, 
+    /// metrics are disabled by default.
+    pub fn swap_metrics(&mut self, metrics: Option<TickMetrics>) -> Option<TickMetrics> {
+        mem::replace(&mut self.metrics, metrics)
+    }
+
+    /// Take the tick metrics recorded so far, if enabled, and reset them so that the
+    /// recording continues from zero. Metrics stay enabled, use 
+    /// [`swap_metrics`](Self::swap_metrics) to disable them.
+    pub fn take_metrics(&mut self) -> Option<TickMetrics> {
+        self.metrics.as_mut().map(mem::take)
+    }
+
+    /// Capture a snapshot of the current state of this world, this is intended for 
+    /// debugging and reproducible bug reports, see [`WorldDump`].
+    pub fn dump_state(&self) -> WorldDump {
+
+        let mut chunks = self.chunks.iter()
+            .filter(|(_, comp)| comp.data.is_some())
+            .map(|(&pos, _)| pos)
+            .collect::<Vec<_>>();
+        chunks.sort_unstable();
+
+        let mut entities = Vec::<(EntityKind, usize)>::new();
+        for comp in self.entities.iter() {
+            match entities.iter_mut().find(|(kind, _)| *kind == comp.kind) {
+                Some((_, count)) => *count += 1,
+                None => entities.push((comp.kind, 1)),
+            }
+        }
+        entities.sort_unstable_by_key(|&(kind, _)| kind as u8);
+
+        WorldDump {
+            dimension: self.dimension,
+            time: self.time,
+            weather: self.weather,
+            chunks,
+            entities,
+            block_entities: self.block_entities.len(),
+            block_ticks: self.block_ticks.len(),
+            light_updates: self.light_updates.len(),
+        }
+
+    }
+
     /// Get the dimension of this world, this is basically only for sky color on client
     /// and also for celestial angle on the server side for sky light calculation. This
     /// has not direct relation with the actual world generation that is providing this
@@ -243,6 +367,81 @@ impl World {
         self.time
     }
 
+    /// Set the world time, in ticks. Note that scheduled block ticks are relative to the
+    /// world time, so they will all be executed at once if time is moved forward.
+    pub fn set_time(&mut self, time: u64) {
+        self.time = time;
+    }
+
+    /// Get the spawn position of this world, this is the block position where players
+    /// are spawned and respawned after death.
+    pub fn get_spawn_pos(&self) -> IVec3 {
+        self.spawn_pos
+    }
+
+    /// Set the spawn position of this world.
+    pub fn set_spawn_pos(&mut self, pos: IVec3) {
+        self.spawn_pos = pos;
+    }
+
+    /// Get the difficulty of this world.
+    pub fn get_difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// Set the difficulty of this world, when set to peaceful the hostile mobs will be
+    /// removed on their next tick.
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+
+    /// Return true if step sound events are pushed when living entities walk.
+    #[inline]
+    pub fn has_step_sounds(&self) -> bool {
+        self.step_sounds
+    }
+
+    /// Enable or disable step sound events, these are pushed for every few blocks walked
+    /// by every living entity, so they are disabled by default because the Notchian 
+    /// client already plays them by itself.
+    pub fn set_step_sounds(&mut self, enabled: bool) {
+        self.step_sounds = enabled;
+    }
+
+    /// Get the celestial angle of the sun depending on the world time, this angle is 
+    /// in range 0.0 to 1.0, where 0.0 is noon and 0.5 is midnight. The angle is always
+    /// 0.5 in the nether.
+    pub fn get_celestial_angle(&self) -> f32 {
+        Self::calc_celestial_angle(self.time, self.dimension)
+    }
+
+    /// Internal function to calculate the celestial angle at the given time.
+    /// 
+    /// REF: WorldProvider::calculateCelestialAngle
+    fn calc_celestial_angle(time: u64, dimension: Dimension) -> f32 {
+
+        let time_wrapped = time % 24000;
+        let mut half_turn = (time_wrapped as f32 + 1.0) / 24000.0 - 0.25;
+
+        if half_turn < 0.0 {
+            half_turn += 1.0;
+        } else if half_turn > 1.0 {
+            half_turn -= 1.0;
+        }
+
+        match dimension {
+            Dimension::Nether => 0.5,
+            _ => half_turn + (1.0 - ((half_turn * std::f32::consts::PI).cos() + 1.0) / 2.0 - half_turn) / 3.0,
+        }
+
+    }
+
+    /// Return true if it is currently day in the world, this depends on the world time
+    /// but also on the weather, it is never day when thundering.
+    pub fn is_day(&self) -> bool {
+        Self::compute_sky_light_subtracted(self.time, self.weather, self.dimension) < 4
+    }
+
     /// Get a mutable access to this world's random number generator.
     pub fn get_rand_mut(&mut self) -> &mut JavaRandom {
         &mut self.rand
@@ -268,6 +467,25 @@ impl World {
             self.set_block_entity_inner(pos, block_entity);
         }
 
+        for tick in snapshot.block_ticks {
+            debug_assert_eq!(calc_chunk_pos_unchecked(tick.pos), (snapshot.cx, snapshot.cz), "incoherent block tick in chunk snapshot");
+            self.schedule_block_tick(tick.pos, tick.id, tick.delay);
+        }
+
+    }
+
+    /// Load the chunk at the given coordinates from the given source and insert it into
+    /// this world, see [`insert_chunk_snapshot`](Self::insert_chunk_snapshot). False is
+    /// returned if the source doesn't support loading this chunk.
+    pub fn load_chunk_from_source(&mut self, source: &mut impl ChunkSource, cx: i32, cz: i32) -> Result<bool, ChunkSourceError> {
+        match source.load(cx, cz) {
+            Ok(snapshot) => {
+                self.insert_chunk_snapshot(snapshot);
+                Ok(true)
+            }
+            Err(ChunkSourceError::Unsupported) => Ok(false),
+            Err(e) => Err(e),
+        }
     }
 
     /// Create a snapshot of a chunk's content, this only works if chunk data is existing.
@@ -288,9 +506,25 @@ impl World {
                 .filter_map(|(&pos, &index)| self.block_entities.get(index).unwrap().inner.clone()
                     .map(|e| (pos, e)))
                 .collect(),
+            block_ticks: self.block_ticks.iter()
+                .filter(|tick| calc_chunk_pos_unchecked(tick.state.pos) == (cx, cz))
+                .map(|tick| tick.to_scheduled(self.time))
+                .collect(),
         })
     }
 
+    /// Create a snapshot of a chunk's content, just like [`take_chunk_snapshot`], but 
+    /// also clear the dirty flag of the chunk, this should be used when the snapshot is 
+    /// taken in order to be saved, see [`is_chunk_dirty`].
+    /// 
+    /// [`take_chunk_snapshot`]: Self::take_chunk_snapshot
+    /// [`is_chunk_dirty`]: Self::is_chunk_dirty
+    pub fn save_chunk_snapshot(&mut self, cx: i32, cz: i32) -> Option<ChunkSnapshot> {
+        let snapshot = self.take_chunk_snapshot(cx, cz)?;
+        self.chunks.get_mut(&(cx, cz)).unwrap().dirty = false;
+        Some(snapshot)
+    }
+
     /// Remove a chunk at given chunk coordinates and return a snapshot of it. If there
     /// is no chunk at the coordinates but entities or block entities are present, None
     /// is returned but entities and block entities are removed from the world.
@@ -308,6 +542,19 @@ impl World {
                 .map(|e| (pos, e)))
             .collect();
         
+        // Scheduled ticks are also removed, so they are not duplicated if the snapshot
+        // is inserted again.
+        let mut block_ticks = Vec::new();
+        self.block_ticks.retain(|tick| {
+            if calc_chunk_pos_unchecked(tick.state.pos) == (cx, cz) {
+                self.block_ticks_states.remove(&tick.state);
+                block_ticks.push(tick.to_scheduled(self.time));
+                false
+            } else {
+                true
+            }
+        });
+        
         if let Some(chunk) = chunk_comp.data {
 
             ret = Some(ChunkSnapshot { 
@@ -316,6 +563,7 @@ impl World {
                 chunk,
                 entities,
                 block_entities,
+                block_ticks,
             });
 
             self.push_event(Event::Chunk { cx, cz, inner: ChunkEvent::Remove });
@@ -330,6 +578,32 @@ impl World {
     //        CHUNKS       //
     // =================== //
 
+    /// Return true if the chunk at given coordinates has been modified since it was last
+    /// saved with [`save_chunk_snapshot`](Self::save_chunk_snapshot), this can be used
+    /// to avoid saving unchanged chunks. False is returned if the chunk is not existing.
+    pub fn is_chunk_dirty(&self, cx: i32, cz: i32) -> bool {
+        self.chunks.get(&(cx, cz)).map(|comp| comp.dirty).unwrap_or(false)
+    }
+
+    /// Iterate over the coordinates of all chunks that are dirty, i.e. that have been 
+    /// modified since they were last saved, see [`is_chunk_dirty`](Self::is_chunk_dirty).
+    /// The iteration order is unspecified.
+    pub fn iter_dirty_chunks(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.chunks.iter()
+            .filter(|(_, comp)| comp.dirty)
+            .map(|(&pos, _)| pos)
+    }
+
+    /// Mark the chunk at given coordinates dirty and push the associated event, this is
+    /// done internally when the chunk is modified but can also be used to force a chunk
+    /// to be saved, for example when it has just been generated.
+    pub fn set_chunk_dirty(&mut self, cx: i32, cz: i32) {
+        if let Some(chunk_comp) = self.chunks.get_mut(&(cx, cz)) {
+            chunk_comp.dirty = true;
+        }
+        self.push_event(Event::Chunk { cx, cz, inner: ChunkEvent::Dirty });
+    }
+
     /// Raw function to add a chunk to the world at the given coordinates. Note that the
     /// given chunk only contains block and light data, so no entity or block entity will
     /// be added by this function.
@@ -432,7 +706,7 @@ impl World {
                 } 
             });
 
-            self.push_event(Event::Chunk { cx, cz, inner: ChunkEvent::Dirty });
+            self.set_chunk_dirty(cx, cz);
 
         }
 
@@ -440,7 +714,43 @@ impl World {
 
     }
 
-    /// Same as the [`set_block`] method, but the previous block and new block are 
+    /// Set only the metadata of the block at given position in the world, if the chunk
+    /// is not loaded, none is returned, but if it is existing the previous metadata is
+    /// returned. This is a fast path of [`set_block`] for blocks that only change their
+    /// metadata, the height map is not recomputed and no light update is scheduled
+    /// because light opacity and emission only depend on the block id. A block change
+    /// event is still pushed if the metadata is actually changed.
+    ///
+    /// [`set_block`]: Self::set_block
+    pub fn set_block_metadata(&mut self, pos: IVec3, metadata: u8) -> Option<u8> {
+
+        let (cx, cz) = calc_chunk_pos(pos)?;
+        let chunk = self.get_chunk_mut(cx, cz)?;
+        let (id, prev_metadata) = chunk.get_block(pos);
+
+        if metadata != prev_metadata {
+
+            chunk.set_metadata(pos, metadata);
+
+            self.push_event(Event::Block {
+                pos,
+                inner: BlockEvent::Set {
+                    id,
+                    metadata,
+                    prev_id: id,
+                    prev_metadata,
+                }
+            });
+
+            self.set_chunk_dirty(cx, cz);
+
+        }
+
+        Some(prev_metadata)
+
+    }
+
+    /// Same as the [`set_block`] method, but the previous block and new block are
     /// notified of that removal and addition.
     /// 
     /// [`set_block`]: Self::set_block
@@ -460,6 +770,21 @@ impl World {
         Some((prev_id, prev_metadata))
     }
 
+    /// Same as the [`set_block_notify`] method, but additionally set the block entity at
+    /// that position before notifying blocks around, so the block and its block entity 
+    /// are always observed together. The previous block entity, if any, is removed by
+    /// the block change notification and the block entity set event is always pushed 
+    /// after the block set event.
+    /// 
+    /// [`set_block_notify`]: Self::set_block_notify
+    pub fn set_block_entity_notify(&mut self, pos: IVec3, id: u8, metadata: u8, block_entity: impl Into<Box<BlockEntity>>) -> Option<(u8, u8)> {
+        let (prev_id, prev_metadata) = self.set_block(pos, id, metadata)?;
+        self.notify_change_unchecked(pos, prev_id, prev_metadata, id, metadata);
+        self.set_block_entity(pos, block_entity);
+        self.notify_blocks_around(pos, id);
+        Some((prev_id, prev_metadata))
+    }
+
     /// Get block and metadata at given position in the world, if the chunk is not
     /// loaded, none is returned.
     pub fn get_block(&self, pos: IVec3) -> Option<(u8, u8)> {
@@ -468,6 +793,92 @@ impl World {
         Some(chunk.get_block(pos))
     }
 
+    /// Find the closest block from the given center and within the given radius that
+    /// matches the given predicate, the predicate is given the position, id and metadata
+    /// of the block. Blocks in unloaded chunks are ignored. The search is done through 
+    /// cubic shells of increasing radius, so the nearest matching blocks are checked 
+    /// first. If many blocks are at the same distance, the first found is returned.
+    pub fn find_closest_block(&self, center: IVec3, max_radius: u16, mut predicate: impl FnMut(IVec3, u8, u8) -> bool) -> Option<IVec3> {
+
+        let max_radius = max_radius as i32;
+        let mut closest: Option<(IVec3, i32)> = None;
+
+        for radius in 0..=max_radius {
+
+            for dx in -radius..=radius {
+                for dy in -radius..=radius {
+
+                    // Only the two faces of the shell are checked if not on X/Y edges.
+                    let on_edge = dx.abs() == radius || dy.abs() == radius;
+                    let dz_step = if on_edge { 1 } else { (radius * 2).max(1) as usize };
+
+                    for dz in (-radius..=radius).step_by(dz_step) {
+
+                        let delta = IVec3::new(dx, dy, dz);
+                        let dist_sq = delta.length_squared();
+                        if dist_sq > max_radius * max_radius {
+                            continue;
+                        } else if let Some((_, closest_dist_sq)) = closest {
+                            if dist_sq >= closest_dist_sq {
+                                continue;
+                            }
+                        }
+
+                        let pos = center + delta;
+                        if let Some((id, metadata)) = self.get_block(pos) {
+                            if predicate(pos, id, metadata) {
+                                closest = Some((pos, dist_sq));
+                            }
+                        }
+
+                    }
+
+                }
+            }
+
+            // Blocks in the next shells are at least one block further than this radius,
+            // so they can't be closer than the closest block already found.
+            if let Some((pos, closest_dist_sq)) = closest {
+                if closest_dist_sq <= (radius + 1) * (radius + 1) {
+                    return Some(pos);
+                }
+            }
+
+        }
+
+        closest.map(|(pos, _)| pos)
+
+    }
+
+    /// Find a safe position for a player to stand, close to the given position. A safe
+    /// position has a normal cube block below and two air blocks, so the player is not
+    /// suffocating nor in a fluid. The search is limited to a radius of 16 blocks and
+    /// only loaded chunks are checked, if a chunk source is given then missing chunks
+    /// in that radius are first loaded from it, see 
+    /// [`load_chunk_from_source`](Self::load_chunk_from_source), chunks that fail to
+    /// load are just not checked. The returned position is the one of the feet.
+    pub fn find_safe_spawn(&mut self, around: IVec3, source: Option<&mut impl ChunkSource>) -> Option<IVec3> {
+
+        if let Some(source) = source {
+            let (min_cx, min_cz) = calc_chunk_pos_unchecked(around - 16);
+            let (max_cx, max_cz) = calc_chunk_pos_unchecked(around + 16);
+            for cx in min_cx..=max_cx {
+                for cz in min_cz..=max_cz {
+                    if !self.contains_chunk(cx, cz) {
+                        let _ = self.load_chunk_from_source(source, cx, cz);
+                    }
+                }
+            }
+        }
+
+        self.find_closest_block(around - IVec3::Y, 16, |pos, id, _| {
+            block::material::is_normal_cube(id)
+                && self.is_block_air(pos + IVec3::Y)
+                && self.is_block_air(pos + IVec3::Y * 2)
+        }).map(|pos| pos + IVec3::Y)
+
+    }
+
     // =================== //
     //        HEIGHT       //
     // =================== //
@@ -481,6 +892,44 @@ impl World {
         Some(chunk.get_height(pos) as i32)
     }
 
+    /// Get the position of the highest non-air block in the given column, none is
+    /// returned if the chunk is not loaded or if the column is only made of air.
+    pub fn get_highest_block(&self, x: i32, z: i32) -> Option<IVec3> {
+        self.find_top_block(x, z, |id| id != block::AIR)
+    }
+
+    /// Get the position of the highest block with a solid material in the given column,
+    /// non-solid blocks such as tall grass, flowers or liquids are skipped. None is
+    /// returned if the chunk is not loaded or if there is no solid block in the column.
+    ///
+    /// The height map cannot be used here because it only accounts for blocks that are
+    /// blocking the sky light, so solid blocks like glass would be missed.
+    ///
+    /// REF: World::findTopSolidBlock
+    pub fn get_top_solid_block(&self, x: i32, z: i32) -> Option<IVec3> {
+        self.find_top_block(x, z, |id| block::material::get_material(id).is_solid())
+    }
+
+    /// Internal function to find the highest block in a column that matches the given
+    /// predicate on its block id, the predicate should never match air blocks.
+    fn find_top_block(&self, x: i32, z: i32, mut predicate: impl FnMut(u8) -> bool) -> Option<IVec3> {
+        let mut pos = IVec3::new(x, CHUNK_HEIGHT as i32 - 1, z);
+        let (cx, cz) = calc_chunk_pos_unchecked(pos);
+        let chunk = self.get_chunk(cx, cz)?;
+        while pos.y >= 0 {
+            // Skip entire empty sections, air is never a top block.
+            if chunk.is_section_empty(pos.y as usize / CHUNK_SECTION_HEIGHT) {
+                pos.y -= pos.y % CHUNK_SECTION_HEIGHT as i32 + 1;
+                continue;
+            }
+            if predicate(chunk.get_block(pos).0) {
+                return Some(pos);
+            }
+            pos.y -= 1;
+        }
+        None
+    }
+
     // =================== //
     //        LIGHTS       //
     // =================== //
@@ -510,6 +959,12 @@ impl World {
 
     }
 
+    /// Get the brightness at the given position, computed from the light levels and 
+    /// this world's dimension, see [`Light::brightness`].
+    pub fn get_brightness(&self, pos: IVec3) -> f32 {
+        self.get_light(pos).brightness(self.dimension)
+    }
+
     /// Schedule a light update to be processed in a future tick.
     ///  
     /// See [`tick_light`](Self::tick_light).
@@ -556,6 +1011,44 @@ impl World {
         }
     }
 
+    /// Get the rain strength, in range 0..=1, this value smoothly increases or decreases
+    /// on each tick depending on the current weather, so it is not instantly changed when
+    /// the weather changes.
+    pub fn get_rain_strength(&self) -> f32 {
+        self.rain_strength
+    }
+
+    /// Get the thunder strength, in range 0..=1, like the rain strength it smoothly 
+    /// follows the current weather, it is weighted by the rain strength.
+    /// 
+    /// REF: World::func_27166_f
+    pub fn get_thunder_strength(&self) -> f32 {
+        self.thunder_strength * self.rain_strength
+    }
+
+    /// Instantly set the rain and thunder strengths to match the current weather, this
+    /// is used when restoring a world so that its weather doesn't ramp up from zero.
+    pub fn reset_weather_strength(&mut self) {
+        let (rain, thunder) = match self.weather {
+            Weather::Clear => (0.0, 0.0),
+            Weather::Rain => (1.0, 0.0),
+            Weather::Thunder => (1.0, 1.0),
+        };
+        self.rain_strength = rain;
+        self.thunder_strength = thunder;
+    }
+
+    /// Get the time when the weather will be recomputed.
+    pub fn get_weather_next_time(&self) -> u64 {
+        self.weather_next_time
+    }
+
+    /// Set the time when the weather will be recomputed, this is mostly used when 
+    /// restoring a world from its saved level data.
+    pub fn set_weather_next_time(&mut self, time: u64) {
+        self.weather_next_time = time;
+    }
+
     /// Return true if it's raining at the given position.
     pub fn get_local_weather(&mut self, pos: IVec3) -> LocalWeather {
 
@@ -596,10 +1089,7 @@ impl World {
     #[inline(never)]
     fn spawn_entity_inner(&mut self, entity: Box<Entity>) -> u32 {
 
-        // Get the next unique entity id.
-        let id = self.entities_count;
-        self.entities_count = self.entities_count.checked_add(1)
-            .expect("entity count overflow");
+        let id = self.alloc_entity_id();
 
         let kind = entity.kind();
         trace!("spawn entity #{id} ({:?})", kind);
@@ -619,12 +1109,33 @@ impl World {
         self.entities_id_map.insert(id, entity_index);
         
         self.push_event(Event::Entity { id, inner: EntityEvent::Spawn });
-        self.push_event(Event::Chunk { cx, cz, inner: ChunkEvent::Dirty });
+        self.set_chunk_dirty(cx, cz);
 
         id
 
     }
 
+    /// Get the next unique entity id, removed entity ids are only recycled when enough
+    /// of them are queued, this ensures that a removed id is not immediately reused, so
+    /// that frontends have time to forget about the removed entity. Ids are recycled in
+    /// the order they were freed.
+    fn alloc_entity_id(&mut self) -> u32 {
+        
+        if self.entities_free_ids.len() > ENTITY_ID_RECYCLE_THRESHOLD {
+            return self.entities_free_ids.pop_front().unwrap();
+        }
+
+        match self.entities_count.checked_add(1) {
+            Some(next_count) => {
+                let id = self.entities_count;
+                self.entities_count = next_count;
+                id
+            }
+            None => self.entities_free_ids.pop_front().expect("entity id overflow"),
+        }
+
+    }
+
     /// Spawn an entity in this world, this function gives it a unique id and ensure 
     /// coherency with chunks cache.
     /// 
@@ -636,6 +1147,24 @@ impl World {
         self.spawn_entity_inner(entity.into())
     }
 
+    /// Spawn a new default entity of the given kind at the given position, with a random
+    /// look, its id is returned. If natural is true, the entity is initialized as if it 
+    /// was naturally spawned, for example sheep get a random color, but there is no 
+    /// check that the entity can actually naturally spawn there.
+    pub fn spawn_kind(&mut self, kind: EntityKind, pos: DVec3, natural: bool) -> u32 {
+
+        let mut entity = kind.new_default(pos);
+        entity.0.persistent = true;
+        entity.0.look.x = self.rand.next_float() * std::f32::consts::TAU;
+
+        if natural {
+            entity.init_natural_spawn(self);
+        }
+
+        self.spawn_entity(entity)
+
+    }
+
     /// Return true if an entity is present from its id.
     pub fn contains_entity(&self, id: u32) -> bool {
         self.entities_id_map.contains_key(&id)
@@ -663,6 +1192,42 @@ impl World {
         self.entities.get_mut(index).unwrap().inner.as_deref_mut()
     }
 
+    /// Damage an entity given its id, optionally giving the id of the entity at the 
+    /// origin of the damage. The damage is actually applied on the next tick of the 
+    /// entity, this respects the hurt cooldown, applies knock back away from the origin
+    /// entity and handles the death of living entities. This returns false if the entity
+    /// is not existing or is the current entity being updated.
+    pub fn damage_entity(&mut self, id: u32, damage: u16, origin_id: Option<u32>) -> bool {
+        let Some(Entity(base, _)) = self.get_entity_mut(id) else { return false };
+        base.hurt.push(Hurt { damage, origin_id });
+        true
+    }
+
+    /// Heal a living entity given its id, its health is clamped to the maximum health 
+    /// of the entity kind. Dead entities cannot be healed. This returns false if the
+    /// entity is not existing, not living or dead.
+    /// 
+    /// REF: EntityLiving::heal
+    pub fn heal_entity(&mut self, id: u32, amount: u16) -> bool {
+
+        let Some(Entity(_, BaseKind::Living(living, living_kind))) = self.get_entity_mut(id) else {
+            return false;
+        };
+
+        if living.health == 0 {
+            return false;
+        }
+
+        let health = living.health.saturating_add(amount).min(living_kind.max_health());
+        if health > living.health {
+            living.health = health;
+            self.push_event(Event::Entity { id, inner: EntityEvent::Metadata });
+        }
+
+        true
+
+    }
+
     /// Remove an entity with given id, returning some boxed entity is successful. This
     /// returns true if the entity has been successfully removed removal, the entity's
     /// storage is guaranteed to be freed after return, but the entity footprint in the
@@ -671,6 +1236,81 @@ impl World {
         self.remove_entity_inner(id, true, reason).is_some()
     }
 
+    /// Remove all entities for which the given predicate returns true, returning the
+    /// number of removed entities. Like [`remove_entity`], the chunk cache is kept
+    /// coherent. Player entities are also removed if matched, so the predicate should
+    /// exclude them if needed.
+    ///
+    /// The given reason is only used for log tracing.
+    pub fn remove_entities_matching(&mut self, mut predicate: impl FnMut(u32, &Entity) -> bool, reason: &str) -> usize {
+
+        let ids = self.iter_entities()
+            .filter(|&(id, entity)| predicate(id, entity))
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+
+        ids.into_iter()
+            .filter(|&id| self.remove_entity(id, reason))
+            .count()
+
+    }
+
+    /// Make the entity with the given id ride the given vehicle entity, or stop riding
+    /// its current vehicle if none is given. The entity stops riding its previous 
+    /// vehicle before, and when dismounting it's moved on top of it. This returns false,
+    /// with no change, if one of the entities is not existing or is the current entity 
+    /// being updated, or if the vehicle is already ridden by another entity.
+    /// 
+    /// REF: Entity::mountEntity
+    pub fn set_entity_vehicle(&mut self, id: u32, vehicle_id: Option<u32>) -> bool {
+
+        let Some(Entity(base, _)) = self.get_entity(id) else { return false };
+        let prev_vehicle_id = base.vehicle_id;
+
+        if let Some(vehicle_id) = vehicle_id {
+            if vehicle_id == id {
+                return false;
+            }
+            let Some(Entity(vehicle_base, _)) = self.get_entity(vehicle_id) else { return false };
+            if vehicle_base.rider_id.is_some_and(|rider_id| rider_id != id) {
+                return false;
+            }
+        }
+
+        if prev_vehicle_id == vehicle_id {
+            return true;
+        }
+
+        if let Some(prev_vehicle_id) = prev_vehicle_id {
+
+            let mut dismount_pos = None;
+            if let Some(Entity(prev_vehicle_base, _)) = self.get_entity_mut(prev_vehicle_id) {
+                if prev_vehicle_base.rider_id == Some(id) {
+                    prev_vehicle_base.rider_id = None;
+                }
+                dismount_pos = Some(DVec3::new(prev_vehicle_base.pos.x, prev_vehicle_base.bb.max.y, prev_vehicle_base.pos.z));
+            }
+
+            let entity = self.get_entity_mut(id).unwrap();
+            entity.0.vehicle_id = None;
+
+            if let (None, Some(pos)) = (vehicle_id, dismount_pos) {
+                entity.teleport(pos);
+                self.push_event(Event::Entity { id, inner: EntityEvent::Position { pos } });
+            }
+
+        }
+
+        if let Some(vehicle_id) = vehicle_id {
+            self.get_entity_mut(vehicle_id).unwrap().0.rider_id = Some(id);
+            self.get_entity_mut(id).unwrap().0.vehicle_id = Some(vehicle_id);
+        }
+
+        self.push_event(Event::Entity { id, inner: EntityEvent::Ride { vehicle_id } });
+        true
+
+    }
+
     /// Internal version of [`remove_entity`] that returns the removed component.
     /// 
     /// The caller can specify if the entity is known to be in an existing chunk
@@ -681,6 +1321,7 @@ impl World {
     fn remove_entity_inner(&mut self, id: u32, has_chunk: bool, reason: &str) -> Option<EntityComponent> {
 
         let index = self.entities_id_map.remove(&id)?;
+        self.entities_free_ids.push_back(id);
 
         // Also remove the entity from the player map, if it was.
         self.player_entities_map.remove(&id);
@@ -726,7 +1367,7 @@ impl World {
 
         self.push_event(Event::Entity { id, inner: EntityEvent::Remove });
         if has_chunk {
-            self.push_event(Event::Chunk { cx, cz, inner: ChunkEvent::Dirty });
+            self.set_chunk_dirty(cx, cz);
         }
 
         Some(comp)
@@ -805,7 +1446,7 @@ impl World {
         }
 
         self.push_event(Event::BlockEntity { pos, inner: BlockEntityEvent::Set });
-        self.push_event(Event::Chunk { cx, cz, inner: ChunkEvent::Dirty });
+        self.set_chunk_dirty(cx, cz);
 
     }
 
@@ -892,7 +1533,7 @@ impl World {
 
         self.push_event(Event::BlockEntity { pos, inner: BlockEntityEvent::Remove });
         if has_chunk {
-            self.push_event(Event::Chunk { cx, cz, inner: ChunkEvent::Dirty });
+            self.set_chunk_dirty(cx, cz);
         }
 
         Some(comp)
@@ -989,6 +1630,18 @@ impl World {
         }
     }
 
+    /// Iterate over all entities of the given kind in the world.
+    /// *This function can't return the current updated entity.*
+    pub fn iter_entities_of_kind(&self, kind: EntityKind) -> impl Iterator<Item = (u32, &Entity)> + '_ {
+        self.iter_entities().filter(move |(_, entity)| entity.kind() == kind)
+    }
+
+    /// Iterate over all living entities in the world.
+    /// *This function can't return the current updated entity.*
+    pub fn iter_living_entities(&self) -> impl Iterator<Item = (u32, &Entity)> + '_ {
+        self.iter_entities().filter(|(_, entity)| matches!(entity, Entity(_, BaseKind::Living(_, _))))
+    }
+
     /// Iterate over all entities of the given chunk.
     /// *This function can't return the current updated entity.*
     #[inline]
@@ -1051,6 +1704,29 @@ impl World {
 
     }
 
+    /// Iterate over all entities whose position is inside the given bounding box, unlike
+    /// [`iter_entities_colliding`](Self::iter_entities_colliding) the entities' bounding
+    /// boxes are not checked. The minimum of the box is inclusive and the maximum is 
+    /// exclusive. *This function can't return the current updated entity.*
+    pub fn iter_entities_in_box(&self, bb: BoundingBox) -> impl Iterator<Item = (u32, &Entity)> + '_ {
+
+        let (start_cx, start_cz) = calc_entity_chunk_pos(bb.min);
+        let (end_cx, end_cz) = calc_entity_chunk_pos(bb.max);
+
+        ChunkComponentsIter { 
+            chunks: &self.chunks, 
+            range: ChunkRange::new(start_cx, start_cz, end_cx, end_cz) 
+        }.flat_map(|comp| comp.entities.values())
+            .filter_map(move |&index| {
+                let comp = self.entities.get(index).unwrap();
+                // We ignore updated entities.
+                let entity = comp.inner.as_deref()?;
+                let pos = entity.0.pos;
+                (pos.cmpge(bb.min).all() && pos.cmplt(bb.max).all()).then_some((comp.id, entity))
+            })
+
+    }
+
     /// Return true if any entity is colliding the given bounding box. The hard argument
     /// can be set to true in order to only check for "hard" entities, hard entities can
     /// prevent block placements and entity spawning.
@@ -1059,6 +1735,25 @@ impl World {
             .any(|(_, entity)| !hard || entity.kind().is_hard())
     }
 
+    /// Find the nearest alive player entity (as defined by [`set_player_entity`]) from
+    /// the given position and within the given maximum distance, returning its id and 
+    /// its squared distance to the position. Dead players are kept in the world until
+    /// they respawn, so they are ignored.
+    /// 
+    /// [`set_player_entity`]: Self::set_player_entity
+    pub fn nearest_player_entity(&self, pos: DVec3, max_dist: f64) -> Option<(u32, f64)> {
+        nearest_entity(self.iter_player_entities(), pos, max_dist, |_, entity| {
+            matches!(entity, Entity(_, BaseKind::Living(living, _)) if living.health > 0)
+        })
+    }
+
+    /// Find the nearest entity from the given position and within the given maximum 
+    /// distance, the entity must also match the given predicate. The id of the entity
+    /// and its squared distance to the position are returned.
+    pub fn nearest_entity_filtered(&self, pos: DVec3, max_dist: f64, predicate: impl FnMut(u32, &Entity) -> bool) -> Option<(u32, f64)> {
+        nearest_entity(self.iter_entities(), pos, max_dist, predicate)
+    }
+
     // =================== //
     //       TICKING       //
     // =================== //
@@ -1074,23 +1769,48 @@ impl World {
             // println!("sky_light_subtracted: {}", self.sky_light_subtracted);
         }
 
+        // Only query time if metrics are enabled.
+        let mut start = self.metrics.is_some().then(Instant::now);
+
         self.tick_weather();
+        self.record_metric(&mut start, |m| &mut m.weather);
         // TODO: Wake up all sleeping player if day time.
         
         self.tick_natural_spawn();
+        self.record_metric(&mut start, |m| &mut m.spawn);
 
         self.tick_sky_light();
+        self.record_metric(&mut start, |m| &mut m.sky_light);
 
         self.time += 1;
 
         self.tick_blocks();
+        self.record_metric(&mut start, |m| &mut m.blocks);
         self.tick_entities();
+        self.record_metric(&mut start, |m| &mut m.entities);
         self.tick_block_entities();
+        self.record_metric(&mut start, |m| &mut m.block_entities);
 
         self.tick_light(1000);
+        self.record_metric(&mut start, |m| &mut m.light);
+
+        if let Some(metrics) = &mut self.metrics {
+            metrics.count += 1;
+        }
         
     }
 
+    /// Record the time elapsed since the given start instant into the metric returned
+    /// by the given function, and then reset the start instant. This does nothing if 
+    /// metrics are disabled.
+    fn record_metric(&mut self, start: &mut Option<Instant>, func: impl FnOnce(&mut TickMetrics) -> &mut FadingAverage) {
+        if let (Some(metrics), Some(start)) = (&mut self.metrics, start) {
+            let now = Instant::now();
+            func(metrics).push((now - *start).as_secs_f32(), 0.02);
+            *start = now;
+        }
+    }
+
     /// Update current weather in the world.
     fn tick_weather(&mut self) {
 
@@ -1117,7 +1837,23 @@ impl World {
 
         }
 
-    }
+        // Smoothly move the rain and thunder strengths toward the current weather.
+        // REF: World::updateWeather
+        const STRENGTH_STEP: f32 = 0.01;
+        let (rain, thunder) = match self.weather {
+            Weather::Clear => (false, false),
+            Weather::Rain => (true, false),
+            Weather::Thunder => (true, true),
+        };
+
+        let step = |strength: f32, active: bool| {
+            if active { strength + STRENGTH_STEP } else { strength - STRENGTH_STEP }.clamp(0.0, 1.0)
+        };
+
+        self.rain_strength = step(self.rain_strength, rain);
+        self.thunder_strength = step(self.thunder_strength, thunder);
+
+    }
 
     /// Do natural animal and mob spawning in the world.
     fn tick_natural_spawn(&mut self) {
@@ -1160,11 +1896,19 @@ impl World {
             if max_world_count == 0 {
                 continue;
             }
+            // Hostile mobs never spawn in peaceful difficulty.
+            if category == EntityCategory::Mob && self.difficulty == Difficulty::Peaceful {
+                continue;
+            }
             // Skip the category if it already has enough loaded entities.
             if categories_count[category as usize] > max_world_count * self.chunks.len() / 256 {
                 continue;
             }
 
+            // Hostile mobs can't spawn in daylight, so we only try them under the ground
+            // when it's day. This is checked once for all chunks of the category.
+            let mob_daylight = category == EntityCategory::Mob && self.is_day();
+
             for &(cx, cz) in &loaded_chunks {
 
                 // Temporary borrowing of chunk data to query biome and block.
@@ -1192,6 +1936,14 @@ impl World {
                     continue;
                 }
 
+                // PARITY: The Notchian implementation checks the light level of each
+                // spawned mob, that would always be too high in daylight when exposed
+                // to the sky, we skip the whole attempt early in that case, so the random
+                // numbers sequence is not the same.
+                if mob_daylight && center_pos.y >= chunk_data.get_height(center_pos) as i32 {
+                    continue;
+                }
+
                 let chance_sum = kinds.iter().map(|kind| kind.chance).sum::<u16>();
                 let index = self.rand.next_int_bounded(chance_sum as i32) as u16;
                 let mut chance_acc = 0;
@@ -1289,7 +2041,11 @@ impl World {
                             continue;
                         }
 
-                        self.spawn_entity(entity);
+                        let id = self.spawn_entity(entity);
+                        if kind == EntityKind::Spider {
+                            self.try_spawn_spider_jockey(id);
+                        }
+
                         spawn_count += 1;
                         if spawn_count >= max_chunk_count {
                             break 'pack;
@@ -1308,33 +2064,59 @@ impl World {
 
     }
 
+    /// Called after a spider has been naturally spawned, it has 1% chance of being a 
+    /// spider jockey, in such case a skeleton is spawned and mounted on the spider, its
+    /// entity id is returned.
+    fn try_spawn_spider_jockey(&mut self, spider_id: u32) -> Option<u32> {
+
+        if self.rand.next_int_bounded(100) != 0 {
+            return None;
+        }
+
+        let Entity(spider_base, _) = self.get_entity(spider_id)?;
+        let mut skeleton = Skeleton::new_default(spider_base.pos);
+        skeleton.0.look = spider_base.look;
+
+        let skeleton_id = self.spawn_entity(skeleton);
+        self.set_entity_vehicle(skeleton_id, Some(spider_id));
+        Some(skeleton_id)
+
+    }
+
     /// Update the sky light value depending on the current time, it is then used to get
     /// the real light value of blocks.
     fn tick_sky_light(&mut self) {
+        self.sky_light_subtracted = Self::compute_sky_light_subtracted(self.time, self.weather, self.dimension);
+    }
 
-        let time_wrapped = self.time % 24000;
-        let mut half_turn = (time_wrapped as f32 + 1.0) / 24000.0 - 0.25;
+    /// Get the sky light currently subtracted from the chunks' sky light, this value is 
+    /// updated on each tick depending on the time and weather.
+    pub fn get_sky_light_subtracted(&self) -> u8 {
+        self.sky_light_subtracted
+    }
 
-        if half_turn < 0.0 {
-            half_turn += 1.0;
-        } else if half_turn > 1.0 {
-            half_turn -= 1.0;
-        }
+    /// Force the sky light subtracted from the chunks' sky light, note that this value
+    /// will be recomputed on the next world tick.
+    pub fn set_sky_light_subtracted(&mut self, sky_light_subtracted: u8) {
+        self.sky_light_subtracted = sky_light_subtracted.min(15);
+    }
 
-        let celestial_angle = match self.dimension {
-            Dimension::Nether => 0.5,
-            _ => half_turn + (1.0 - ((half_turn * std::f32::consts::PI).cos() + 1.0) / 2.0 - half_turn) / 3.0,
-        };
+    /// Calculate the sky light to subtract from the chunks' sky light, depending on the
+    /// celestial angle at the given time and the weather. This is the value computed on
+    /// each world tick, it ranges from 0 at noon to 11 at midnight with clear weather.
+    /// 
+    /// REF: World::calculateSkylightSubtracted
+    pub fn compute_sky_light_subtracted(time: u64, weather: Weather, dimension: Dimension) -> u8 {
 
-        let factor = (celestial_angle * std::f32::consts::TAU).cos() * 2.0 + 0.5;
+        let factor = (Self::calc_celestial_angle(time, dimension) * std::f32::consts::TAU).cos() * 2.0 + 0.5;
         let factor = factor.clamp(0.0, 1.0);
-        let factor = match self.weather {
+        let factor = match weather {
             Weather::Clear => 1.0,
             Weather::Rain => 0.6875,
             Weather::Thunder => 0.47265625,
         } * factor;
 
-        self.sky_light_subtracted = ((1.0 - factor) * 11.0) as u8;
+        ((1.0 - factor) * 11.0) as u8
 
     }
 
@@ -1345,7 +2127,7 @@ impl World {
 
         // Schedule ticks...
         while let Some(tick) = self.block_ticks.first() {
-            if self.time > tick.time {
+            if self.time >= tick.time {
                 // This tick should be activated.
                 let tick = self.block_ticks.pop_first().unwrap();
                 assert!(self.block_ticks_states.remove(&tick.state));
@@ -1367,6 +2149,8 @@ impl World {
 
         // Lightning bolts are rare enough to just use a non cached vector.
         let mut lightning_bolt = Vec::new();
+        // Same for blocks placed by the weather, snow and ice.
+        let mut weather_blocks = Vec::new();
 
         // Random tick only on loaded chunks.
         for (&(cx, cz), chunk) in &mut self.chunks {
@@ -1389,8 +2173,39 @@ impl World {
 
                 }
 
-                // TODO: Random snowing.
+                // Random snowing and water freezing in snowy biomes.
+                if self.rand.next_int_bounded(16) == 0 {
+
+                    self.random_ticks_seed = self.random_ticks_seed
+                        .wrapping_mul(3)
+                        .wrapping_add(1013904223);
+
+                    let rand = self.random_ticks_seed >> 2;
+                    let mut pos = IVec3::new(rand & 15, 0, (rand >> 8) & 15);
+                    pos.y = chunk_data.get_height(pos) as i32;
+
+                    if pos.y > 0 && pos.y < CHUNK_HEIGHT as i32 
+                    && chunk_data.get_biome(pos).has_snow() 
+                    && chunk_data.get_block_light(pos) < 10 {
+
+                        let (id, _) = chunk_data.get_block(pos);
+                        let (below_id, below_metadata) = chunk_data.get_block(pos - IVec3::Y);
 
+                        if self.weather != Weather::Clear 
+                        && id == block::AIR 
+                        && below_id != block::AIR 
+                        && below_id != block::ICE 
+                        && block::material::get_material(below_id).is_solid() {
+                            weather_blocks.push((chunk_pos + pos, block::SNOW));
+                        }
+
+                        if below_id == block::WATER_STILL && below_metadata == 0 {
+                            weather_blocks.push((chunk_pos + pos - IVec3::Y, block::ICE));
+                        }
+
+                    }
+
+                }
                 
                 // Minecraft run 80 random ticks per tick per chunk.
                 for _ in 0..80 {
@@ -1403,7 +2218,9 @@ impl World {
                     let pos = IVec3::new((rand >> 0) & 15, (rand >> 16) & 127, (rand >> 8) & 15);
 
                     let (id, metadata) = chunk_data.get_block(pos);
-                    pending_random_ticks.push((chunk_pos + pos, id, metadata));
+                    if block::random_tick_enabled(id) {
+                        pending_random_ticks.push((chunk_pos + pos, id, metadata));
+                    }
 
                 }
 
@@ -1414,6 +2231,12 @@ impl World {
             self.tick_block_unchecked(pos, id, metadata, true);
         }
 
+        for (pos, id) in weather_blocks.drain(..) {
+            if id != block::SNOW || self.can_place_block(pos, Face::NegY, id) {
+                self.set_block_notify(pos, id, 0);
+            }
+        }
+
         for pos in lightning_bolt.drain(..) {
             if self.get_local_weather(pos) == LocalWeather::Rain {
                 self.spawn_entity(LightningBolt::new_default(pos.as_dvec3()));
@@ -1430,60 +2253,81 @@ impl World {
         self.entities.reset();
 
         while let Some((_, comp)) = self.entities.current_mut() {
-
-            if !comp.loaded {
-                self.entities.advance();
-                continue;
+            if comp.loaded {
+                self.tick_current_entity();
             }
+            self.entities.advance();
+        }
+
+    }
 
-            let mut entity = comp.inner.take()
-                .expect("entity was already being updated");
+    /// Tick a single entity given its id, this runs the same logic as entities ticked by
+    /// [`tick`](Self::tick) but without ticking anything else in the world, this is 
+    /// mostly useful for testing and debugging entities. This returns false if the 
+    /// entity is not existing or if it is not loaded.
+    pub fn tick_entity(&mut self, id: u32) -> bool {
+
+        let Some(&index) = self.entities_id_map.get(&id) else { return false };
+        if !self.entities.get(index).expect("invalid entity index").loaded {
+            return false;
+        }
 
-            let id = comp.id;
-            let (prev_cx, prev_cz) = (comp.cx, comp.cz);
-            entity.tick(&mut *self, id);
+        self.entities.select(index);
+        self.tick_current_entity();
+        self.entities.deselect();
+        true
 
-            // Get the component again, the entity may have been removed.
-            if let Some((index, comp)) = self.entities.current_mut() {
+    }
 
-                debug_assert_eq!(comp.id, id, "entity id incoherent");
+    /// Internal function to tick the entity currently selected in the entities tick 
+    /// vector, the entity is moved to its new chunk if needed.
+    fn tick_current_entity(&mut self) {
 
-                // Check if the entity moved to another chunk...
-                let (new_cx, new_cz) = calc_entity_chunk_pos(entity.0.pos);
-                comp.inner = Some(entity);
+        let Some((_, comp)) = self.entities.current_mut() else { return };
+        let mut entity = comp.inner.take()
+            .expect("entity was already being updated");
 
-                if (prev_cx, prev_cz) != (new_cx, new_cz) {
+        let id = comp.id;
+        let (prev_cx, prev_cz) = (comp.cx, comp.cz);
+        entity.tick(&mut *self, id);
 
-                    // NOTE: This part is really critical as this ensures Memory Safety
-                    // in iterators and therefore avoids Undefined Behaviors. Each entity
-                    // really needs to be in a single chunk at a time.
-                    
-                    let removed_index = self.chunks.get_mut(&(prev_cx, prev_cz))
-                        .expect("entity previous chunk is missing")
-                        .entities.remove(&id);
-                    debug_assert_eq!(removed_index, Some(index), "entity is incoherent in its previous chunk");
+        // Get the component again, the entity may have been removed.
+        if let Some((index, comp)) = self.entities.current_mut() {
 
-                    // Update the world entity to its new chunk and orphan state.
-                    comp.cx = new_cx;
-                    comp.cz = new_cz;
+            debug_assert_eq!(comp.id, id, "entity id incoherent");
 
-                    // Insert the entity in its new chunk.
-                    let new_chunk_comp = self.chunks.entry((new_cx, new_cz)).or_default();
-                    let insert_success = new_chunk_comp.entities.insert(id, index).is_none();
-                    debug_assert!(insert_success, "entity was already present in its new chunk");
-                    // Update the loaded flag of the entity depending on the new chunk
-                    // being loaded or not.
-                    comp.loaded = new_chunk_comp.data.is_some();
+            // Check if the entity moved to another chunk...
+            let (new_cx, new_cz) = calc_entity_chunk_pos(entity.0.pos);
+            comp.inner = Some(entity);
 
-                    self.push_event(Event::Chunk { cx: prev_cx, cz: prev_cz, inner: ChunkEvent::Dirty });
-                    self.push_event(Event::Chunk { cx: new_cx, cz: new_cz, inner: ChunkEvent::Dirty });
+            if (prev_cx, prev_cz) != (new_cx, new_cz) {
 
-                }
+                // NOTE: This part is really critical as this ensures Memory Safety
+                // in iterators and therefore avoids Undefined Behaviors. Each entity
+                // really needs to be in a single chunk at a time.
+                
+                let removed_index = self.chunks.get_mut(&(prev_cx, prev_cz))
+                    .expect("entity previous chunk is missing")
+                    .entities.remove(&id);
+                debug_assert_eq!(removed_index, Some(index), "entity is incoherent in its previous chunk");
+
+                // Update the world entity to its new chunk and orphan state.
+                comp.cx = new_cx;
+                comp.cz = new_cz;
+
+                // Insert the entity in its new chunk.
+                let new_chunk_comp = self.chunks.entry((new_cx, new_cz)).or_default();
+                let insert_success = new_chunk_comp.entities.insert(id, index).is_none();
+                debug_assert!(insert_success, "entity was already present in its new chunk");
+                // Update the loaded flag of the entity depending on the new chunk
+                // being loaded or not.
+                comp.loaded = new_chunk_comp.data.is_some();
+
+                self.set_chunk_dirty(prev_cx, prev_cz);
+                self.set_chunk_dirty(new_cx, new_cz);
 
             }
 
-            self.entities.advance();
-
         }
 
     }
@@ -1582,7 +2426,7 @@ impl World {
             }
 
             if changed {
-                self.push_event(Event::Chunk { cx, cz, inner: ChunkEvent::Dirty });
+                self.set_chunk_dirty(cx, cz);
             }
 
             if changed && update.credit >= 1 {
@@ -1628,6 +2472,32 @@ pub enum Weather {
     Thunder,
 }
 
+/// Difficulty of a world, this affects hostile mobs spawning and damages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    /// Hostile mobs are removed and never spawn.
+    Peaceful,
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+
+    /// Scale the damage dealt by a hostile mob to a player depending on difficulty.
+    /// 
+    /// REF: EntityPlayer::attackEntityFrom
+    pub fn scale_mob_damage(self, damage: u16) -> u16 {
+        match self {
+            Difficulty::Peaceful => 0,
+            Difficulty::Easy => damage / 3 + 1,
+            Difficulty::Normal => damage,
+            Difficulty::Hard => damage * 3 / 2,
+        }
+    }
+
+}
+
 /// Type of weather at a specific position.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LocalWeather {
@@ -1664,17 +2534,94 @@ impl Light {
         u8::max(self.block, self.sky_real)
     }
 
-    /// Calculate the block brightness from its light levels.
+    /// Calculate the block brightness from its light levels, in the given dimension,
+    /// the minimum brightness is higher in the nether.
+    /// 
+    /// REF: WorldProvider::generateLightBrightnessTable
     #[inline]
-    pub fn brightness(self) -> f32 {
-        // TODO: In nether, OFFSET is 0.1
-        const OFFSET: f32 = 0.05;
+    pub fn brightness(self, dimension: Dimension) -> f32 {
+        let offset = match dimension {
+            Dimension::Overworld => 0.05,
+            Dimension::Nether => 0.1,
+        };
         let base = 1.0 - self.max_real() as f32 / 15.0;
-        (1.0 - base) * (base * 3.0 + 1.0) * (1.0 - OFFSET) + OFFSET
+        (1.0 - base) / (base * 3.0 + 1.0) * (1.0 - offset) + offset
     }
 
 }
 
+/// Timings of each phase of the world tick, each timing is a fading average of the time
+/// spent in the phase, in seconds.
+#[derive(Debug, Clone, Default)]
+pub struct TickMetrics {
+    /// Number of ticks recorded in these metrics.
+    pub count: u64,
+    /// Time spent updating the weather.
+    pub weather: FadingAverage,
+    /// Time spent on natural entity spawning.
+    pub spawn: FadingAverage,
+    /// Time spent updating the sky light level.
+    pub sky_light: FadingAverage,
+    /// Time spent on scheduled and random block ticks.
+    pub blocks: FadingAverage,
+    /// Time spent ticking entities.
+    pub entities: FadingAverage,
+    /// Time spent ticking block entities.
+    pub block_entities: FadingAverage,
+    /// Time spent propagating light updates.
+    pub light: FadingAverage,
+}
+
+/// A snapshot of the state of a world, returned by [`World::dump_state`]. Its display
+/// implementation produces a compact single-line JSON object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldDump {
+    /// The dimension of the world.
+    pub dimension: Dimension,
+    /// The world time.
+    pub time: u64,
+    /// The current weather.
+    pub weather: Weather,
+    /// Coordinates of all chunks with data, sorted.
+    pub chunks: Vec<(i32, i32)>,
+    /// Number of entities for each entity kind present in the world.
+    pub entities: Vec<(EntityKind, usize)>,
+    /// Number of block entities.
+    pub block_entities: usize,
+    /// Number of scheduled block ticks.
+    pub block_ticks: usize,
+    /// Number of pending light updates.
+    pub light_updates: usize,
+}
+
+impl fmt::Display for WorldDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        write!(f, "{{\"dimension\":\"{:?}\",\"time\":{},\"weather\":\"{:?}\",\"chunks\":[", 
+            self.dimension, self.time, self.weather)?;
+
+        for (i, (cx, cz)) in self.chunks.iter().enumerate() {
+            if i != 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "[{cx},{cz}]")?;
+        }
+
+        f.write_str("],\"entities\":{")?;
+
+        for (i, (kind, count)) in self.entities.iter().enumerate() {
+            if i != 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "\"{kind:?}\":{count}")?;
+        }
+
+        write!(f, "}},\"block_entities\":{},\"block_ticks\":{},\"light_updates\":{}}}",
+            self.block_entities, self.block_ticks, self.light_updates)
+
+    }
+}
+
 /// Different kind of lights in the word.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum LightKind {
@@ -1732,6 +2679,11 @@ pub enum Event {
         center: DVec3,
         /// Radius of the explosion around center.
         radius: f32,
+        /// Positions of all blocks affected by the explosion, this includes air blocks
+        /// and all these blocks have been replaced by air.
+        blocks: Vec<IVec3>,
+        /// Entities affected by the explosion, with the velocity added to them.
+        entities: Vec<(u32, DVec3)>,
     },
     /// An event to debug and spawn block break particles at the given position.
     DebugParticle {
@@ -1777,12 +2729,32 @@ pub enum BlockEvent {
         /// The note to play.
         note: u8,
     },
+    /// Play a sound of the given block sound group.
+    GroupSound {
+        /// The sound group of the block.
+        group: SoundGroup,
+        /// The kind of sound to play from the group.
+        kind: GroupSoundKind,
+    },
+}
+
+/// Kind of sound played from a block sound group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GroupSoundKind {
+    /// The block has been broken.
+    Break,
+    /// The block has been placed.
+    Place,
+    /// An entity stepped on the block, only pushed if step sounds are enabled, see
+    /// [`World::set_step_sounds`].
+    Step,
 }
 
 /// An event with an entity.
 #[derive(Debug, Clone, PartialEq)]
 pub enum EntityEvent {
-    /// The entity has been spawned. The initial chunk position is given.
+    /// The entity has been spawned. The initial chunk position is given. This is also
+    /// used when an existing entity is respawned, like a player after its death.
     Spawn,
     /// The entity has been removed. The last chunk position is given.
     Remove,
@@ -1811,6 +2783,29 @@ pub enum EntityEvent {
     Dead,
     /// Some unspecified entity metadata has changed.
     Metadata,
+    /// The entity started riding the given vehicle entity, or stopped riding if none.
+    Ride {
+        vehicle_id: Option<u32>,
+    },
+    /// An entity with an inventory, such as a chest minecart, have seen some of its 
+    /// stored item stack changed.
+    Storage {
+        /// The index of the stack in the entity's inventory.
+        index: u8,
+        /// The next item stack at this index.
+        stack: ItemStack,
+    },
+    /// A human entity have seen one of its armor item stack changed, for example when 
+    /// damaged.
+    Armor {
+        /// The index of the armor stack, from 0 for the helmet to 3 for the boots.
+        index: u8,
+        /// The next item stack at this index.
+        stack: ItemStack,
+    },
+    /// The entity has stayed long enough in a portal and should be transferred to 
+    /// another dimension, this is left to the event listener.
+    Portal,
 }
 
 /// An event with a block entity.
@@ -1888,6 +2883,19 @@ pub struct ChunkSnapshot {
     /// Block entities in that chunk, all block entities are mapped to their absolute
     /// coordinates in the world.
     pub block_entities: HashMap<IVec3, Box<BlockEntity>>,
+    /// Scheduled block ticks in that chunk, ordered by delay.
+    pub block_ticks: Vec<ScheduledBlockTick>,
+}
+
+/// A scheduled block tick, as stored in chunk snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduledBlockTick {
+    /// Absolute position of the block to tick.
+    pub pos: IVec3,
+    /// The expected id of the block, the tick is ignored if the block is different.
+    pub id: u8,
+    /// Remaining delay before ticking the block.
+    pub delay: u64,
 }
 
 impl ChunkSnapshot {
@@ -1900,6 +2908,7 @@ impl ChunkSnapshot {
             chunk: Chunk::new(),
             entities: Vec::new(),
             block_entities: HashMap::new(),
+            block_ticks: Vec::new(),
         }
     }
 
@@ -1937,6 +2946,9 @@ struct ChunkComponent {
     entities: IndexMap<u32, usize>,
     /// Block entities belonging to this chunk.
     block_entities: HashMap<IVec3, usize>,
+    /// True when the chunk has been modified since it was last saved, see 
+    /// [`World::save_chunk_snapshot`].
+    dirty: bool,
 }
 
 /// Internal type for storing a world entity and keep track of its current chunk.
@@ -1994,6 +3006,19 @@ struct BlockTick {
     state: BlockTickState,
 }
 
+impl BlockTick {
+
+    /// Convert this block tick to its public representation, given the current time.
+    fn to_scheduled(&self, time: u64) -> ScheduledBlockTick {
+        ScheduledBlockTick {
+            pos: self.state.pos,
+            id: self.state.id,
+            delay: self.time.saturating_sub(time),
+        }
+    }
+
+}
+
 impl PartialEq for BlockTick {
     fn eq(&self, other: &Self) -> bool {
         self.uid == other.uid && self.time == other.time
@@ -2027,6 +3052,20 @@ struct LightUpdate {
     credit: u8,
 }
 
+/// Type of the listener function that can be set on a world to receive its events, see
+/// [`World::set_event_listener`].
+pub type EventListenerFn = dyn FnMut(&Event) + Send;
+
+/// Internal wrapper for the world's event listener, this is needed because the world
+/// is clonable but the listener is not, so a cloned listener is just removed.
+struct EventListener(Option<Box<EventListenerFn>>);
+
+impl Clone for EventListener {
+    fn clone(&self) -> Self {
+        Self(None)
+    }
+}
+
 /// A tick vector is an internal structure used for both entities and block entities,
 /// it acts as a dynamically linked list where where the iteration order is defined before
 /// ticking and where insertion and removal of elements doesn't not affect the ordering.
@@ -2195,6 +3234,20 @@ impl<T> TickVec<T> {
 
     }
 
+    /// Select the value at the given index as the current one to tick, this is used to
+    /// tick a single value outside of the linked list iteration.
+    fn select(&mut self, index: usize) {
+        debug_assert!(index < self.inner.len(), "invalid index");
+        self.index = index;
+        self.invalidated = false;
+    }
+
+    /// Clear the current value being ticked.
+    fn deselect(&mut self) {
+        self.index = Self::END;
+        self.invalidated = false;
+    }
+
     /// Go to the next entity to tick.
     fn advance(&mut self) {
         // Do nothing if the current value was removed, because we already advanced the 
@@ -2830,8 +3883,36 @@ impl Iterator for ChunkRange {
 #[cfg(test)]
 mod tests {
 
+    use crate::test::new_floor_world;
     use super::*;
 
+    /// Build a world of 8x8 chunks of flat stone terrain in plains biome, with a player
+    /// in the middle and sky light updated for the given time. The player entity id is
+    /// returned with the world.
+    fn new_flat_world(time: u64) -> (World, u32) {
+
+        let mut world = World::new(Dimension::Overworld);
+
+        for cx in -4..4 {
+            for cz in -4..4 {
+                let mut chunk = Chunk::new();
+                let chunk_mut = Arc::make_mut(&mut chunk);
+                chunk_mut.fill_block(IVec3::ZERO, IVec3::new(16, 64, 16), block::STONE, 0);
+                chunk_mut.biome.fill(Biome::Plains);
+                chunk_mut.recompute_all_height();
+                world.set_chunk(cx, cz, chunk);
+            }
+        }
+
+        let player_id = world.spawn_entity(crate::entity::Human::new_default(DVec3::new(0.5, 64.0, 0.5)));
+        world.set_player_entity(player_id, true);
+
+        world.set_time(time);
+        world.tick_sky_light();
+        (world, player_id)
+
+    }
+
     #[test]
     fn chunk_range() {
 
@@ -2901,4 +3982,929 @@ mod tests {
 
     }
 
+    #[test]
+    fn tick_metrics() {
+
+        let mut world = new_floor_world(block::GRASS);
+
+        for i in 0..4 {
+            world.spawn_entity(crate::entity::Pig::new_with(|base, _, _| {
+                base.pos = DVec3::new(2.5 + i as f64 * 3.0, 64.0, 8.5);
+            }));
+        }
+
+        world.set_block_notify(IVec3::new(8, 64, 8), block::FURNACE, 0);
+
+        assert!(world.take_metrics().is_none());
+        world.swap_metrics(Some(TickMetrics::default()));
+
+        for _ in 0..10 {
+            world.tick();
+        }
+
+        let metrics = world.take_metrics().unwrap();
+        assert_eq!(metrics.count, 10);
+        for metric in [&metrics.weather, &metrics.spawn, &metrics.sky_light, &metrics.blocks, &metrics.entities, &metrics.block_entities, &metrics.light] {
+            assert!(metric.get() >= 0.0);
+        }
+        assert!(metrics.entities.get() > 0.0);
+
+        // Metrics are reset but still recorded once taken.
+        world.tick();
+        assert_eq!(world.take_metrics().unwrap().count, 1);
+        assert_eq!(world.take_metrics().unwrap().count, 0);
+
+        world.swap_metrics(None);
+        world.tick();
+        assert!(world.take_metrics().is_none());
+
+    }
+
+    #[test]
+    fn damage_entity() {
+
+        let mut world = new_floor_world(block::STONE);
+
+        let id = world.spawn_entity(crate::entity::Pig::new_with(|base, _, _| {
+            base.pos = DVec3::new(8.5, 64.0, 8.5);
+        }));
+
+        assert!(!world.damage_entity(id + 1, 100, None));
+        assert!(world.damage_entity(id, 100, None));
+
+        world.swap_events(Some(Vec::new()));
+        world.tick();
+
+        let events = world.swap_events(Some(Vec::new())).unwrap();
+        assert!(events.iter().any(|event| matches!(event, Event::Entity { id: event_id, inner: EntityEvent::Dead } if *event_id == id)));
+
+        let Some(Entity(_, crate::entity::BaseKind::Living(living, _))) = world.get_entity(id) else { panic!() };
+        assert_eq!(living.health, 0);
+        assert_eq!(living.death_time, 1);
+
+        // The dead entity is removed after 20 ticks of death timer.
+        for _ in 0..19 {
+            world.tick();
+        }
+        assert!(world.contains_entity(id));
+        world.tick();
+        assert!(!world.contains_entity(id));
+
+    }
+
+    #[test]
+    fn heal_entity() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let id = world.spawn_entity(crate::entity::Human::new_with(|base, living, _| {
+            base.pos = DVec3::new(8.5, 64.0, 8.5);
+            living.health = 12;
+        }));
+
+        let pig_id = world.spawn_entity(crate::entity::Pig::new_default(DVec3::new(4.5, 64.0, 4.5)));
+
+        world.swap_events(Some(Vec::new()));
+
+        assert!(world.heal_entity(id, 4));
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(id) else { panic!() };
+        assert_eq!(living.health, 16);
+
+        let events = world.swap_events(Some(Vec::new())).unwrap();
+        assert!(events.iter().any(|event| matches!(event, Event::Entity { id: event_id, inner: EntityEvent::Metadata } if *event_id == id)));
+
+        // Health is capped to the maximum health.
+        assert!(world.heal_entity(id, 10));
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(id) else { panic!() };
+        assert_eq!(living.health, 20);
+
+        // Already full, no metadata event.
+        world.swap_events(Some(Vec::new()));
+        assert!(world.heal_entity(id, 1));
+        assert!(world.swap_events(Some(Vec::new())).unwrap().is_empty());
+
+        // Pigs have less health.
+        assert!(world.heal_entity(pig_id, 10));
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(pig_id) else { panic!() };
+        assert_eq!(living.health, 10);
+
+        // Dead entities cannot be healed.
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity_mut(id) else { panic!() };
+        living.health = 0;
+        assert!(!world.heal_entity(id, 4));
+        assert!(!world.heal_entity(id + 100, 4));
+
+    }
+
+    #[test]
+    fn dump_state() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+        world.set_chunk(-1, 2, Chunk::new());
+        world.set_time(1234);
+        world.set_weather(Weather::Rain);
+
+        world.spawn_entity(crate::entity::Pig::new_default(DVec3::new(4.5, 64.0, 4.5)));
+        world.spawn_entity(crate::entity::Pig::new_default(DVec3::new(6.5, 64.0, 4.5)));
+        world.spawn_entity(crate::entity::Zombie::new_default(DVec3::new(8.5, 64.0, 8.5)));
+        world.schedule_block_tick(IVec3::new(2, 64, 2), block::SAND, 3);
+        world.set_block(IVec3::new(3, 64, 3), block::TORCH, 0);
+
+        let dump = world.dump_state();
+        assert_eq!(dump.dimension, Dimension::Overworld);
+        assert_eq!(dump.time, 1234);
+        assert_eq!(dump.weather, Weather::Rain);
+        assert_eq!(dump.chunks, [(-1, 2), (0, 0)]);
+        assert_eq!(dump.entities, [(EntityKind::Pig, 2), (EntityKind::Zombie, 1)]);
+        assert_eq!(dump.entities.iter().map(|&(_, count)| count).sum::<usize>(), world.get_entity_count());
+        assert_eq!(dump.block_entities, world.get_block_entity_count());
+        assert_eq!(dump.block_ticks, 1);
+        assert_eq!(dump.light_updates, world.get_light_update_count());
+        assert_ne!(dump.light_updates, 0);
+
+        let text = dump.to_string();
+        assert!(text.starts_with(r#"{"dimension":"Overworld","time":1234,"weather":"Rain","chunks":[[-1,2],[0,0]],"entities":{"Pig":2,"Zombie":1},"block_entities":0,"block_ticks":1,"#));
+
+    }
+
+    #[test]
+    fn set_block_entity_notify() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let pos = IVec3::new(8, 64, 8);
+        world.set_block_entity_notify(pos, block::FURNACE, 0, BlockEntity::Furnace(Default::default()));
+        
+        world.swap_events(Some(Vec::new()));
+        assert_eq!(world.set_block_entity_notify(pos, block::CHEST, 0, BlockEntity::Chest(Default::default())), Some((block::FURNACE, 0)));
+
+        assert_eq!(world.get_block(pos), Some((block::CHEST, 0)));
+        assert!(matches!(world.get_block_entity(pos), Some(BlockEntity::Chest(_))));
+
+        let events = world.swap_events(None).unwrap()
+            .into_iter()
+            .filter(|event| !matches!(event, Event::Chunk { .. }))
+            .collect::<Vec<_>>();
+
+        assert!(matches!(events[..], [
+            Event::Block { inner: BlockEvent::Set { id: block::CHEST, prev_id: block::FURNACE, .. }, .. },
+            Event::BlockEntity { inner: BlockEntityEvent::Remove, .. },
+            Event::BlockEntity { inner: BlockEntityEvent::Set, .. },
+        ]));
+
+    }
+
+    #[test]
+    fn event_listener() {
+
+        use std::sync::Mutex;
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_listener = Arc::clone(&received);
+        world.set_event_listener(Some(Box::new(move |event: &Event| {
+            if let &Event::Block { pos, inner: BlockEvent::Set { id, .. } } = event {
+                received_listener.lock().unwrap().push((pos, id));
+            }
+        })));
+
+        // Events queue is disabled but the listener still receive events.
+        assert!(!world.has_events());
+        let pos = IVec3::new(8, 64, 8);
+        world.set_block(pos, block::STONE, 0);
+        assert_eq!(received.lock().unwrap()[..], [(pos, block::STONE)]);
+
+        // The listener coexists with the events queue.
+        world.swap_events(Some(Vec::new()));
+        world.set_block(pos, block::DIRT, 0);
+        assert_eq!(received.lock().unwrap()[..], [(pos, block::STONE), (pos, block::DIRT)]);
+        assert!(world.swap_events(None).unwrap().iter().any(|event| matches!(event, Event::Block { inner: BlockEvent::Set { id: block::DIRT, .. }, .. })));
+
+        // Cloned world has no listener.
+        assert!(!world.clone().has_event_listener());
+        assert!(world.set_event_listener(None).is_some());
+
+    }
+
+    #[test]
+    fn world_send() {
+        // The world must stay sendable to other threads, even with an event listener.
+        fn assert_send<T: Send>() {}
+        assert_send::<World>();
+    }
+
+    #[test]
+    fn brightness() {
+
+        let mut chunk = Chunk::new();
+        let chunk_mut = Arc::make_mut(&mut chunk);
+        chunk_mut.fill_light(IVec3::ZERO, IVec3::new(16, 128, 16), 0, 0);
+
+        let mut overworld = World::new(Dimension::Overworld);
+        let mut nether = World::new(Dimension::Nether);
+        overworld.set_chunk(0, 0, chunk.clone());
+        nether.set_chunk(0, 0, chunk);
+
+        let pos = IVec3::new(4, 64, 4);
+        assert_eq!(overworld.get_brightness(pos), 0.05);
+        assert_eq!(nether.get_brightness(pos), 0.1);
+
+        // Brightness increases with the light level, up to full brightness.
+        let mut prev = 0.0;
+        for level in 0..=15 {
+            overworld.get_chunk_mut(0, 0).unwrap().set_block_light(pos, level);
+            let brightness = overworld.get_brightness(pos);
+            assert!(brightness > prev, "brightness should increase at level {level}");
+            prev = brightness;
+        }
+        assert_eq!(prev, 1.0);
+
+        // Reference values from the Notchian brightness table, the brightness curve is 
+        // not linear and is below 0.5 until light level 12.
+        for (level, expected) in [(4, 0.1291667), (8, 0.2611111), (11, 0.437037), (12, 0.525)] {
+            overworld.get_chunk_mut(0, 0).unwrap().set_block_light(pos, level);
+            let brightness = overworld.get_brightness(pos);
+            assert!((brightness - expected).abs() < 1e-6, "level {level}: expected {expected}, got {brightness}");
+        }
+
+    }
+
+    #[test]
+    fn light_emission() {
+
+        for (id, emission) in [
+            (block::LAVA_STILL, 15),
+            (block::LAVA_MOVING, 15),
+            (block::FIRE, 15),
+            (block::GLOWSTONE, 15),
+            (block::PUMPKIN_LIT, 15),
+            (block::TORCH, 14),
+            (block::FURNACE_LIT, 13),
+            (block::PORTAL, 11),
+            (block::REDSTONE_ORE_LIT, 9),
+            (block::REPEATER_LIT, 9),
+            (block::REDSTONE_TORCH_LIT, 7),
+            (block::BROWN_MUSHROOM, 1),
+        ] {
+
+            let mut world = World::new(Dimension::Overworld);
+            world.set_chunk(0, 0, Chunk::new());
+
+            let pos = IVec3::new(8, 64, 8);
+            world.set_block(pos, id, 0);
+            world.tick_light(usize::MAX);
+
+            assert_eq!(world.get_light(pos).block, emission, "block id {id}");
+            assert_eq!(world.get_light(pos + IVec3::new(0, 0, 3)).block, emission.saturating_sub(3), "block id {id}");
+
+        }
+
+    }
+
+    #[test]
+    fn chunk_dirty() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+        assert!(!world.is_chunk_dirty(0, 0));
+        assert!(!world.is_chunk_dirty(1, 0));
+
+        world.set_block(IVec3::new(8, 64, 8), block::STONE, 0);
+        assert!(world.is_chunk_dirty(0, 0));
+
+        // Taking a snapshot doesn't clear the flag, saving does.
+        assert!(world.take_chunk_snapshot(0, 0).is_some());
+        assert!(world.is_chunk_dirty(0, 0));
+        assert!(world.save_chunk_snapshot(0, 0).is_some());
+        assert!(!world.is_chunk_dirty(0, 0));
+
+    }
+
+    #[test]
+    fn iter_dirty_chunks() {
+
+        let mut world = World::new(Dimension::Overworld);
+        for cx in -2..2 {
+            for cz in -2..2 {
+                world.set_chunk(cx, cz, Chunk::new());
+            }
+        }
+
+        assert_eq!(world.iter_dirty_chunks().count(), 0);
+
+        world.set_block(IVec3::new(8, 64, 8), block::STONE, 0);
+        world.set_block(IVec3::new(-8, 64, 20), block::STONE, 0);
+        world.set_block(IVec3::new(-20, 64, -20), block::STONE, 0);
+
+        let mut dirty = world.iter_dirty_chunks().collect::<Vec<_>>();
+        dirty.sort();
+        assert_eq!(dirty, [(-2, -2), (-1, 1), (0, 0)]);
+
+        world.save_chunk_snapshot(0, 0);
+        let mut dirty = world.iter_dirty_chunks().collect::<Vec<_>>();
+        dirty.sort();
+        assert_eq!(dirty, [(-2, -2), (-1, 1)]);
+
+    }
+
+    #[test]
+    fn spider_jockey() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        // Force the random generator to the jockey case.
+        let seed = (0..).find(|&seed| JavaRandom::new(seed).next_int_bounded(100) == 0).unwrap();
+        world.rand = JavaRandom::new(seed);
+
+        let spider_id = world.spawn_entity(crate::entity::Spider::new_default(DVec3::new(8.5, 64.0, 8.5)));
+        let skeleton_id = world.try_spawn_spider_jockey(spider_id).unwrap();
+
+        let Entity(spider_base, _) = world.get_entity(spider_id).unwrap();
+        assert_eq!(spider_base.rider_id, Some(skeleton_id));
+        let spider_pos = spider_base.pos;
+        let skeleton = world.get_entity(skeleton_id).unwrap();
+        assert_eq!(skeleton.kind(), EntityKind::Skeleton);
+        assert_eq!(skeleton.0.vehicle_id, Some(spider_id));
+        assert_eq!(skeleton.0.pos, spider_pos);
+        assert!(!skeleton.0.persistent);
+
+        // The skeleton is moved with the spider.
+        world.get_entity_mut(spider_id).unwrap().teleport(DVec3::new(4.5, 64.0, 4.5));
+        world.tick();
+        let spider_pos = world.get_entity(spider_id).unwrap().0.pos;
+        let skeleton_pos = world.get_entity(skeleton_id).unwrap().0.pos;
+        assert_eq!(skeleton_pos.x, spider_pos.x);
+        assert_eq!(skeleton_pos.z, spider_pos.z);
+        assert!(skeleton_pos.y > spider_pos.y);
+
+        // Same seed but with one more roll, this should not spawn a jockey.
+        world.rand = JavaRandom::new(seed);
+        world.rand.next_int();
+        assert_eq!(world.try_spawn_spider_jockey(spider_id), None);
+
+    }
+
+    #[test]
+    fn entities_in_box() {
+
+        let mut world = World::new(Dimension::Overworld);
+        let pig = |x: f64, z: f64| crate::entity::Pig::new_default(DVec3::new(x, 64.0, z));
+
+        // Box crossing the boundary between two chunks.
+        let bb = BoundingBox::new(14.0, 63.0, 2.0, 18.0, 66.0, 6.0);
+
+        let inside = [
+            world.spawn_entity(pig(14.0, 2.0)),
+            world.spawn_entity(pig(15.5, 4.0)),
+            world.spawn_entity(pig(17.9, 5.9)),
+        ];
+        
+        // These entities have their bounding box colliding the box.
+        world.spawn_entity(pig(13.9, 4.0));
+        world.spawn_entity(pig(18.0, 4.0));
+        world.spawn_entity(pig(16.0, 6.2));
+        // This one is far.
+        world.spawn_entity(pig(40.0, 4.0));
+
+        assert_eq!(world.iter_entities_colliding(bb).count(), 6);
+
+        let mut ids = world.iter_entities_in_box(bb).map(|(id, _)| id).collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, inside);
+
+    }
+
+    #[test]
+    fn find_closest_block() {
+
+        let mut world = World::new(Dimension::Overworld);
+        for cx in 0..2 {
+            let mut chunk = Chunk::new();
+            Arc::make_mut(&mut chunk).fill_block(IVec3::new(0, 60, 0), IVec3::new(16, 8, 16), block::STONE, 0);
+            world.set_chunk(cx, 0, chunk);
+        }
+
+        let is_water = |_, id, _| id == block::WATER_STILL;
+        let center = IVec3::new(8, 64, 8);
+
+        // A far water block, outside of the search radius.
+        world.set_block(IVec3::new(20, 64, 8), block::WATER_STILL, 0);
+        assert_eq!(world.find_closest_block(center, 8, is_water), None);
+        assert_eq!(world.find_closest_block(center, 12, is_water), Some(IVec3::new(20, 64, 8)));
+
+        // A closer water block.
+        world.set_block(IVec3::new(5, 62, 10), block::WATER_STILL, 0);
+        assert_eq!(world.find_closest_block(center, 8, is_water), Some(IVec3::new(5, 62, 10)));
+        assert_eq!(world.find_closest_block(center, 16, is_water), Some(IVec3::new(5, 62, 10)));
+
+        // The center block itself.
+        assert_eq!(world.find_closest_block(center, 0, |_, id, _| id == block::STONE), Some(center));
+
+    }
+
+    #[test]
+    fn find_safe_spawn() {
+
+        use crate::source::MemoryChunkSource;
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        // Nothing to stand on.
+        assert_eq!(world.find_safe_spawn(IVec3::new(8, 64, 8), None::<&mut MemoryChunkSource>), None);
+
+        let chunk = world.get_chunk_mut(0, 0).unwrap();
+        chunk.fill_block(IVec3::ZERO, IVec3::new(16, 60, 16), block::STONE, 0);
+        chunk.fill_block(IVec3::new(0, 60, 0), IVec3::new(16, 1, 16), block::DIRT, 0);
+        chunk.fill_block(IVec3::new(0, 61, 0), IVec3::new(16, 1, 16), block::WATER_STILL, 0);
+        chunk.recompute_all_height();
+
+        // A single dry column in the middle of the water.
+        world.set_block(IVec3::new(3, 61, 4), block::GRASS, 0);
+
+        let pos = world.find_safe_spawn(IVec3::new(8, 70, 8), None::<&mut MemoryChunkSource>).unwrap();
+        assert_eq!(pos, IVec3::new(3, 62, 4));
+        assert!(world.is_block_normal_cube(pos - IVec3::Y));
+        assert!(world.is_block_air(pos));
+        assert!(world.is_block_air(pos + IVec3::Y));
+
+        // A block above the grass makes it unsafe.
+        world.set_block(IVec3::new(3, 63, 4), block::STONE, 0);
+        assert_eq!(world.find_safe_spawn(IVec3::new(8, 70, 8), None::<&mut MemoryChunkSource>), Some(IVec3::new(3, 64, 4)));
+
+        // Missing chunks are loaded from the source when given.
+        let mut source = MemoryChunkSource::new();
+        source.save(world.save_chunk_snapshot(0, 0).unwrap()).unwrap();
+        let mut world = World::new(Dimension::Overworld);
+        assert_eq!(world.find_safe_spawn(IVec3::new(8, 70, 8), None::<&mut MemoryChunkSource>), None);
+        assert_eq!(world.find_safe_spawn(IVec3::new(8, 70, 8), Some(&mut source)), Some(IVec3::new(3, 64, 4)));
+        assert!(world.contains_chunk(0, 0));
+
+    }
+
+    #[test]
+    fn load_chunk_from_source() {
+
+        use crate::source::MemoryChunkSource;
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+        world.set_block(IVec3::new(8, 64, 8), block::STONE, 0);
+        world.spawn_entity(crate::entity::Pig::new_default(DVec3::new(8.5, 65.0, 8.5)));
+
+        let mut source = MemoryChunkSource::new();
+        source.save(world.save_chunk_snapshot(0, 0).unwrap()).unwrap();
+        
+        let mut world = World::new(Dimension::Overworld);
+        assert!(!world.load_chunk_from_source(&mut source, 1, 0).unwrap());
+        assert!(!world.contains_chunk(1, 0));
+        assert!(world.load_chunk_from_source(&mut source, 0, 0).unwrap());
+        assert_eq!(world.get_block(IVec3::new(8, 64, 8)), Some((block::STONE, 0)));
+        assert_eq!(world.get_entity_count(), 1);
+
+    }
+
+    #[test]
+    fn entity_id_recycle() {
+
+        let mut world = World::new(Dimension::Overworld);
+        let pig = || crate::entity::Pig::new_default(DVec3::new(8.5, 64.0, 8.5));
+
+        // Some entities that are kept alive during the whole test.
+        let alive = (0..10).map(|_| world.spawn_entity(pig())).collect::<Vec<_>>();
+
+        let removed = (0..ENTITY_ID_RECYCLE_THRESHOLD).map(|_| world.spawn_entity(pig())).collect::<Vec<_>>();
+        for &id in &removed {
+            assert!(world.remove_entity(id, "test"));
+        }
+
+        // Not enough ids freed to recycle them.
+        let id = world.spawn_entity(pig());
+        assert!(!removed.contains(&id));
+        assert!(world.remove_entity(id, "test"));
+
+        // Now the oldest freed id is recycled, but only one because the threshold is 
+        // reached again.
+        assert_eq!(world.spawn_entity(pig()), removed[0]);
+        assert!(!removed.contains(&world.spawn_entity(pig())));
+
+        // Remove many entities and check that they are recycled in order.
+        let removed = (0..ENTITY_ID_RECYCLE_THRESHOLD).map(|_| world.spawn_entity(pig())).collect::<Vec<_>>();
+        for &id in &removed {
+            assert!(world.remove_entity(id, "test"));
+        }
+
+        let mut ids = HashSet::new();
+        for _ in 0..ENTITY_ID_RECYCLE_THRESHOLD {
+            let id = world.spawn_entity(pig());
+            assert!(!alive.contains(&id));
+            assert!(ids.insert(id));
+        }
+
+        assert!(ids.iter().all(|&id| id < removed[0]));
+
+        for &id in &alive {
+            assert!(world.contains_entity(id));
+        }
+
+    }
+
+    #[test]
+    fn nearest_entity() {
+
+        let mut world = World::new(Dimension::Overworld);
+
+        let mut spawn_player = |x: f64| {
+            let id = world.spawn_entity(crate::entity::Human::new_default(DVec3::new(x, 64.0, 0.0)));
+            world.set_player_entity(id, true);
+            id
+        };
+
+        let far_id = spawn_player(20.0);
+        let near_id = spawn_player(-5.0);
+        let mid_id = spawn_player(8.0);
+        let pig_id = world.spawn_entity(crate::entity::Pig::new_default(DVec3::new(2.0, 64.0, 0.0)));
+
+        assert_eq!(world.nearest_player_entity(DVec3::new(0.0, 64.0, 0.0), 16.0), Some((near_id, 25.0)));
+        assert_eq!(world.nearest_player_entity(DVec3::new(20.0, 64.0, 3.0), 4.0), Some((far_id, 9.0)));
+        assert_eq!(world.nearest_player_entity(DVec3::new(0.0, 64.0, 0.0), 4.0), None);
+        assert_eq!(world.nearest_player_entity(DVec3::new(40.0, 64.0, 0.0), 16.0), None);
+
+        // Dead players are ignored.
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity_mut(near_id) else { panic!() };
+        living.health = 0;
+        assert_eq!(world.nearest_player_entity(DVec3::new(0.0, 64.0, 0.0), 16.0), Some((mid_id, 64.0)));
+
+        // The pig is not a player but it is the nearest entity.
+        assert_eq!(world.nearest_entity_filtered(DVec3::new(0.0, 64.0, 0.0), 16.0, |_, _| true), Some((pig_id, 4.0)));
+        assert_eq!(world.nearest_entity_filtered(DVec3::new(0.0, 64.0, 0.0), 16.0, |id, _| id != pig_id), Some((near_id, 25.0)));
+
+    }
+
+    #[test]
+    fn day_time() {
+
+        let mut world = World::new(Dimension::Overworld);
+
+        world.set_time(1000);
+        assert!(world.is_day());
+        world.set_time(18000);
+        assert!(!world.is_day());
+
+        // Noon and midnight.
+        world.set_time(5999);
+        assert!(world.get_celestial_angle().abs() < 1e-4);
+        world.set_time(17999);
+        assert!((world.get_celestial_angle() - 0.5).abs() < 1e-4);
+
+        // The celestial angle is coherent with the sky light.
+        for time in (0..24000).step_by(500) {
+            world.set_time(time);
+            world.tick_sky_light();
+            assert_eq!(world.is_day(), world.sky_light_subtracted < 4);
+        }
+
+        world.set_time(1000);
+        world.weather = Weather::Thunder;
+        assert!(!world.is_day());
+
+    }
+
+    #[test]
+    fn weather_strength() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_weather_next_time(u64::MAX);
+        world.tick();
+        assert_eq!(world.get_rain_strength(), 0.0);
+
+        // The rain strength ramps up instead of jumping to full strength.
+        world.set_weather(Weather::Rain);
+        let mut prev = 0.0;
+        for _ in 0..50 {
+            world.tick();
+            let strength = world.get_rain_strength();
+            assert!(strength > prev && strength < 1.0, "{strength}");
+            prev = strength;
+        }
+
+        for _ in 0..60 {
+            world.tick();
+        }
+        assert_eq!(world.get_rain_strength(), 1.0);
+        assert_eq!(world.get_thunder_strength(), 0.0);
+
+        world.set_weather(Weather::Thunder);
+        world.tick();
+        assert!(world.get_thunder_strength() > 0.0 && world.get_thunder_strength() < 1.0);
+
+        // Both strengths ramp down when clear.
+        world.set_weather(Weather::Clear);
+        world.tick();
+        assert!(world.get_rain_strength() < 1.0 && world.get_rain_strength() > 0.9);
+        for _ in 0..100 {
+            world.tick();
+        }
+        assert_eq!(world.get_rain_strength(), 0.0);
+        assert_eq!(world.get_thunder_strength(), 0.0);
+
+    }
+
+    #[test]
+    fn sky_light_subtracted() {
+
+        let compute = World::compute_sky_light_subtracted;
+        assert_eq!(compute(6000, Weather::Clear, Dimension::Overworld), 0);
+        assert_eq!(compute(18000, Weather::Clear, Dimension::Overworld), 11);
+        assert!(compute(6000, Weather::Thunder, Dimension::Overworld) > 0);
+        assert!((0..24000).all(|time| compute(time, Weather::Clear, Dimension::Overworld) <= 11));
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+        world.set_time(18000);
+        world.tick_sky_light();
+        assert_eq!(world.get_sky_light_subtracted(), 11);
+
+        let pos = IVec3::new(8, 100, 8);
+        world.set_sky_light_subtracted(5);
+        assert_eq!(world.get_light(pos).sky_real, 10);
+
+    }
+
+    #[test]
+    fn set_block_metadata() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let pos = IVec3::new(3, 64, 3);
+        world.set_block(pos - IVec3::Y, block::FARMLAND, 0);
+        world.set_block(pos, block::WHEAT, 0);
+
+        // Tamper the height to check that it is not recomputed.
+        world.get_chunk_mut(0, 0).unwrap().set_height(pos, 10);
+        let light_updates = world.get_light_update_count();
+
+        world.swap_events(Some(Vec::new()));
+
+        assert_eq!(world.set_block_metadata(pos, 5), Some(0));
+        assert_eq!(world.get_block(pos), Some((block::WHEAT, 5)));
+        assert_eq!(world.get_chunk(0, 0).unwrap().get_height(pos), 10);
+        assert_eq!(world.get_light_update_count(), light_updates);
+
+        let events = world.swap_events(Some(Vec::new())).unwrap();
+        assert!(matches!(events[..], [
+            Event::Block { pos: event_pos, inner: BlockEvent::Set { id: block::WHEAT, metadata: 5, prev_id: block::WHEAT, prev_metadata: 0 } },
+            Event::Chunk { cx: 0, cz: 0, inner: ChunkEvent::Dirty },
+        ] if event_pos == pos));
+
+        // Same metadata, no event.
+        assert_eq!(world.set_block_metadata(pos, 5), Some(5));
+        assert!(world.swap_events(Some(Vec::new())).unwrap().is_empty());
+
+        // Unloaded chunk.
+        assert_eq!(world.set_block_metadata(IVec3::new(100, 64, 100), 5), None);
+
+    }
+
+    #[test]
+    fn top_block() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        assert_eq!(world.get_highest_block(3, 5), None);
+        assert_eq!(world.get_top_solid_block(3, 5), None);
+        assert_eq!(world.get_highest_block(100, 5), None);
+
+        for y in 0..63 {
+            world.set_block(IVec3::new(3, y, 5), block::DIRT, 0);
+        }
+        world.set_block(IVec3::new(3, 63, 5), block::GRASS, 0);
+        world.set_block(IVec3::new(3, 64, 5), block::TALL_GRASS, 1);
+
+        assert_eq!(world.get_highest_block(3, 5), Some(IVec3::new(3, 64, 5)));
+        assert_eq!(world.get_top_solid_block(3, 5), Some(IVec3::new(3, 63, 5)));
+
+        // Glass is solid but doesn't block sky light.
+        world.set_block(IVec3::new(3, 80, 5), block::GLASS, 0);
+        assert_eq!(world.get_top_solid_block(3, 5), Some(IVec3::new(3, 80, 5)));
+
+    }
+
+    #[test]
+    fn iter_entities_filtered() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let pig0 = world.spawn_entity(crate::entity::Pig::new_default(DVec3::new(2.5, 64.0, 2.5)));
+        let pig1 = world.spawn_entity(crate::entity::Pig::new_default(DVec3::new(6.5, 64.0, 2.5)));
+        let cow = world.spawn_entity(crate::entity::Cow::new_default(DVec3::new(8.5, 64.0, 8.5)));
+        world.spawn_entity(crate::entity::Item::new_default(DVec3::new(4.5, 64.0, 4.5)));
+        world.spawn_entity(crate::entity::Arrow::new_default(DVec3::new(4.5, 66.0, 4.5)));
+
+        let mut pigs = world.iter_entities_of_kind(EntityKind::Pig).map(|(id, _)| id).collect::<Vec<_>>();
+        pigs.sort();
+        assert_eq!(pigs, [pig0, pig1]);
+
+        assert!(world.iter_entities_of_kind(EntityKind::Pig).all(|(_, entity)| entity.kind() == EntityKind::Pig));
+        assert_eq!(world.iter_entities_of_kind(EntityKind::Item).count(), 1);
+        assert_eq!(world.iter_entities_of_kind(EntityKind::Zombie).count(), 0);
+
+        let mut living = world.iter_living_entities().map(|(id, _)| id).collect::<Vec<_>>();
+        living.sort();
+        assert_eq!(living, [pig0, pig1, cow]);
+
+    }
+
+    #[test]
+    fn spawn_kind() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let pos = DVec3::new(8.5, 64.0, 8.5);
+        let pig_id = world.spawn_kind(EntityKind::Pig, pos, false);
+        let Some(entity) = world.get_entity(pig_id) else { panic!() };
+        assert_eq!(entity.kind(), EntityKind::Pig);
+        assert_eq!(entity.0.pos, pos);
+        assert!(entity.0.persistent);
+
+        let sheep_color = |world: &World, id| {
+            let Some(Entity(_, BaseKind::Living(_, crate::entity::LivingKind::Sheep(sheep)))) = world.get_entity(id) else { panic!() };
+            sheep.color
+        };
+
+        // Most sheep are white, but natural spawn randomize some colors.
+        let natural_ids = (0..100)
+            .map(|_| world.spawn_kind(EntityKind::Sheep, pos, true))
+            .collect::<Vec<_>>();
+        let natural_colors = natural_ids.iter()
+            .map(|&id| sheep_color(&world, id))
+            .collect::<Vec<_>>();
+        assert!(natural_colors.iter().any(|&color| color != 0));
+        assert!(natural_colors.iter().all(|&color| [0, 6, 7, 8, 12, 15].contains(&color)));
+
+        let default_ids = (0..100)
+            .map(|_| world.spawn_kind(EntityKind::Sheep, pos, false))
+            .collect::<Vec<_>>();
+        let default_colors = default_ids.iter()
+            .map(|&id| sheep_color(&world, id))
+            .collect::<Vec<_>>();
+        assert!(default_colors.iter().all(|&color| color == 0));
+
+    }
+
+    #[test]
+    fn tick_single_entity() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let falling_block = |pos: DVec3| crate::entity::FallingBlock::new_with(|base, falling_block| {
+            base.pos = pos;
+            falling_block.block_id = block::SAND;
+        });
+
+        let id = world.spawn_entity(falling_block(DVec3::new(8.5, 70.5, 8.5)));
+        let other_id = world.spawn_entity(falling_block(DVec3::new(4.5, 70.5, 4.5)));
+
+        assert!(world.tick_entity(id));
+        assert!(!world.tick_entity(other_id + 1));
+        assert_eq!(world.get_time(), 0);
+
+        let Some(Entity(base, BaseKind::FallingBlock(_))) = world.get_entity(id) else { panic!() };
+        assert_eq!(base.lifetime, 1);
+        assert!(base.pos.y < 70.5 && base.vel.y < 0.0);
+
+        let Some(Entity(base, BaseKind::FallingBlock(_))) = world.get_entity(other_id) else { panic!() };
+        assert_eq!(base.lifetime, 0);
+        assert_eq!(base.pos.y, 70.5);
+        assert_eq!(base.vel.y, 0.0);
+
+        // The entity can be ticked again, and the whole world tick is still working.
+        assert!(world.tick_entity(id));
+        world.tick();
+        let Some(Entity(base, BaseKind::FallingBlock(_))) = world.get_entity(id) else { panic!() };
+        assert_eq!(base.lifetime, 3);
+        let Some(Entity(base, BaseKind::FallingBlock(_))) = world.get_entity(other_id) else { panic!() };
+        assert_eq!(base.lifetime, 1);
+
+    }
+
+    #[test]
+    fn remove_entities_matching() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+        world.set_chunk(1, 0, Chunk::new());
+
+        let item = |pos: DVec3| crate::entity::Item::new_with(|base, item| {
+            base.pos = pos;
+            item.stack = ItemStack::new_block(block::DIRT, 0);
+        });
+
+        let pig_id = world.spawn_entity(crate::entity::Pig::new_default(DVec3::new(2.5, 64.0, 2.5)));
+        world.spawn_entity(item(DVec3::new(4.5, 64.0, 4.5)));
+        let zombie_id = world.spawn_entity(crate::entity::Zombie::new_default(DVec3::new(20.5, 64.0, 4.5)));
+        world.spawn_entity(item(DVec3::new(20.5, 64.0, 8.5)));
+        world.spawn_entity(item(DVec3::new(8.5, 64.0, 8.5)));
+
+        let removed = world.remove_entities_matching(|_, entity| entity.kind() == EntityKind::Item, "test");
+        assert_eq!(removed, 3);
+        assert_eq!(world.get_entity_count(), 2);
+        assert!(world.iter_entities().all(|(_, entity)| entity.kind() != EntityKind::Item));
+        assert_eq!(world.get_entity(pig_id).unwrap().kind(), EntityKind::Pig);
+        assert_eq!(world.get_entity(zombie_id).unwrap().kind(), EntityKind::Zombie);
+
+        // Chunk cache must still be coherent with remaining entities.
+        assert_eq!(world.iter_entities_in_chunk(0, 0).count(), 1);
+        assert_eq!(world.iter_entities_in_chunk(1, 0).count(), 1);
+        assert_eq!(world.remove_entities_matching(|_, _| false, "test"), 0);
+
+        world.tick();
+        assert_eq!(world.get_entity_count(), 2);
+
+    }
+
+    #[test]
+    fn natural_spawn_daylight() {
+
+        /// Count the hostile mobs after many natural spawn attempts at the given time.
+        fn count_mobs(time: u64) -> usize {
+
+            let (mut world, _) = new_flat_world(time);
+            for _ in 0..400 {
+                world.tick_natural_spawn();
+            }
+
+            world.iter_entities()
+                .filter(|(_, entity)| entity.category() == EntityCategory::Mob)
+                .count()
+
+        }
+
+        assert_eq!(count_mobs(6000), 0, "hostile mobs spawned at midday");
+        assert_ne!(count_mobs(18000), 0, "no hostile mob spawned at midnight");
+
+    }
+
+    #[test]
+    fn difficulty() {
+
+        /// Build a flat world at midnight with the given difficulty.
+        fn new_world(difficulty: Difficulty) -> (World, u32) {
+            let (mut world, player_id) = new_flat_world(18000);
+            world.set_difficulty(difficulty);
+            (world, player_id)
+        }
+
+        fn count_mobs(world: &World) -> usize {
+            world.iter_entities()
+                .filter(|(_, entity)| entity.category() == EntityCategory::Mob)
+                .count()
+        }
+
+        // Peaceful removes existing hostile mobs and prevent natural spawning.
+        let (mut world, _) = new_world(Difficulty::Peaceful);
+        world.spawn_entity(crate::entity::Zombie::new_default(DVec3::new(4.5, 64.0, 4.5)));
+        world.spawn_entity(crate::entity::Pig::new_default(DVec3::new(-4.5, 64.0, -4.5)));
+        assert_eq!(count_mobs(&world), 1);
+        world.tick();
+        assert_eq!(count_mobs(&world), 0);
+        assert_eq!(world.iter_entities_of_kind(EntityKind::Pig).count(), 1);
+
+        for _ in 0..400 {
+            world.tick_natural_spawn();
+        }
+        assert_eq!(count_mobs(&world), 0);
+
+        // Hard difficulty scales the zombie damage from 5 to 7.
+        assert_eq!(Difficulty::Easy.scale_mob_damage(5), 2);
+        assert_eq!(Difficulty::Normal.scale_mob_damage(5), 5);
+        assert_eq!(Difficulty::Hard.scale_mob_damage(5), 7);
+
+        let (mut world, player_id) = new_world(Difficulty::Hard);
+        world.spawn_entity(crate::entity::Zombie::new_default(DVec3::new(1.5, 64.0, 0.5)));
+
+        for _ in 0..200 {
+            world.tick();
+            let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(player_id) else { panic!() };
+            if living.health != 20 {
+                assert_eq!(living.health, 13);
+                return;
+            }
+        }
+
+        panic!("zombie did not attack the player");
+
+    }
+
 }
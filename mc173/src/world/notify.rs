@@ -34,14 +34,29 @@ impl World {
     /// Notify a block a the position, the notification origin block id is given.
     pub(super) fn notify_block_unchecked(&mut self, pos: IVec3, id: u8, metadata: u8, origin_id: u8) {
         match id {
-            block::REDSTONE if origin_id != block::REDSTONE => self.notify_redstone(pos),
+            block::REDSTONE => self.notify_redstone_wire(pos, origin_id),
             block::REPEATER |
             block::REPEATER_LIT => self.notify_repeater(pos, id, metadata),
+            block::TORCH => { self.notify_faced(pos, id, block::torch::get_face(metadata)); }
             block::REDSTONE_TORCH |
-            block::REDSTONE_TORCH_LIT => self.notify_redstone_torch(pos, id),
+            block::REDSTONE_TORCH_LIT => self.notify_redstone_torch(pos, id, metadata),
+            block::LADDER => { self.notify_faced(pos, id, block::ladder::get_face(metadata)); }
+            block::BUTTON => { self.notify_faced(pos, id, block::button::get_face(metadata)); }
+            block::LEVER => { self.notify_faced(pos, id, block::lever::get_face(metadata).map(|(face, _)| face)); }
+            // The wall sign face points away from the block it is attached to.
+            block::WALL_SIGN => { self.notify_faced(pos, id, block::sign::get_wall_face(metadata).map(Face::opposite)); }
+            block::RAIL |
+            block::POWERED_RAIL |
+            block::DETECTOR_RAIL |
+            block::WOOD_PRESSURE_PLATE |
+            block::STONE_PRESSURE_PLATE |
+            block::SNOW |
+            block::CAKE |
+            block::SIGN |
+            block::SUGAR_CANES => { self.notify_support(pos, Face::NegY, id); }
             block::DISPENSER => self.notify_dispenser(pos, origin_id),
             block::WATER_MOVING |
-            block::LAVA_MOVING => self.notify_fluid(pos, id, metadata),
+            block::LAVA_MOVING => { self.notify_fluid(pos, id, metadata); }
             block::WATER_STILL |
             block::LAVA_STILL => self.notify_fluid_still(pos, id, metadata),
             block::TRAPDOOR => self.notify_trapdoor(pos, metadata, origin_id),
@@ -54,7 +69,7 @@ impl World {
             block::DEAD_BUSH => self.notify_flower(pos, &[block::SAND]),
             block::WHEAT => self.notify_flower(pos, &[block::FARMLAND]),
             block::RED_MUSHROOM |
-            block::BROWN_MUSHROOM => self.notify_mushroom(pos),
+            block::BROWN_MUSHROOM => { self.notify_support(pos, Face::NegY, id); }
             block::CACTUS => self.notify_cactus(pos),
             block::SAND |
             block::GRAVEL => self.schedule_block_tick(pos, id, 3),
@@ -63,6 +78,7 @@ impl World {
             block::STICKY_PISTON => self.notify_piston(pos, id, metadata),
             block::PISTON_EXT => self.notify_piston_ext(pos, metadata, origin_id),
             block::NOTE_BLOCK => self.notify_note_block(pos, origin_id),
+            block::TNT if is_redstone_block(origin_id) => self.notify_tnt(pos),
             _ => {}
         }
     }
@@ -83,17 +99,18 @@ impl World {
                     self.notify_blocks_around(pos + face.delta(), block::LEVER);
                 }
             }
-            // Remove the chest/dispenser block entity.
+            // Remove the chest/dispenser block entity and drop its contents.
             block::CHEST if to_id != block::CHEST => { 
-                self.remove_block_entity(pos); 
+                self.notify_container_remove(pos); 
             }
             block::DISPENSER if to_id != block::DISPENSER => { 
-                self.remove_block_entity(pos);
+                self.notify_container_remove(pos);
             }
-            // Remove the furnace block entity.
+            // Remove the furnace block entity, the furnace being lit or unlit should not
+            // drop its contents.
             block::FURNACE |
             block::FURNACE_LIT if to_id != block::FURNACE_LIT && to_id != block::FURNACE => {
-                self.remove_block_entity(pos);
+                self.notify_container_remove(pos);
             }
             block::SPAWNER if to_id != block::SPAWNER => {
                 self.remove_block_entity(pos);
@@ -109,16 +126,17 @@ impl World {
 
         match to_id {
             block::WATER_MOVING => self.schedule_block_tick(pos, to_id, 5),
-            block::LAVA_MOVING => self.schedule_block_tick(pos, to_id, 30),
+            block::LAVA_MOVING => self.notify_lava_place(pos, to_metadata),
             block::REDSTONE => self.notify_redstone(pos),
             block::REPEATER |
-            block::REPEATER_LIT => self.notify_repeater(pos, to_id, from_metadata),
+            block::REPEATER_LIT => self.notify_repeater(pos, to_id, to_metadata),
             block::REDSTONE_TORCH |
-            block::REDSTONE_TORCH_LIT => self.notify_redstone_torch(pos, to_id),
+            block::REDSTONE_TORCH_LIT => self.notify_redstone_torch(pos, to_id, to_metadata),
             block::SAND |
             block::GRAVEL => self.schedule_block_tick(pos, to_id, 3),
             block::CACTUS => self.notify_cactus(pos),
             block::FIRE => self.notify_fire_place(pos),
+            block::TNT => self.notify_tnt(pos),
             block::PISTON |
             block::STICKY_PISTON => self.notify_piston(pos, to_id, to_metadata),
             _ => {}
@@ -126,21 +144,41 @@ impl World {
 
     }
 
-    /// Notification of a moving fluid block.
-    fn notify_fluid(&mut self, pos: IVec3, id: u8, metadata: u8) {
-        // If the fluid block is lava, check if we make cobblestone or lava.
-        if id == block::LAVA_MOVING {
-            let distance = block::fluid::get_distance(metadata);
-            for face in Face::HORIZONTAL {
-                if let Some((block::WATER_MOVING | block::WATER_STILL, _)) = self.get_block(pos + face.delta()) {
-                    // If there is at least one water block around.
-                    if distance == 0 {
-                        self.set_block_notify(pos, block::OBSIDIAN, 0);
-                    } else if distance <= 4 {
-                        self.set_block_notify(pos, block::COBBLESTONE, 0);
-                    }
-                }
-            }
+    /// Notification of a moving fluid block. If the fluid block is lava and is in contact
+    /// with water, it turns into obsidian if source or cobblestone if close enough to its
+    /// source. This returns true if the lava has been hardened.
+    fn notify_fluid(&mut self, pos: IVec3, id: u8, metadata: u8) -> bool {
+
+        if id != block::LAVA_MOVING {
+            return false;
+        }
+
+        // NOTE: Lava doesn't harden with water below it.
+        let water_around = [Face::NegZ, Face::PosZ, Face::NegX, Face::PosX, Face::PosY].into_iter()
+            .any(|face| matches!(self.get_block(pos + face.delta()), Some((block::WATER_MOVING | block::WATER_STILL, _))));
+
+        if !water_around {
+            return false;
+        }
+
+        // NOTE: We use raw metadata, so falling lava doesn't harden.
+        if metadata == 0 {
+            self.set_block_notify(pos, block::OBSIDIAN, 0);
+            true
+        } else if metadata <= 4 {
+            self.set_block_notify(pos, block::COBBLESTONE, 0);
+            true
+        } else {
+            false
+        }
+
+    }
+
+    /// Notification of a moving lava block being placed, it may harden immediately if in
+    /// contact with water, or its tick is scheduled.
+    fn notify_lava_place(&mut self, pos: IVec3, metadata: u8) {
+        if !self.notify_fluid(pos, block::LAVA_MOVING, metadata) {
+            self.schedule_block_tick(pos, block::LAVA_MOVING, 30);
         }
     }
 
@@ -150,8 +188,10 @@ impl World {
         // Subtract 1 from id to go from still to moving.
         let moving_id = id - 1;
 
-        self.notify_fluid(pos, moving_id, metadata);
-        self.set_block_self_notify(pos, moving_id, metadata);
+        // The fluid is no longer still if it has not been hardened.
+        if !self.notify_fluid(pos, moving_id, metadata) {
+            self.set_block_self_notify(pos, moving_id, metadata);
+        }
 
     }
 
@@ -163,29 +203,35 @@ impl World {
                 return;
             }
         }
-        self.break_block(pos);
+        self.break_block(pos, true);
     }
 
-    /// Notification of a mushroom block.
-    fn notify_mushroom(&mut self, pos: IVec3) {
-        if self.get_light(pos).max() >= 13 || !self.is_block_opaque_cube(pos - IVec3::Y) {
-            self.break_block(pos);
+    /// Check that the block at the given position is still supported by the blocks
+    /// around it, if not the block is broken. Returns true if the block is supported.
+    fn notify_support(&mut self, pos: IVec3, face: Face, id: u8) -> bool {
+        if self.is_block_supported(pos, face, id) {
+            true
+        } else {
+            self.break_block(pos, true);
+            false
         }
     }
 
-    /// Notification of a cactus block. The block is broken if 
-    fn notify_cactus(&mut self, pos: IVec3) {
-        for face in Face::HORIZONTAL {
-            if self.is_block_solid(pos + face.delta()) {
-                self.break_block(pos);
-                return;
-            }
-        }
-        if !matches!(self.get_block(pos - IVec3::Y), Some((block::CACTUS | block::SAND, _))) {
-            self.break_block(pos);
+    /// Notification of a block attached to another block on the given face, such as
+    /// ladders, buttons, levers or wall signs. Returns true if the block is still there.
+    fn notify_faced(&mut self, pos: IVec3, id: u8, face: Option<Face>) -> bool {
+        match face {
+            Some(face) => self.notify_support(pos, face, id),
+            None => true,
         }
     }
 
+    /// Notification of a cactus block. The block is broken if any solid block is around
+    /// or if it's not on sand or another cactus.
+    fn notify_cactus(&mut self, pos: IVec3) {
+        self.notify_support(pos, Face::NegY, block::CACTUS);
+    }
+
     /// Notification of a fire block, the fire block is removed if the block below is no
     /// longer a normal cube wall blocks cannot catch fire.
     /// 
@@ -280,6 +326,10 @@ impl World {
     /// Notification of a redstone repeater block.
     fn notify_repeater(&mut self, pos: IVec3, id: u8, metadata: u8) {
 
+        if !self.notify_support(pos, Face::NegY, id) {
+            return;
+        }
+
         let lit = id == block::REPEATER_LIT;
         let face = block::repeater::get_face(metadata);
         let delay = block::repeater::get_delay_ticks(metadata);
@@ -292,8 +342,18 @@ impl World {
     }
 
     /// Notification of a redstone repeater block.
-    fn notify_redstone_torch(&mut self, pos: IVec3, id: u8) {
-        self.schedule_block_tick(pos, id, 2);
+    fn notify_redstone_torch(&mut self, pos: IVec3, id: u8, metadata: u8) {
+        if self.notify_faced(pos, id, block::torch::get_face(metadata)) {
+            self.schedule_block_tick(pos, id, 2);
+        }
+    }
+
+    /// Notification of a redstone wire block, the wire is broken if the block below is
+    /// no longer a normal cube, the power is not updated if notified by another wire.
+    fn notify_redstone_wire(&mut self, pos: IVec3, origin_id: u8) {
+        if self.notify_support(pos, Face::NegY, block::REDSTONE) && origin_id != block::REDSTONE {
+            self.notify_redstone(pos);
+        }
     }
 
     fn notify_dispenser(&mut self, pos: IVec3, origin_id: u8) {
@@ -310,7 +370,7 @@ impl World {
     fn notify_trapdoor(&mut self, pos: IVec3, mut metadata: u8, origin_id: u8) {
         let face = block::trapdoor::get_face(metadata);
         if !self.is_block_opaque_cube(pos + face.delta()) {
-            self.break_block(pos);
+            self.break_block(pos, true);
         } else {
             let open = block::trapdoor::is_open(metadata);
             if is_redstone_block(origin_id) {
@@ -347,7 +407,7 @@ impl World {
             // If the block above is not the same door block, naturally break itself.
             if let Some((above_id, _)) = self.get_block(pos + IVec3::Y) {
                 if above_id != id {
-                    self.break_block(pos);
+                    self.break_block(pos, true);
                     return;
                 }
             }
@@ -355,7 +415,7 @@ impl World {
             // Also check that door can stay in place.
             if !self.is_block_opaque_cube(pos - IVec3::Y) {
                 // NOTE: This will notify the upper part and destroy it.
-                self.break_block(pos);
+                self.break_block(pos, true);
                 return;
             }
 
@@ -576,7 +636,7 @@ impl World {
             // extension has been removed.
             let head_pos = pos + delta;
             if !self.is_block(head_pos, block::PISTON_EXT) {
-                self.break_block(pos);
+                self.break_block(pos, true);
             }
 
         }
@@ -624,6 +684,27 @@ impl World {
 
     }
 
+    /// Notification of a removed container block (chest, furnace or dispenser), its
+    /// block entity is removed and all of its stacks are scattered around.
+    /// 
+    /// REF: BlockChest::onBlockRemoval, BlockFurnace::onBlockRemoval, 
+    /// BlockDispenser::onBlockRemoval
+    fn notify_container_remove(&mut self, pos: IVec3) {
+        if let Some(block_entity) = self.remove_block_entity_inner(pos, true).and_then(|comp| comp.inner) {
+            self.spawn_block_entity_loot(pos, &block_entity);
+        }
+    }
+
+    /// Notification of a TNT block, it is ignited if powered by redstone.
+    /// 
+    /// REF: BlockTNT::onNeighborBlockChange, BlockTNT::onBlockAdded
+    fn notify_tnt(&mut self, pos: IVec3) {
+        if self.has_passive_power(pos) {
+            self.spawn_primed_tnt(pos, 80);
+            self.set_block_notify(pos, block::AIR, 0);
+        }
+    }
+
     /// Notify a redstone dust block. This function is a bit special because this 
     /// notification in itself will trigger other notifications for all updated blocks.
     /// The redstone update in the 
@@ -881,3 +962,118 @@ fn is_redstone_block(id: u8) -> bool {
         _ => false,
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::test::new_floor_world;
+    use glam::DVec3;
+
+    use crate::entity::{Entity, BaseKind};
+    use crate::item::ItemStack;
+    use crate::chunk::Chunk;
+    use crate::world::Dimension;
+
+    use super::*;
+
+    #[test]
+    fn torch_support() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let support_pos = IVec3::new(8, 64, 8);
+        let torch_pos = support_pos + IVec3::Y;
+        let wall_torch_pos = support_pos + IVec3::X;
+
+        world.set_block(support_pos, block::STONE, 0);
+        world.place_block(torch_pos, Face::NegY, block::TORCH, 0);
+        world.place_block(wall_torch_pos, Face::NegX, block::TORCH, 0);
+        
+        // Notifying torches with their support doesn't break them.
+        world.notify_blocks_around(support_pos, block::STONE);
+        assert!(world.is_block(torch_pos, block::TORCH));
+        assert!(world.is_block(wall_torch_pos, block::TORCH));
+
+        world.set_block_notify(support_pos, block::AIR, 0);
+        assert!(world.is_block(torch_pos, block::AIR));
+        assert!(world.is_block(wall_torch_pos, block::AIR));
+
+        // Both torches should have popped off as items.
+        let torch_stack = ItemStack::new_block(block::TORCH, 0);
+        let items = world.iter_entities()
+            .filter_map(|(_, entity)| match entity {
+                Entity(base, BaseKind::Item(item)) => Some((base.pos, item.stack)),
+                _ => None
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|&(_, stack)| stack == torch_stack));
+        assert!(items.iter().all(|&(pos, _)| pos.distance(DVec3::new(9.0, 65.0, 8.5)) < 2.0));
+
+    }
+
+    #[test]
+    fn tnt_redstone() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let tnt_pos = IVec3::new(8, 64, 8);
+        let torch_pos = tnt_pos + IVec3::X;
+        world.set_block(tnt_pos - IVec3::Y, block::STONE, 0);
+        world.set_block(torch_pos - IVec3::Y, block::STONE, 0);
+        world.set_block(tnt_pos, block::TNT, 0);
+
+        world.place_block(torch_pos, Face::NegY, block::REDSTONE_TORCH_LIT, 0);
+        assert!(world.is_block(tnt_pos, block::AIR));
+
+        let tnt = world.iter_entities()
+            .find_map(|(_, entity)| match entity {
+                Entity(base, BaseKind::Tnt(tnt)) => Some((base.pos, tnt.fuse_time)),
+                _ => None
+            });
+        assert_eq!(tnt, Some((tnt_pos.as_dvec3() + 0.5, 80)));
+
+        // TNT placed next to a powered torch is directly ignited.
+        world.set_block_notify(torch_pos + IVec3::X, block::TNT, 0);
+        assert!(world.is_block(torch_pos + IVec3::X, block::AIR));
+        assert_eq!(world.iter_entities().filter(|(_, entity)| matches!(entity, Entity(_, BaseKind::Tnt(_)))).count(), 2);
+
+    }
+
+    #[test]
+    fn lava_harden() {
+
+        let mut world = new_floor_world(block::STONE);
+
+        // Water source placed next to a lava source makes obsidian.
+        let pos = IVec3::new(4, 64, 4);
+        world.set_block(pos, block::LAVA_STILL, 0);
+        world.set_block_notify(pos + IVec3::X, block::WATER_MOVING, 0);
+        assert_eq!(world.get_block(pos), Some((block::OBSIDIAN, 0)));
+
+        // Flowing lava close to its source makes cobblestone.
+        let pos = IVec3::new(8, 64, 4);
+        world.set_block(pos, block::LAVA_STILL, 2);
+        world.set_block_notify(pos + IVec3::Z, block::WATER_MOVING, 0);
+        assert_eq!(world.get_block(pos), Some((block::COBBLESTONE, 0)));
+
+        // Lava flowing down into water makes stone.
+        let pos = IVec3::new(12, 65, 12);
+        world.set_block(pos - IVec3::Y, block::WATER_STILL, 0);
+        world.set_block_notify(pos, block::LAVA_MOVING, 0);
+        assert_eq!(world.get_block(pos), Some((block::LAVA_MOVING, 0)));
+
+        // Lava is ticked 30 ticks after being placed.
+        for _ in 0..31 {
+            world.tick();
+        }
+
+        assert_eq!(world.get_block(pos - IVec3::Y), Some((block::STONE, 0)));
+
+    }
+
+}
@@ -19,15 +19,42 @@ impl World {
     pub fn can_place_block(&mut self, pos: IVec3, face: Face, id: u8) -> bool {
         
         let base = match id {
+            block::PISTON_EXT |
+            block::PISTON_MOVING => false,
+            block::LADDER => self.is_block_opaque_around(pos),
+            block::CHEST => self.can_place_chest(pos),
+            block::WOOD_DOOR |
+            block::IRON_DOOR => self.can_place_door(pos),
+            block::FIRE => self.can_place_fire(pos),
+            _ => self.is_block_supported(pos, face, id),
+        };
+
+        // If the block we are placing has an exclusion box and any hard entity is inside,
+        // we cancel the prevent the placing.
+        if let Some(bb) = self.get_block_exclusion_box(pos, id) {
+            if self.has_entity_colliding(bb, true) {
+                return false;
+            }
+        }
+
+        base && self.is_block_replaceable(pos)
+
+    }
+
+    /// This function checks if the given block id, oriented toward the given face, is 
+    /// supported by the blocks around the given position. This is used when placing the
+    /// block, but also when notified to check if the block should be broken. For blocks
+    /// attached to another block, the face points toward the supporting block.
+    pub fn is_block_supported(&mut self, pos: IVec3, face: Face, id: u8) -> bool {
+        match id {
             block::BUTTON if face.is_y() => false,
             block::BUTTON => self.is_block_opaque_cube(pos + face.delta()),
             block::LEVER if face == Face::PosY => false,
             block::LEVER => self.is_block_opaque_cube(pos + face.delta()),
-            block::LADDER => self.is_block_opaque_around(pos),
+            block::LADDER if face.is_y() => false,
+            block::LADDER => self.is_block_opaque_cube(pos + face.delta()),
             block::TRAPDOOR if face.is_y() => false,
             block::TRAPDOOR => self.is_block_opaque_cube(pos + face.delta()),
-            block::PISTON_EXT |
-            block::PISTON_MOVING => false,
             block::DEAD_BUSH => matches!(self.get_block(pos - IVec3::Y), Some((block::SAND, _))),
             // PARITY: Notchian impl checks block light >= 8 or see sky
             block::DANDELION |
@@ -37,18 +64,21 @@ impl World {
             block::WHEAT => matches!(self.get_block(pos - IVec3::Y), Some((block::FARMLAND, _))),
             block::CACTUS => self.can_place_cactus(pos),
             block::SUGAR_CANES => self.can_place_sugar_canes(pos),
-            block::CAKE => self.is_block_solid(pos - IVec3::Y),
-            block::CHEST => self.can_place_chest(pos),
-            block::WOOD_DOOR |
-            block::IRON_DOOR => self.can_place_door(pos),
+            block::CAKE |
+            block::SIGN => self.is_block_solid(pos - IVec3::Y),
+            block::WALL_SIGN if face.is_y() => false,
+            block::WALL_SIGN => self.is_block_solid(pos + face.delta()),
             block::FENCE => matches!(self.get_block(pos - IVec3::Y), Some((block::FENCE, _))) || self.is_block_solid(pos - IVec3::Y),
-            block::FIRE => self.can_place_fire(pos),
+            block::TORCH |
+            block::REDSTONE_TORCH |
+            block::REDSTONE_TORCH_LIT if face == Face::NegY && self.is_block(pos - IVec3::Y, block::FENCE) => true,
             block::TORCH |
             block::REDSTONE_TORCH |
             block::REDSTONE_TORCH_LIT => self.is_block_normal_cube(pos + face.delta()),
+            // Mushrooms need darkness and opaque block below.
+            block::RED_MUSHROOM |
+            block::BROWN_MUSHROOM => self.get_light(pos).max() < 13 && self.is_block_opaque_cube(pos - IVec3::Y),
             // Common blocks that needs opaque block below.
-            block::RED_MUSHROOM |        // PARITY: Notchian impl checks block light >= 8 or see sky
-            block::BROWN_MUSHROOM => self.is_block_opaque_cube(pos - IVec3::Y),
             block::SNOW => self.is_block_opaque_cube(pos - IVec3::Y),
             block::WOOD_PRESSURE_PLATE |
             block::STONE_PRESSURE_PLATE |
@@ -61,18 +91,7 @@ impl World {
             block::REPEATER_LIT |
             block::REDSTONE => self.is_block_normal_cube(pos - IVec3::Y),
             _ => true,
-        };
-
-        // If the block we are placing has an exclusion box and any hard entity is inside,
-        // we cancel the prevent the placing.
-        if let Some(bb) = self.get_block_exclusion_box(pos, id) {
-            if self.has_entity_colliding(bb, true) {
-                return false;
-            }
         }
-
-        base && self.is_block_replaceable(pos)
-
     }
 
     fn can_place_cactus(&mut self, pos: IVec3) -> bool {
@@ -165,27 +184,36 @@ impl World {
             block::REDSTONE_TORCH_LIT => self.place_faced(pos, face, id, metadata, block::torch::set_face),
             block::LEVER => self.place_lever(pos, face, metadata),
             block::LADDER => self.place_ladder(pos, face, metadata),
+            _ => self.place_block_entity(pos, id, metadata),
+        }
+
+    }
+
+    /// Set the block and notify around, if the block has a block entity, it is set 
+    /// together with the block.
+    fn place_block_entity(&mut self, pos: IVec3, id: u8, metadata: u8) {
+        
+        let block_entity = match id {
+            block::CHEST => BlockEntity::Chest(def()),
+            block::FURNACE => BlockEntity::Furnace(def()),
+            block::DISPENSER => BlockEntity::Dispenser(def()),
+            block::SPAWNER => BlockEntity::Spawner(def()),
+            block::NOTE_BLOCK => BlockEntity::NoteBlock(def()),
+            block::JUKEBOX => BlockEntity::Jukebox(def()),
             _ => {
                 self.set_block_notify(pos, id, metadata);
+                return;
             }
-        }
+        };
 
-        match id {
-            block::CHEST => self.set_block_entity(pos, BlockEntity::Chest(def())),
-            block::FURNACE => self.set_block_entity(pos, BlockEntity::Furnace(def())),
-            block::DISPENSER => self.set_block_entity(pos, BlockEntity::Dispenser(def())),
-            block::SPAWNER => self.set_block_entity(pos, BlockEntity::Spawner(def())),
-            block::NOTE_BLOCK => self.set_block_entity(pos, BlockEntity::NoteBlock(def())),
-            block::JUKEBOX => self.set_block_entity(pos, BlockEntity::Jukebox(def())),
-            _ => {}
-        }
+        self.set_block_entity_notify(pos, id, metadata, block_entity);
 
     }
 
     /// Generic function to place a block that has a basic facing function.
     fn place_faced(&mut self, pos: IVec3, face: Face, id: u8, mut metadata: u8, func: impl FnOnce(&mut u8, Face)) {
         func(&mut metadata, face);
-        self.set_block_notify(pos, id, metadata);
+        self.place_block_entity(pos, id, metadata);
     }
 
     fn place_lever(&mut self, pos: IVec3, face: Face, mut metadata: u8) {
@@ -223,3 +251,49 @@ impl World {
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::chunk::Chunk;
+    use crate::world::Dimension;
+
+    use super::*;
+
+    #[test]
+    fn can_place() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let stone_pos = IVec3::new(4, 64, 4);
+        let glass_pos = IVec3::new(8, 64, 8);
+        let farmland_pos = IVec3::new(12, 64, 12);
+        let dirt_pos = IVec3::new(12, 64, 4);
+
+        world.set_block(stone_pos, block::STONE, 0);
+        world.set_block(glass_pos, block::GLASS, 0);
+        world.set_block(farmland_pos, block::FARMLAND, 0);
+        world.set_block(dirt_pos, block::DIRT, 0);
+
+        // Torches need a normal cube to be attached to.
+        assert!(world.can_place_block(stone_pos + IVec3::Y, Face::NegY, block::TORCH));
+        assert!(world.can_place_block(stone_pos + IVec3::X, Face::NegX, block::TORCH));
+        assert!(!world.can_place_block(glass_pos + IVec3::X, Face::NegX, block::TORCH));
+        assert!(!world.can_place_block(stone_pos + IVec3::Y * 2, Face::NegY, block::TORCH));
+
+        // Wheat can only be placed on farmland.
+        assert!(world.can_place_block(farmland_pos + IVec3::Y, Face::NegY, block::WHEAT));
+        assert!(!world.can_place_block(dirt_pos + IVec3::Y, Face::NegY, block::WHEAT));
+        assert!(!world.can_place_block(stone_pos + IVec3::Y, Face::NegY, block::WHEAT));
+
+        // Support rules are shared with notifications.
+        world.set_block(farmland_pos + IVec3::Y, block::WHEAT, 0);
+        assert!(world.is_block_supported(farmland_pos + IVec3::Y, Face::NegY, block::WHEAT));
+        world.set_block(farmland_pos, block::DIRT, 0);
+        assert!(!world.is_block_supported(farmland_pos + IVec3::Y, Face::NegY, block::WHEAT));
+
+    }
+
+}
@@ -15,6 +15,11 @@ use crate::{block, item};
 use super::{World, Dimension, Event, BlockEntityEvent, BlockEntityStorage, LocalWeather};
 
 
+/// Number of ticks a redstone torch toggle is remembered for burning out.
+const REDSTONE_TORCH_BURNOUT_TIME: u64 = 100;
+/// Number of toggles within the burnout time that are needed to burn out a torch.
+const REDSTONE_TORCH_BURNOUT_COUNT: usize = 8;
+
 /// Methods related to block scheduled ticking and random ticking.
 impl World {
 
@@ -39,7 +44,7 @@ impl World {
             block::CAKE => {}, // Seems unused in MC
             block::WHEAT => self.tick_wheat(pos, metadata),
             block::DETECTOR_RAIL => {},
-            block::FARMLAND => {},
+            block::FARMLAND => self.tick_farmland(pos, metadata),
             block::FIRE => self.tick_fire(pos, metadata),
             // PARITY: Notchian client check if flowers can stay, we intentionally don't
             // respect that to allow glitched plants to stay.
@@ -94,25 +99,43 @@ impl World {
 
     }
 
+    /// REF: BlockRedstoneTorch::updateTick
     fn tick_redstone_torch(&mut self, pos: IVec3, metadata: u8, lit: bool) {
 
-        // TODO: Check torch burnout...
-
         let Some(torch_face) = block::torch::get_face(metadata) else { return };
         let powered = self.has_passive_power_from(pos + torch_face.delta(), torch_face.opposite());
 
+        // Forget about toggles that are too old to burn out a torch.
+        while let Some(&(_, time)) = self.redstone_torch_toggles.front() {
+            if self.time.saturating_sub(time) > REDSTONE_TORCH_BURNOUT_TIME {
+                self.redstone_torch_toggles.pop_front();
+            } else {
+                break;
+            }
+        }
+
         if lit {
             if powered {
                 self.set_block_notify(pos, block::REDSTONE_TORCH, metadata);
+                self.redstone_torch_toggles.push_back((pos, self.time));
             }
-        } else {
-            if !powered {
-                self.set_block_notify(pos, block::REDSTONE_TORCH_LIT, metadata);
-            }
+        } else if !powered && !self.is_redstone_torch_burnt_out(pos) {
+            self.set_block_notify(pos, block::REDSTONE_TORCH_LIT, metadata);
         }
 
     }
 
+    /// Return true if the redstone torch at the given position has been toggled too many
+    /// times recently, in such case the torch can't be lit again until older toggles
+    /// are forgotten. This prevents infinite fast clocks.
+    /// 
+    /// REF: BlockRedstoneTorch::checkForBurnout
+    pub fn is_redstone_torch_burnt_out(&self, pos: IVec3) -> bool {
+        self.redstone_torch_toggles.iter()
+            .filter(|&&(toggle_pos, _)| toggle_pos == pos)
+            .count() >= REDSTONE_TORCH_BURNOUT_COUNT
+    }
+
     fn tick_dispenser(&mut self, pos: IVec3, metadata: u8) {
 
         let Some(face) = block::dispenser::get_face(metadata) else { return };
@@ -202,6 +225,30 @@ impl World {
 
     }
 
+    /// Tick a farmland, hydrate it if water is nearby or if it's raining on it, else 
+    /// dry it and eventually turn it back to dirt if no crop is planted on it.
+    fn tick_farmland(&mut self, pos: IVec3, metadata: u8) {
+
+        if self.rand.next_int_bounded(5) != 0 {
+            return;
+        }
+
+        let wet = self.get_local_weather(pos + IVec3::Y) == LocalWeather::Rain ||
+            self.iter_blocks_in(pos - IVec3::new(4, 0, 4), pos + IVec3::new(5, 2, 5))
+                .any(|(_, id, _)| block::material::get_material(id) == Material::Water);
+
+        if wet {
+            if metadata != 7 {
+                self.set_block_notify(pos, block::FARMLAND, 7);
+            }
+        } else if metadata > 0 {
+            self.set_block_notify(pos, block::FARMLAND, metadata - 1);
+        } else if !matches!(self.get_block(pos + IVec3::Y), Some((block::WHEAT, _))) {
+            self.set_block_notify(pos, block::DIRT, 0);
+        }
+
+    }
+
     /// Tick a wheat crop, grow it if possible.
     fn tick_wheat(&mut self, pos: IVec3, metadata: u8) {
 
@@ -274,11 +321,14 @@ impl World {
 
         let below_netherrack = face_block(Face::NegY) == block::NETHERRACK;
         
-        // Fire can stay only if we are on netherrack, or there is no rain around.
+        // Fire can stay only if we are on netherrack, or there is no rain on it or 
+        // around it.
         let can_stay = 
-            below_netherrack ||
-            Face::HORIZONTAL.into_iter()
-                .all(|face| self.get_local_weather(pos + face.delta()) != LocalWeather::Rain);
+            below_netherrack || (
+                self.get_local_weather(pos) != LocalWeather::Rain &&
+                Face::HORIZONTAL.into_iter()
+                    .all(|face| self.get_local_weather(pos + face.delta()) != LocalWeather::Rain)
+            );
         
         if !can_stay {
             self.set_block_notify(pos, block::AIR, 0);
@@ -332,6 +382,10 @@ impl World {
                     } else {
                         self.set_block_notify(face_pos, block::AIR, 0);
                     }
+                    // Burning TNT is ignited.
+                    if face_id == block::TNT {
+                        self.spawn_primed_tnt(face_pos, 80);
+                    }
                 }
 
             }
@@ -386,6 +440,10 @@ impl World {
 
     /// Tick a mushroom to try spreading it.
     fn tick_mushroom(&mut self, pos: IVec3, id: u8) {
+
+        /// Maximum number of mushrooms of the same kind around before spreading stops.
+        const MAX_DENSITY: usize = 5;
+
         if self.rand.next_int_bounded(100) == 0 {
 
             let spread_pos = pos + IVec3 {
@@ -394,15 +452,22 @@ impl World {
                 z: self.rand.next_int_bounded(3) - 1,
             };
 
-            if self.get_light(spread_pos).max() < 13 {
-                if self.is_block_air(spread_pos) {
-                    if self.is_block_opaque_cube(spread_pos - IVec3::Y) {
-                        self.set_block_notify(spread_pos, id, 0);
-                    }
-                }
+            if !self.is_block_air(spread_pos) || !self.is_block_supported(spread_pos, Face::NegY, id) {
+                return;
+            }
+
+            // PARITY: Notchian beta implementation doesn't limit the mushroom density, 
+            // this has been added in later versions to avoid mushrooms filling caves.
+            let density = self.iter_blocks_in(pos - IVec3::new(4, 1, 4), pos + IVec3::new(5, 2, 5))
+                .filter(|&(_, block, _)| block == id)
+                .count();
+
+            if density < MAX_DENSITY {
+                self.set_block_notify(spread_pos, id, 0);
             }
 
         }
+
     }
 
     /// Tick a sapling to grow it.
@@ -536,6 +601,12 @@ impl World {
         // Check if we can flow below.
         let blocked_below = block::material::is_fluid_proof(below_id);
 
+        // Lava flowing into water below turns the water into stone.
+        if flowing_id == block::LAVA_MOVING && block::material::get_material(below_id) == Material::Water {
+            self.set_block_notify(below_pos, block::STONE, 0);
+            return;
+        }
+
         if !block::material::is_fluid(below_id) && !blocked_below {
             // The block below is not a fluid block and do not block fluids, the fluid 
             // below is set to a falling version of the current block.
@@ -558,7 +629,7 @@ impl World {
                     if let Some((face_id, _)) = self.get_block(face_pos) {
                         if !block::material::is_fluid(face_id) && !block::material::is_fluid_proof(face_id) {
                             // TODO: Break only for water.
-                            self.break_block(face_pos);
+                            self.break_block(face_pos, true);
                             self.set_block_notify(face_pos, flowing_id, new_dist);
                         }
                     }
@@ -651,3 +722,198 @@ impl World {
     }
 
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::test::new_floor_world;
+    use std::sync::Arc;
+
+    use crate::chunk::Chunk;
+
+    use super::*;
+    use crate::world::Weather;
+
+    #[test]
+    fn redstone_torch_burnout() {
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, Chunk::new());
+
+        let stone_pos = IVec3::new(8, 64, 8);
+        let lever_pos = stone_pos + IVec3::Y;
+        let torch_pos = stone_pos + IVec3::X;
+
+        world.set_block(stone_pos, block::STONE, 0);
+        world.set_block_notify(torch_pos, block::REDSTONE_TORCH_LIT, 1);
+        world.set_block_notify(lever_pos, block::LEVER, 5);
+
+        let mut toggles = 0;
+        for _ in 0..12 {
+
+            world.set_block_notify(lever_pos, block::LEVER, 5 | 8);
+            for _ in 0..3 {
+                world.tick();
+            }
+            assert!(world.is_block(torch_pos, block::REDSTONE_TORCH));
+            toggles += 1;
+
+            world.set_block_notify(lever_pos, block::LEVER, 5);
+            for _ in 0..3 {
+                world.tick();
+            }
+
+            if toggles < 8 {
+                assert!(world.is_block(torch_pos, block::REDSTONE_TORCH_LIT), "torch burnt out too early");
+            } else {
+                assert!(world.is_block(torch_pos, block::REDSTONE_TORCH), "torch should be burnt out");
+                assert!(world.is_redstone_torch_burnt_out(torch_pos));
+                break;
+            }
+
+        }
+
+        assert_eq!(toggles, 8);
+
+        // After a while, the torch can be lit again when notified.
+        for _ in 0..100 {
+            world.tick();
+        }
+
+        world.notify_block(torch_pos, block::LEVER);
+        for _ in 0..3 {
+            world.tick();
+        }
+        assert!(world.is_block(torch_pos, block::REDSTONE_TORCH_LIT));
+
+        // Moving the clock backward should not break toggles tracking.
+        world.set_block_notify(lever_pos, block::LEVER, 5 | 8);
+        for _ in 0..3 {
+            world.tick();
+        }
+        world.set_time(0);
+        world.set_block_notify(lever_pos, block::LEVER, 5);
+        for _ in 0..3 {
+            world.tick();
+        }
+        assert!(world.is_block(torch_pos, block::REDSTONE_TORCH_LIT));
+
+    }
+
+    #[test]
+    fn repeater_clock() {
+
+        let mut chunk = Chunk::new();
+        Arc::make_mut(&mut chunk).fill_block(IVec3::new(0, 63, 0), IVec3::new(16, 1, 16), block::STONE, 0);
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, chunk);
+
+        // Two repeaters of 2 ticks (delay 1) in a loop, connected with redstone wires.
+        let r0_pos = IVec3::new(4, 64, 4);
+        let r1_pos = IVec3::new(4, 64, 5);
+        for pos in [IVec3::new(5, 64, 4), IVec3::new(5, 64, 5), IVec3::new(3, 64, 5), IVec3::new(3, 64, 4)] {
+            world.set_block_notify(pos, block::REDSTONE, 0);
+        }
+        world.set_block_notify(r1_pos, block::REPEATER, 3 | (1 << 2));
+        
+        // Placing the first repeater lit injects a pulse in the loop.
+        world.set_block_notify(r0_pos, block::REPEATER_LIT, 1 | (1 << 2));
+
+        let mut prev_lit = true;
+        let mut toggles = Vec::new();
+        for _ in 0..200 {
+            world.tick();
+            let lit = world.is_block(r0_pos, block::REPEATER_LIT);
+            if lit != prev_lit {
+                toggles.push(world.get_time());
+                prev_lit = lit;
+            }
+        }
+
+        // Each repeater delays the pulse by 4 ticks, so the first repeater is toggled 
+        // every 4 ticks, for a period of 8 ticks.
+        assert_eq!(toggles.len(), 50);
+        assert_eq!(toggles[0], 4);
+        assert!(toggles.windows(2).all(|w| w[1] - w[0] == 4), "unstable clock: {toggles:?}");
+
+    }
+
+    #[test]
+    fn random_tick_filter() {
+        assert!(!block::random_tick_enabled(block::AIR));
+        assert!(!block::random_tick_enabled(block::STONE));
+        assert!(!block::random_tick_enabled(block::BUTTON));
+        assert!(block::random_tick_enabled(block::WHEAT));
+        assert!(block::random_tick_enabled(block::SAPLING));
+        assert!(block::random_tick_enabled(block::FIRE));
+    }
+
+    #[test]
+    fn rain_extinguish_fire() {
+
+        let mut chunk = Chunk::new();
+        let chunk_mut = Arc::make_mut(&mut chunk);
+        chunk_mut.fill_block(IVec3::new(0, 63, 0), IVec3::new(16, 2, 16), block::STONE, 0);
+        chunk_mut.set_block(IVec3::new(8, 64, 8), block::AIR, 0);
+        chunk_mut.recompute_all_height();
+
+        let mut world = World::new(Dimension::Overworld);
+        world.set_chunk(0, 0, chunk);
+
+        // The fire is in a hole, so only its own position is exposed to rain.
+        let pos = IVec3::new(8, 64, 8);
+        world.set_block(pos, block::FIRE, 0);
+        world.set_weather(Weather::Rain);
+
+        world.tick_block_unchecked(pos, block::FIRE, 0, false);
+        assert_eq!(world.get_block(pos), Some((block::AIR, 0)));
+
+    }
+
+    #[test]
+    fn mushroom_spread() {
+
+        // Mushrooms only spread in the dark.
+        let mut world = new_floor_world(block::STONE);
+        world.get_chunk_mut(0, 0).unwrap().fill_light(IVec3::new(0, 64, 0), IVec3::new(16, 3, 16), 0, 0);
+
+        let pos = IVec3::new(8, 64, 8);
+        assert!(world.can_place_block(pos, Face::NegY, block::BROWN_MUSHROOM));
+        world.set_block(pos, block::BROWN_MUSHROOM, 0);
+
+        let count = |world: &World| world.iter_blocks_in(IVec3::new(0, 63, 0), IVec3::new(16, 67, 16))
+            .filter(|&(_, id, _)| id == block::BROWN_MUSHROOM)
+            .count();
+
+        for _ in 0..100000 {
+            world.tick_block_unchecked(pos, block::BROWN_MUSHROOM, 0, true);
+            if count(&world) > 1 {
+                break;
+            }
+        }
+
+        // The new mushroom must be adjacent and on the ground.
+        let (new_pos, _, _) = world.iter_blocks_in(IVec3::new(0, 63, 0), IVec3::new(16, 67, 16))
+            .find(|&(other_pos, id, _)| id == block::BROWN_MUSHROOM && other_pos != pos)
+            .expect("mushroom should have spread");
+        
+        assert_eq!(new_pos.y, 64);
+        assert!((new_pos - pos).abs().max_element() <= 1);
+
+        // Spreading is capped by the density of mushrooms around.
+        for _ in 0..100000 {
+            world.tick_block_unchecked(pos, block::BROWN_MUSHROOM, 0, true);
+        }
+
+        assert_eq!(count(&world), 5);
+
+        // Mushrooms cannot be placed in the light.
+        let light_pos = IVec3::new(8, 64, 0);
+        world.get_chunk_mut(0, 0).unwrap().set_sky_light(light_pos, 15);
+        assert!(!world.can_place_block(light_pos, Face::NegY, block::BROWN_MUSHROOM));
+
+    }
+
+}
@@ -3,7 +3,7 @@
 use glam::{IVec3, DVec3, Vec3};
 
 use crate::block_entity::BlockEntity;
-use crate::entity::{Arrow, BaseKind, Bobber, Entity, EntityKind, Item, Painting, PaintingArt, ProjectileKind, Snowball, Tnt};
+use crate::entity::{Arrow, BaseKind, Bobber, Entity, EntityKind, Item, Painting, PaintingArt, ProjectileKind, Snowball};
 use crate::inventory::InventoryHandle;
 use crate::gen::tree::TreeGenerator;
 use crate::block::sapling::TreeKind;
@@ -12,7 +12,7 @@ use crate::util::default as def;
 use crate::geom::Face;
 use crate::block;
 
-use super::World;
+use super::{World, Event, BlockEvent, GroupSoundKind};
 use super::bound::RayTraceKind;
 
 
@@ -74,7 +74,11 @@ impl World {
             item::BOW => self.use_bow_stack(inv, index, entity_id),
             item::SNOWBALL => self.use_snowball_stack(inv, index, entity_id),
             item::FISHING_ROD => self.use_fishing_rod_stack(inv, index, entity_id),
-            _ => ()
+            _ => {
+                if let Some(heal) = item::food::get_heal(stack.id) {
+                    self.use_food_stack(inv, index, entity_id, heal);
+                }
+            }
         }
 
     }
@@ -120,6 +124,13 @@ impl World {
         }
 
         self.place_block(pos, face, id, metadata);
+
+        // REF: ItemBlock::onItemUse
+        self.push_event(Event::Block {
+            pos,
+            inner: BlockEvent::GroupSound { group: block::sound_group(id), kind: GroupSoundKind::Place },
+        });
+
         true
 
     }
@@ -241,14 +252,13 @@ impl World {
             let look = self.get_entity(entity_id).unwrap().0.look;
             let mut metadata = 0;
             block::sign::set_post_yaw(&mut metadata, look.x + std::f32::consts::PI);
-            self.set_block(pos, block::SIGN, metadata);
+            self.set_block_entity_notify(pos, block::SIGN, metadata, BlockEntity::Sign(def()));
         } else {
             let mut metadata = 0;
             block::sign::set_wall_face(&mut metadata, face);
-            self.set_block(pos, block::WALL_SIGN, metadata);
+            self.set_block_entity_notify(pos, block::WALL_SIGN, metadata, BlockEntity::Sign(def()));
         }
 
-        self.set_block_entity(pos, BlockEntity::Sign(def()));
         true
 
     }
@@ -308,10 +318,7 @@ impl World {
     fn use_flint_and_steel(&mut self, pos: IVec3, face: Face) -> bool {
 
         if self.is_block(pos, block::TNT) {
-            self.spawn_entity(Tnt::new_with(|new_base, new_tnt| {
-                new_base.pos = pos.as_dvec3() + 0.5;
-                new_tnt.fuse_time = 80;
-            }));
+            self.spawn_primed_tnt(pos, 80);
             self.set_block_notify(pos, block::AIR, 0);
         } else {
             let fire_pos = pos + face.delta();
@@ -622,4 +629,139 @@ impl World {
 
     }
 
+    fn use_food_stack(&mut self, inv: &mut InventoryHandle, index: usize, entity_id: u32, heal: u16) {
+
+        self.heal_entity(entity_id, heal);
+
+        let stack = inv.get(index);
+        if stack.id == item::MUSHROOM_STEW {
+            inv.set(index, ItemStack::new_single(item::BOWL, 0));
+        } else {
+            inv.set(index, stack.inc_damage(1));
+        }
+
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::test::new_floor_world;
+    use crate::entity::Human;
+
+    use super::*;
+
+    /// Create a world with a stone floor at y=63 and a human standing on it and looking
+    /// down, returning the world and the human entity id.
+    fn new_world() -> (World, u32) {
+
+        let mut world = new_floor_world(block::STONE);
+
+        let entity_id = world.spawn_entity(Human::new_with(|base, _, _| {
+            base.pos = DVec3::new(8.5, 64.0, 8.5);
+            base.look.y = std::f32::consts::FRAC_PI_2;
+        }));
+
+        (world, entity_id)
+
+    }
+
+    #[test]
+    fn flint_and_steel_tnt() {
+
+        let (mut world, entity_id) = new_world();
+        let pos = IVec3::new(8, 63, 8);
+        world.set_block(pos, block::TNT, 0);
+
+        let mut stacks = [ItemStack::new_single(item::FLINT_AND_STEEL, 0)];
+        world.use_stack(&mut InventoryHandle::new(&mut stacks), 0, pos, Face::PosY, entity_id);
+        assert!(world.is_block(pos, block::AIR));
+
+        let tnt = world.iter_entities()
+            .find_map(|(_, entity)| match entity {
+                Entity(base, BaseKind::Tnt(tnt)) => Some((base.pos, base.vel.y, tnt.fuse_time)),
+                _ => None
+            });
+        assert_eq!(tnt, Some((pos.as_dvec3() + 0.5, 0.2, 80)));
+
+    }
+
+    #[test]
+    fn bucket() {
+
+        let (mut world, entity_id) = new_world();
+        let pos = IVec3::new(8, 64, 8);
+
+        let mut stacks = [ItemStack::new_single(item::WATER_BUCKET, 0)];
+        world.use_raw_stack(&mut InventoryHandle::new(&mut stacks), 0, entity_id);
+        assert_eq!(world.get_block(pos), Some((block::WATER_MOVING, 0)));
+        assert_eq!(stacks[0], ItemStack::new_single(item::BUCKET, 0));
+
+        world.use_raw_stack(&mut InventoryHandle::new(&mut stacks), 0, entity_id);
+        assert_eq!(world.get_block(pos), Some((block::AIR, 0)));
+        assert_eq!(stacks[0], ItemStack::new_single(item::WATER_BUCKET, 0));
+
+    }
+
+    #[test]
+    fn food() {
+
+        let (mut world, entity_id) = new_world();
+
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity_mut(entity_id) else { panic!() };
+        living.health = 10;
+
+        let mut stacks = [ItemStack::new_single(item::BREAD, 0), ItemStack::new_single(item::MUSHROOM_STEW, 0)];
+        world.use_raw_stack(&mut InventoryHandle::new(&mut stacks), 0, entity_id);
+        assert!(stacks[0].is_empty());
+
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(entity_id) else { panic!() };
+        assert_eq!(living.health, 15);
+
+        world.use_raw_stack(&mut InventoryHandle::new(&mut stacks), 1, entity_id);
+        assert_eq!(stacks[1], ItemStack::new_single(item::BOWL, 0));
+
+        let Some(Entity(_, BaseKind::Living(living, _))) = world.get_entity(entity_id) else { panic!() };
+        assert_eq!(living.health, 20);
+
+    }
+
+    #[test]
+    fn sign() {
+
+        let (mut world, entity_id) = new_world();
+
+        // Placing on the side of a block creates a wall sign facing away from it.
+        let wall_pos = IVec3::new(8, 66, 4);
+        world.set_block(wall_pos, block::STONE, 0);
+
+        let mut stacks = [ItemStack::new_single(item::SIGN, 0)];
+        world.use_stack(&mut InventoryHandle::new(&mut stacks), 0, wall_pos, Face::PosZ, entity_id);
+        assert!(stacks[0].is_empty());
+
+        let sign_pos = wall_pos + IVec3::Z;
+        let (id, metadata) = world.get_block(sign_pos).unwrap();
+        assert_eq!(id, block::WALL_SIGN);
+        assert_eq!(block::sign::get_wall_face(metadata), Some(Face::PosZ));
+        assert!(matches!(world.get_block_entity(sign_pos), Some(BlockEntity::Sign(_))));
+
+        // Cannot place a sign below a block.
+        let mut stacks = [ItemStack::new_single(item::SIGN, 0)];
+        world.use_stack(&mut InventoryHandle::new(&mut stacks), 0, wall_pos, Face::NegY, entity_id);
+        assert!(world.is_block(wall_pos - IVec3::Y, block::AIR));
+
+        // Placing on top of a block creates a sign post facing the player.
+        world.get_entity_mut(entity_id).unwrap().0.look.x = std::f32::consts::FRAC_PI_2;
+        let ground_pos = IVec3::new(10, 63, 10);
+        world.use_stack(&mut InventoryHandle::new(&mut stacks), 0, ground_pos, Face::PosY, entity_id);
+        assert!(stacks[0].is_empty());
+
+        let sign_pos = ground_pos + IVec3::Y;
+        assert_eq!(world.get_block(sign_pos), Some((block::SIGN, 12)));
+        assert!(matches!(world.get_block_entity(sign_pos), Some(BlockEntity::Sign(_))));
+
+    }
+
 }